@@ -37,7 +37,7 @@ where
 
 pub fn eval_lua(lua: &Lua, code: &str) -> Result<Value> {
 	let res = lua.load(code).eval::<mlua::Value>();
-	let res_lua_value = process_lua_eval_result(lua, res, code)?;
+	let res_lua_value = process_lua_eval_result(lua, res, code, None)?;
 	let serde_value = lua_value_to_serde_value(res_lua_value)?;
 	Ok(serde_value)
 }
@@ -18,6 +18,16 @@ pub async fn run_reflective_agent_with_runtime(
 	data_lua_code: &str,
 	input: Option<Value>,
 	runtime: Runtime,
+) -> Result<Value> {
+	run_reflective_agent_with_options(data_lua_code, input, runtime, &RunBaseOptions::default()).await
+}
+
+/// Same as `run_reflective_agent_with_runtime`, with the run base options (e.g., cassette, mock dir)
+pub async fn run_reflective_agent_with_options(
+	data_lua_code: &str,
+	input: Option<Value>,
+	runtime: Runtime,
+	run_base_options: &RunBaseOptions,
 ) -> Result<Value> {
 	// -- create the run and task for test
 	// TODO: Probably need to do an insert if not exist
@@ -36,8 +46,7 @@ pub async fn run_reflective_agent_with_runtime(
 	let agent = load_reflective_agent(data_lua_code)?;
 	let input = if let Some(input) = input { input } else { Value::Null };
 
-	let res =
-		run_command_agent_input_for_test(0, &runtime, &agent, Value::Null, input, &RunBaseOptions::default()).await?;
+	let res = run_command_agent_input_for_test(0, &runtime, &agent, Value::Null, input, run_base_options).await?;
 	let res = res.unwrap_or_default();
 	Ok(res)
 }
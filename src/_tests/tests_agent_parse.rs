@@ -1,5 +1,6 @@
 use crate::_test_support::assert_contains;
-use crate::agent::Agent;
+use crate::agent::{Agent, InputType};
+use crate::model::Stage;
 
 pub type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
 
@@ -87,3 +88,80 @@ return "hello"
 
 	Ok(())
 }
+
+#[tokio::test]
+async fn test_agent_parse_inputs_section() -> Result<()> {
+	// -- Setup & Fixtures
+	let content = r#"
+# Inputs
+
+The files to summarize.
+
+```toml
+description = "The file to summarize"
+
+[[fields]]
+name = "path"
+type = "string"
+required = true
+
+[[params]]
+name = "lang"
+default = "en"
+```
+
+# User
+
+Summarize
+"#;
+
+	// -- Exec
+	let agent = Agent::mock_from_content(content)?;
+
+	// -- Check
+	let schema = agent.inputs_schema().ok_or("Should have inputs schema")?;
+	assert_eq!(schema.input_type(), InputType::Object);
+	assert_eq!(schema.description.as_deref(), Some("The file to summarize"));
+	assert_eq!(schema.fields.len(), 1);
+	assert_eq!(schema.fields[0].name, "path");
+	assert!(schema.fields[0].required);
+	assert_eq!(schema.params[0].default, Some("en".into()));
+	assert_eq!(agent.prompt_parts().len(), 1);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_agent_parse_tools_section() -> Result<()> {
+	// -- Setup & Fixtures
+	let content = r#"
+# Tools
+
+```lua
+return {
+	{
+		name        = "get_weather",
+		description = "Get the weather of a city",
+		schema      = { type = "object", properties = { city = { type = "string" } } },
+		handler     = function(args) return "sunny" end
+	}
+}
+```
+
+# User
+
+What is the weather in Paris?
+"#;
+
+	// -- Exec
+	let agent = Agent::mock_from_content(content)?;
+
+	// -- Check
+	let tools_script = agent.tools_script().ok_or("Should have tools script")?;
+	assert_contains(tools_script, "name        = \"get_weather\"");
+	let location = agent.script_location(Stage::Ai).ok_or("Should have tools location")?;
+	assert_eq!(location.line, 5);
+	assert_eq!(agent.prompt_parts().len(), 1);
+
+	Ok(())
+}
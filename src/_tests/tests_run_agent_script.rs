@@ -567,3 +567,318 @@ return data
 
 	Ok(())
 }
+
+#[tokio::test]
+async fn test_run_agent_script_lua_error_agent_line() -> Result<()> {
+	// -- Setup & Fixtures
+	let data_lua_code = "local a = 1\nlocal b = nil\nreturn b.x";
+
+	// -- Exec
+	let err = crate::_test_support::run_reflective_agent(data_lua_code, None)
+		.await
+		.err()
+		.ok_or("Should have failed")?;
+
+	// -- Check
+	// The `# Data` code block starts at line 4 of the reflective agent file
+	assert_contains(&err.to_string(), "At mock-reflective-agent.aip:6 'return b.x'");
+
+	Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_agent_script_lua_busy_loop_task_timeout() -> Result<()> {
+	// -- Setup & Fixtures
+	let content = r#"
+# Options
+
+```toml
+task_timeout = 0.2
+```
+
+# Data
+
+```lua
+while true do end
+```
+"#;
+	let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+	let agent = Agent::mock_from_content(content)?;
+
+	// -- Exec
+	let res = run_agent(
+		&runtime,
+		None,
+		agent,
+		Some(vec!["one".into()]),
+		&RunBaseOptions::default(),
+		true,
+	)
+	.await;
+
+	// -- Check
+	let err = res.err().ok_or("Should have failed")?;
+	assert!(matches!(err, crate::Error::Timeout { what: "Task", .. }), "err: {err}");
+	let run = crate::model::RunBmc::list(runtime.mm(), None)?
+		.pop()
+		.ok_or("Should have one run")?;
+	let task = crate::model::TaskBmc::list_for_run(runtime.mm(), run.id)?
+		.pop()
+		.ok_or("Should have one task")?;
+	let err_id = task.end_err_id.ok_or("Task should have an end error")?;
+	let err_rec = crate::model::ErrBmc::get(runtime.mm(), err_id)?;
+	assert_eq!(err_rec.stage, Some(crate::model::Stage::Data));
+	assert_contains(err_rec.content.as_deref().unwrap_or_default(), "Task timeout");
+
+	Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_agent_script_response_schema_loaded_for_run() -> Result<()> {
+	// -- Setup & Fixtures
+	// The response schema is loaded with the run options (even if no task sends an instruction)
+	let content = r#"
+# Options
+
+```toml
+response_schema = "{ not json"
+```
+
+# Output
+
+```lua
+return "output"
+```
+"#;
+	let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+	let agent = Agent::mock_from_content(content)?;
+
+	// -- Exec
+	let res = run_agent(
+		&runtime,
+		None,
+		agent,
+		Some(vec!["one".into()]),
+		&RunBaseOptions::default(),
+		true,
+	)
+	.await;
+
+	// -- Check
+	let err = res.err().ok_or("Should fail")?;
+	assert!(
+		err.to_string().contains("Invalid inline response_schema JSON"),
+		"err: {err}"
+	);
+	let mm = runtime.mm();
+	let run = crate::model::RunBmc::list(mm, None)?.pop().ok_or("Should have one run")?;
+	let tasks = crate::model::TaskBmc::list_for_run(mm, run.id)?;
+	assert!(tasks.is_empty(), "No task should be created");
+
+	Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_agent_script_tools_not_loaded_without_instruction() -> Result<()> {
+	// -- Setup & Fixtures
+	// The tools are only loaded for an actual AI call (this tools script would fail the task)
+	let content = r#"
+# Tools
+
+```lua
+error("tools should not be loaded")
+```
+
+# Output
+
+```lua
+return "output - " .. input
+```
+"#;
+	let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+	let agent = Agent::mock_from_content(content)?;
+
+	// -- Exec
+	let res = run_agent(
+		&runtime,
+		None,
+		agent,
+		Some(vec!["one".into()]),
+		&RunBaseOptions::default(),
+		true,
+	)
+	.await?;
+
+	// -- Check
+	let outputs = res.outputs.ok_or("Should have outputs")?;
+	assert_eq!(outputs, vec![serde_json::json!("output - one")]);
+
+	Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_agent_script_resume_before_all_options() -> Result<()> {
+	// -- Setup & Fixtures
+	// The Before All overrides the model, which must be kept when resuming (the Before All is not executed again)
+	let content = r#"
+# Options
+
+```toml
+model = "agent-model"
+```
+
+# Before All
+
+```lua
+return aip.flow.before_all_response({ before_all = "ba", options = { model = "ba-model" } })
+```
+
+# Output
+
+```lua
+return before_all .. " - " .. options.model
+```
+"#;
+	let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+	let agent = Agent::mock_from_content(content)?;
+	let res = run_agent(
+		&runtime,
+		None,
+		agent.clone(),
+		Some(vec!["one".into()]),
+		&RunBaseOptions::default(),
+		true,
+	)
+	.await?;
+	let outputs = res.outputs.ok_or("Should have outputs")?;
+	assert_eq!(outputs, vec![serde_json::json!("ba - ba-model")]);
+	let mm = runtime.mm();
+	let run = crate::model::RunBmc::list(mm, None)?.pop().ok_or("Should have one run")?;
+	// NOTE: Mark the task as failed, so that it is executed again by the resume
+	let task = crate::model::TaskBmc::list_for_run(mm, run.id)?
+		.pop()
+		.ok_or("Should have one task")?;
+	let task_u = crate::model::TaskForUpdate {
+		end_state: Some(crate::model::EndState::Err),
+		..Default::default()
+	};
+	crate::model::TaskBmc::update(mm, task.id, task_u)?;
+	let resume = crate::run::RunResume::load(mm, &run)?;
+	let inputs = resume.inputs().to_vec();
+	let run_base_options = RunBaseOptions::default().with_resume(resume);
+
+	// -- Exec
+	let res = run_agent(&runtime, None, agent, Some(inputs), &run_base_options, true).await?;
+
+	// -- Check
+	let outputs = res.outputs.ok_or("Should have outputs")?;
+	assert_eq!(outputs, vec![serde_json::json!("ba - ba-model")]);
+
+	Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_agent_script_params_validated_for_run_and_replay() -> Result<()> {
+	// -- Setup & Fixtures
+	// The `--param` values are strings, converted and completed with the defaults of the `# Inputs` params
+	let content = r#"
+# Inputs
+
+```toml
+[[params]]
+name = "count"
+type = "integer"
+
+[[params]]
+name = "lang"
+default = "en"
+```
+
+# Output
+
+```lua
+return { count = CTX.PARAMS.count, lang = CTX.PARAMS.lang }
+```
+"#;
+	let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+	let agent = Agent::mock_from_content(content)?;
+	let mut raw_params = serde_json::Map::new();
+	raw_params.insert("count".into(), "3".into());
+	let run_base_options = RunBaseOptions::default().with_params(raw_params);
+	let expected = serde_json::json!({ "count": 3, "lang": "en" });
+
+	// -- Exec
+	let res = run_agent(
+		&runtime,
+		None,
+		agent.clone(),
+		Some(vec!["one".into()]),
+		&run_base_options,
+		true,
+	)
+	.await?;
+	let mm = runtime.mm();
+	let run = crate::model::RunBmc::list(mm, None)?.pop().ok_or("Should have one run")?;
+	let stored_params = crate::model::RunBmc::get_params(mm, &run)?;
+	// replay with the raw params (e.g., recorded before the validation)
+	let replay =
+		run_agent_task_replay(&runtime, agent, run.id, 0, Value::Null, "one".into(), &run_base_options).await?;
+
+	// -- Check
+	assert_eq!(res.outputs.ok_or("Should have outputs")?, vec![expected.clone()]);
+	assert_eq!(Value::Object(stored_params), expected);
+	assert_eq!(
+		replay.response.outputs.ok_or("Should have replay outputs")?,
+		vec![expected]
+	);
+
+	Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_run_agent_script_params_in_scripts_and_prompt() -> Result<()> {
+	// -- Setup & Fixtures
+	// `mock::echo` returns the rendered user prompt (no mock file in the mock dir)
+	let content = r#"
+# Options
+
+```toml
+model = "mock::echo"
+```
+
+# Data
+
+```lua
+return { ctx_name = CTX.PARAMS.name, global_name = params.name }
+```
+
+# User
+
+Hello {{params.name}}
+
+# Output
+
+```lua
+return { ctx_name = data.ctx_name, global_name = data.global_name, ai = ai_response.content:match("^%s*(.-)%s*$") }
+```
+"#;
+	let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+	let agent = Agent::mock_from_content(content)?;
+	let mut params = serde_json::Map::new();
+	params.insert("name".into(), "Jen".into());
+	let run_base_options = RunBaseOptions::default()
+		.with_params(params)
+		.with_mock_dir(crate::_test_support::gen_test_dir_path());
+
+	// -- Exec
+	let res = run_agent(&runtime, None, agent, Some(vec!["one".into()]), &run_base_options, true).await?;
+
+	// -- Check
+	let outputs = res.outputs.ok_or("Should have outputs")?;
+	assert_eq!(
+		outputs,
+		vec![serde_json::json!({ "ctx_name": "Jen", "global_name": "Jen", "ai": "Hello Jen" })]
+	);
+
+	Ok(())
+}
@@ -1,6 +1,10 @@
 use crate::agent::PromptPart;
+use crate::agent::agent_doc::AgentSectionLines;
+use crate::agent::agent_inputs_schema::InputsSchema;
 use crate::agent::agent_options::AgentOptions;
 use crate::agent::agent_ref::AgentRef;
+use crate::model::Stage;
+use crate::script::ScriptLocation;
 use crate::{Error, Result};
 use genai::ModelName;
 use genai::chat::ChatOptions;
@@ -90,6 +94,11 @@ impl Agent {
 			.unwrap_or_else(|| self.inner.agent_options.clone())
 	}
 
+	/// The options overridden after the agent load (e.g., by the Before All), if any
+	pub fn options_ov(&self) -> Option<&AgentOptions> {
+		self.agent_options_ov.as_deref()
+	}
+
 	pub fn options_as_ref(&self) -> &AgentOptions {
 		self.agent_options_ov
 			.as_ref()
@@ -145,6 +154,11 @@ impl Agent {
 		res
 	}
 
+	/// The `# Inputs` section, if declared
+	pub fn inputs_schema(&self) -> Option<&InputsSchema> {
+		self.inner.inputs_schema.as_deref()
+	}
+
 	pub fn before_all_script(&self) -> Option<&str> {
 		self.inner.before_all_script.as_deref()
 	}
@@ -164,6 +178,26 @@ impl Agent {
 	pub fn after_all_script(&self) -> Option<&str> {
 		self.inner.after_all_script.as_deref()
 	}
+
+	/// The `# Tools` script (returns the tool definitions called by the AI)
+	pub fn tools_script(&self) -> Option<&str> {
+		self.inner.tools_script.as_deref()
+	}
+
+	/// The location of the stage Lua code block in the agent file (for the Lua error lines)
+	pub fn script_location(&self, stage: Stage) -> Option<ScriptLocation> {
+		let lines = &self.inner.section_lines;
+		let line = match stage {
+			Stage::BeforeAll => lines.before_all,
+			Stage::Data => lines.data,
+			Stage::Output => lines.output,
+			Stage::AfterAll => lines.after_all,
+			// NOTE: The tool handlers are executed in the AI stage
+			Stage::Ai => lines.tools,
+			Stage::AiGen => None,
+		}?;
+		Some(ScriptLocation::new(self.file_path(), line))
+	}
 }
 
 /// Peekers
@@ -234,6 +268,9 @@ pub(super) struct AgentInner {
 	/// The model that came from the options
 	pub model_name: Option<ModelName>,
 
+	/// The `# Inputs` section
+	pub inputs_schema: Option<Arc<InputsSchema>>,
+
 	pub before_all_script: Option<String>,
 
 	/// Contains the instruction, system, assistant in order of the file
@@ -243,6 +280,12 @@ pub(super) struct AgentInner {
 	pub data_script: Option<String>,
 	pub output_script: Option<String>,
 	pub after_all_script: Option<String>,
+
+	/// The `# Tools` section script
+	pub tools_script: Option<String>,
+
+	/// The lines of the sections in the agent file
+	pub section_lines: AgentSectionLines,
 }
 
 // endregion: --- AgentInner
@@ -1,9 +1,10 @@
-use crate::Result;
+use crate::agent::agent_inputs_schema::InputsSchema;
 use crate::agent::agent_options::AgentOptions;
 use crate::agent::agent_ref::AgentRef;
 use crate::agent::{Agent, AgentInner, PartKind, PromptPart, get_prompt_part_kind, get_prompt_part_options_str};
 use crate::support::md::InBlockState;
 use crate::support::tomls::parse_toml_into_json;
+use crate::{Error, Result};
 use genai::ModelName;
 use simple_fs::{SPath, read_to_string};
 use std::path::Path;
@@ -15,6 +16,36 @@ pub struct AgentDoc {
 	raw_content: String,
 }
 
+// region:    --- AgentSections
+
+/// The raw sections of an agent file (see `AgentDoc::sections`)
+#[derive(Debug, Default)]
+pub struct AgentSections {
+	pub options_toml: Option<String>,
+	pub inputs_toml: Option<String>,
+	pub before_all_script: Option<String>,
+	pub data_script: Option<String>,
+	pub prompt_parts: Vec<PromptPart>,
+	pub output_script: Option<String>,
+	pub after_all_script: Option<String>,
+	pub tools_script: Option<String>,
+	pub lines: AgentSectionLines,
+}
+
+/// The 1-based line numbers, in the agent file, of the first content line of the section code blocks
+#[derive(Debug, Clone, Default)]
+pub struct AgentSectionLines {
+	pub options: Option<usize>,
+	pub inputs: Option<usize>,
+	pub before_all: Option<usize>,
+	pub data: Option<usize>,
+	pub output: Option<usize>,
+	pub after_all: Option<usize>,
+	pub tools: Option<usize>,
+}
+
+// endregion: --- AgentSections
+
 // region:    --- Capture State
 
 #[derive(Debug)]
@@ -30,6 +61,10 @@ enum CaptureMode {
 	OptionsSection,
 	OptionsTomlBlock,
 
+	// Below the # Inputs section
+	InputsSection,
+	InputsTomlBlock,
+
 	// Below the data heading (perhaps not in a code block)
 	DataSection,
 	// Inside the code block
@@ -46,6 +81,11 @@ enum CaptureMode {
 	AfterAllSection,
 	// Inside the code block
 	AfterAllCodeBlock,
+
+	// Below the tools heading (perhaps not in a code block)
+	ToolsSection,
+	// Inside the code block
+	ToolsCodeBlock,
 }
 
 impl CaptureMode {
@@ -58,10 +98,12 @@ impl CaptureMode {
 		matches!(
 			self,
 			CaptureMode::OptionsTomlBlock
+				| CaptureMode::InputsTomlBlock
 				| CaptureMode::BeforeAllCodeBlock
 				| CaptureMode::DataCodeBlock
 				| CaptureMode::OutputCodeBlock
 				| CaptureMode::AfterAllCodeBlock
+				| CaptureMode::ToolsCodeBlock
 		)
 	}
 
@@ -81,23 +123,33 @@ impl AgentDoc {
 		Ok(Self { spath, raw_content })
 	}
 
+	pub fn from_content(spath: impl AsRef<Path>, content: impl Into<String>) -> Result<Self> {
+		let spath = SPath::from_std_path(spath.as_ref())?;
+		let raw_content = content.into();
+		Ok(Self { spath, raw_content })
+	}
+
 	pub fn into_agent(self, name: &str, agent_ref: AgentRef, options: AgentOptions) -> Result<Agent> {
 		let agent_inner = self.into_agent_inner(name, agent_ref, options)?;
 		let agent = Agent::new(agent_inner)?;
 		Ok(agent)
 	}
 
-	/// Internal method to create the first part of the agent inner
+	/// Extract the raw sections of the agent file (without parsing/validating them),
+	/// with the line numbers of their content.
 	/// This is sort of a Lexer, but very customize to extracting the Agent parts
-	fn into_agent_inner(self, name: &str, agent_ref: AgentRef, agent_options: AgentOptions) -> Result<AgentInner> {
+	pub fn sections(&self) -> Result<AgentSections> {
 		let mut capture_mode = CaptureMode::None;
+		let mut lines = AgentSectionLines::default();
 
 		// -- The buffers
 		let mut options_toml: Vec<&str> = Vec::new();
+		let mut inputs_toml: Vec<&str> = Vec::new();
 		let mut before_all_script: Vec<&str> = Vec::new();
 		let mut data_script: Vec<&str> = Vec::new();
 		let mut output_script: Vec<&str> = Vec::new();
 		let mut after_all_script: Vec<&str> = Vec::new();
+		let mut tools_script: Vec<&str> = Vec::new();
 
 		let mut prompt_parts: Vec<PromptPart> = Vec::new();
 		// the vec String allow to be more efficient (as join later is more efficient)
@@ -110,7 +162,10 @@ impl AgentDoc {
 
 		let mut block_state = InBlockState::Out;
 
-		for line in self.raw_content.lines() {
+		for (line_idx, line) in self.raw_content.lines().enumerate() {
+			// The 1-based line number of the next line (e.g., first line of a code block content)
+			let next_line_no = line_idx + 2;
+
 			// Update block state regardless of capture mode
 			let old_block_state = block_state;
 			block_state = block_state.compute_new(line);
@@ -121,6 +176,8 @@ impl AgentDoc {
 				let header_lower = line[1..].trim().to_lowercase();
 				if header_lower == "options" {
 					capture_mode = CaptureMode::OptionsSection;
+				} else if header_lower == "inputs" {
+					capture_mode = CaptureMode::InputsSection;
 				} else if header_lower == "before all" {
 					capture_mode = CaptureMode::BeforeAllSection;
 				} else if header_lower == "data" {
@@ -129,6 +186,8 @@ impl AgentDoc {
 					capture_mode = CaptureMode::OutputSection;
 				} else if header_lower == "after all" {
 					capture_mode = CaptureMode::AfterAllSection;
+				} else if header_lower == "tools" {
+					capture_mode = CaptureMode::ToolsSection;
 				} else if let Some(part_kind) = get_prompt_part_kind(&header_lower) {
 					capture_mode = CaptureMode::PromptPart;
 					// TODO: will need to pass full case header in case we take string values in part options
@@ -136,7 +195,7 @@ impl AgentDoc {
 					// we finalize the previous part if present
 					finalize_current_prompt_part(&mut current_part, &mut prompt_parts);
 					// then, we create the new current_part
-					current_part = Some(CurrentPromptPart(part_kind, part_options_str, Vec::new()));
+					current_part = Some(CurrentPromptPart(part_kind, part_options_str, Vec::new(), next_line_no));
 				} else if capture_mode.is_prompt_part() && !block_state.is_out() {
 					// If we're in a prompt part and inside a code block,
 					// we should not process this as a section change
@@ -165,6 +224,7 @@ impl AgentDoc {
 				CaptureMode::OptionsSection => {
 					if (line.starts_with("```toml") || line.starts_with("````toml")) && old_block_state.is_out() {
 						capture_mode = CaptureMode::OptionsTomlBlock;
+						lines.options.get_or_insert(next_line_no);
 						continue;
 					}
 				}
@@ -177,10 +237,28 @@ impl AgentDoc {
 					}
 				}
 
+				// -- Inputs
+				CaptureMode::InputsSection => {
+					if (line.starts_with("```toml") || line.starts_with("````toml")) && old_block_state.is_out() {
+						capture_mode = CaptureMode::InputsTomlBlock;
+						lines.inputs.get_or_insert(next_line_no);
+						continue;
+					}
+				}
+				CaptureMode::InputsTomlBlock => {
+					if line.starts_with("```") && block_state.is_out() && !old_block_state.is_out() {
+						capture_mode = CaptureMode::None;
+						continue;
+					} else {
+						push_line(&mut inputs_toml, line);
+					}
+				}
+
 				// -- Before All
 				CaptureMode::BeforeAllSection => {
 					if (line.starts_with("```lua") || line.starts_with("````lua")) && old_block_state.is_out() {
 						capture_mode = CaptureMode::BeforeAllCodeBlock;
+						lines.before_all.get_or_insert(next_line_no);
 						continue;
 					}
 				}
@@ -197,6 +275,7 @@ impl AgentDoc {
 				CaptureMode::DataSection => {
 					if (line.starts_with("```lua") || line.starts_with("````lua")) && old_block_state.is_out() {
 						capture_mode = CaptureMode::DataCodeBlock;
+						lines.data.get_or_insert(next_line_no);
 						continue;
 					}
 				}
@@ -213,6 +292,7 @@ impl AgentDoc {
 				CaptureMode::OutputSection => {
 					if (line.starts_with("```lua") || line.starts_with("````lua")) && old_block_state.is_out() {
 						capture_mode = CaptureMode::OutputCodeBlock;
+						lines.output.get_or_insert(next_line_no);
 						continue;
 					}
 				}
@@ -229,6 +309,7 @@ impl AgentDoc {
 				CaptureMode::AfterAllSection => {
 					if (line.starts_with("```lua") || line.starts_with("````lua")) && old_block_state.is_out() {
 						capture_mode = CaptureMode::AfterAllCodeBlock;
+						lines.after_all.get_or_insert(next_line_no);
 						continue;
 					}
 				}
@@ -240,15 +321,55 @@ impl AgentDoc {
 						push_line(&mut after_all_script, line);
 					}
 				}
+
+				// -- Tools
+				CaptureMode::ToolsSection => {
+					if (line.starts_with("```lua") || line.starts_with("````lua")) && old_block_state.is_out() {
+						capture_mode = CaptureMode::ToolsCodeBlock;
+						lines.tools.get_or_insert(next_line_no);
+						continue;
+					}
+				}
+				CaptureMode::ToolsCodeBlock => {
+					if line.starts_with("```") && block_state.is_out() && !old_block_state.is_out() {
+						capture_mode = CaptureMode::None;
+						continue;
+					} else {
+						push_line(&mut tools_script, line);
+					}
+				}
 			}
 		}
 
 		// -- We finilize the last part if it was not closed
 		finalize_current_prompt_part(&mut current_part, &mut prompt_parts);
 
-		// -- Returning the data
+		Ok(AgentSections {
+			options_toml: buffer_to_string(options_toml),
+			inputs_toml: buffer_to_string(inputs_toml),
+			before_all_script: buffer_to_string(before_all_script),
+			data_script: buffer_to_string(data_script),
+			prompt_parts,
+			output_script: buffer_to_string(output_script),
+			after_all_script: buffer_to_string(after_all_script),
+			tools_script: buffer_to_string(tools_script),
+			lines,
+		})
+	}
 
-		let options_toml = buffer_to_string(options_toml);
+	/// Internal method to create the first part of the agent inner
+	fn into_agent_inner(self, name: &str, agent_ref: AgentRef, agent_options: AgentOptions) -> Result<AgentInner> {
+		let AgentSections {
+			options_toml,
+			inputs_toml,
+			before_all_script,
+			data_script,
+			prompt_parts,
+			output_script,
+			after_all_script,
+			tools_script,
+			lines: section_lines,
+		} = self.sections()?;
 
 		let agent_options_ov: Option<AgentOptions> = if let Some(options_toml) = options_toml {
 			Some(AgentOptions::from_options_value(parse_toml_into_json(&options_toml)?)?)
@@ -261,6 +382,20 @@ impl AgentDoc {
 			None => agent_options,
 		};
 
+		// -- Get the inputs schema
+		let inputs_schema = match inputs_toml {
+			Some(inputs_toml) => {
+				let schema = parse_toml_into_json(&inputs_toml)
+					.and_then(InputsSchema::from_value)
+					.map_err(|err| Error::AgentInputsSchemaInvalid {
+						agent_path: self.spath.to_string(),
+						cause: err.to_string(),
+					})?;
+				Some(Arc::new(schema))
+			}
+			None => None,
+		};
+
 		// -- Get the model name
 		let model_name = agent_options.model().map(ModelName::from);
 
@@ -276,34 +411,30 @@ impl AgentDoc {
 
 			model_name,
 
-			before_all_script: buffer_to_string(before_all_script),
-			data_script: buffer_to_string(data_script),
+			inputs_schema,
+
+			before_all_script,
+			data_script,
 
 			prompt_parts,
 
-			output_script: buffer_to_string(output_script),
-			after_all_script: buffer_to_string(after_all_script),
+			output_script,
+			after_all_script,
+
+			tools_script,
+
+			section_lines,
 		};
 
 		Ok(agent_inner)
 	}
 }
 
-/// Constructor for test
-#[cfg(test)]
-impl AgentDoc {
-	pub fn from_content(spath: impl AsRef<Path>, content: impl Into<String>) -> Result<Self> {
-		let spath = SPath::from_std_path(spath.as_ref())?;
-		let raw_content = content.into();
-		Ok(Self { spath, raw_content })
-	}
-}
-
 // region:    --- Support
 
-/// Type of the function below and the `into_agent_inner` lexer
-/// (PartKind, PartOptionsStr, Content)
-struct CurrentPromptPart<'a>(PartKind, Option<String>, Vec<&'a str>);
+/// Type of the function below and the `sections` lexer
+/// (PartKind, PartOptionsStr, Content, LineNo)
+struct CurrentPromptPart<'a>(PartKind, Option<String>, Vec<&'a str>, usize);
 
 /// Finalize a eventual current_part
 fn finalize_current_prompt_part(current_part: &mut Option<CurrentPromptPart<'_>>, prompt_parts: &mut Vec<PromptPart>) {
@@ -319,6 +450,7 @@ fn finalize_current_prompt_part(current_part: &mut Option<CurrentPromptPart<'_>>
 			kind,
 			options_str,
 			content,
+			line: current_part.3,
 		};
 		prompt_parts.push(part);
 	}
@@ -0,0 +1,308 @@
+//! The `# Inputs` section of an agent, declaring the inputs and params it expects.
+//!
+//! The section has a toml code block such as:
+//!
+//! ```toml
+//! description = "The files to summarize"
+//! type = "object"
+//!
+//! [[fields]]
+//! name = "path"
+//! type = "string"
+//! required = true
+//! description = "The file path"
+//!
+//! [[params]]
+//! name = "lang"
+//! default = "en"
+//! description = "The language of the summary"
+//! ```
+//!
+//! The inputs and params are validated (and defaults applied) before the `# Before All` stage.
+
+use crate::{Error, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+// region:    --- Types
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InputsSchema {
+	/// The type of each input (`object` when fields are declared, otherwise `any`)
+	#[serde(rename = "type")]
+	pub typ: Option<InputType>,
+
+	pub description: Option<String>,
+
+	/// The fields of the object inputs
+	#[serde(default)]
+	pub fields: Vec<FieldSpec>,
+
+	/// The run params (`--param key=value`)
+	#[serde(default)]
+	pub params: Vec<FieldSpec>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldSpec {
+	pub name: String,
+
+	#[serde(rename = "type")]
+	pub typ: Option<InputType>,
+
+	pub description: Option<String>,
+
+	#[serde(default)]
+	pub required: bool,
+
+	pub default: Option<Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, derive_more::Display)]
+#[serde(rename_all = "lowercase")]
+pub enum InputType {
+	#[display("string")]
+	String,
+	#[display("number")]
+	Number,
+	#[display("integer")]
+	Integer,
+	#[display("boolean")]
+	Boolean,
+	#[display("array")]
+	Array,
+	#[display("object")]
+	Object,
+	#[display("any")]
+	Any,
+}
+
+// endregion: --- Types
+
+/// Constructor
+impl InputsSchema {
+	/// Build from the `# Inputs` toml block value
+	pub fn from_value(value: Value) -> Result<Self> {
+		let schema = serde_json::from_value(value)?;
+		Ok(schema)
+	}
+}
+
+/// Validation
+impl InputsSchema {
+	/// The type of each input
+	pub fn input_type(&self) -> InputType {
+		match self.typ {
+			Some(typ) => typ,
+			None if !self.fields.is_empty() => InputType::Object,
+			None => InputType::Any,
+		}
+	}
+
+	/// Validate the inputs, and set the defaults of the missing fields.
+	pub fn validate_inputs(&self, inputs: Vec<Value>) -> Result<Vec<Value>> {
+		let input_type = self.input_type();
+
+		inputs
+			.into_iter()
+			.enumerate()
+			.map(|(idx, input)| {
+				if !input_type.matches(&input) {
+					return Err(Error::custom(format!(
+						"Input #{idx} must be of type '{input_type}' but was {}",
+						value_type_name(&input)
+					)));
+				}
+				match input {
+					Value::Object(obj) if !self.fields.is_empty() => {
+						let obj = validate_fields(&self.fields, obj, false)
+							.map_err(|err| Error::custom(format!("Input #{idx} {err}")))?;
+						Ok(Value::Object(obj))
+					}
+					input => Ok(input),
+				}
+			})
+			.collect()
+	}
+
+	/// Validate the params, and set the defaults of the missing ones.
+	///
+	/// NOTE: Since the `--param` values are strings, they are converted to the declared
+	///       `number`, `integer`, or `boolean` type.
+	pub fn validate_params(&self, params: Map<String, Value>) -> Result<Map<String, Value>> {
+		validate_fields(&self.params, params, true).map_err(|err| Error::custom(format!("Param {err}")))
+	}
+}
+
+impl InputType {
+	fn matches(&self, value: &Value) -> bool {
+		match self {
+			InputType::String => value.is_string(),
+			InputType::Number => value.is_number(),
+			InputType::Integer => value.is_i64() || value.is_u64(),
+			InputType::Boolean => value.is_boolean(),
+			InputType::Array => value.is_array(),
+			InputType::Object => value.is_object(),
+			InputType::Any => true,
+		}
+	}
+
+	/// Convert a string value to this type (for the `--param` values)
+	fn coerce_str(&self, value: &str) -> Option<Value> {
+		match self {
+			InputType::Number => value
+				.parse::<f64>()
+				.ok()
+				.and_then(|n| serde_json::Number::from_f64(n).map(Value::Number)),
+			InputType::Integer => value.parse::<i64>().ok().map(Value::from),
+			InputType::Boolean => value.parse::<bool>().ok().map(Value::Bool),
+			_ => None,
+		}
+	}
+}
+
+// region:    --- Support
+
+/// Validate the fields of an object, setting the defaults of the missing fields.
+/// The unknown properties are kept as is.
+fn validate_fields(specs: &[FieldSpec], mut obj: Map<String, Value>, coerce: bool) -> Result<Map<String, Value>> {
+	for spec in specs {
+		let name = spec.name.as_str();
+		match obj.get(name) {
+			None | Some(Value::Null) => {
+				if let Some(default) = &spec.default {
+					obj.insert(name.to_string(), default.clone());
+				} else if spec.required {
+					return Err(Error::custom(format!("'{name}' is required")));
+				}
+			}
+			Some(value) => {
+				let Some(typ) = spec.typ else {
+					continue;
+				};
+				if typ.matches(value) {
+					continue;
+				}
+				let coerced = match value {
+					Value::String(s) if coerce => typ.coerce_str(s),
+					_ => None,
+				};
+				match coerced {
+					Some(coerced) => {
+						obj.insert(name.to_string(), coerced);
+					}
+					None => {
+						return Err(Error::custom(format!(
+							"'{name}' must be of type '{typ}' but was {}",
+							value_type_name(value)
+						)));
+					}
+				}
+			}
+		}
+	}
+
+	Ok(obj)
+}
+
+fn value_type_name(value: &Value) -> &'static str {
+	match value {
+		Value::Null => "null",
+		Value::Bool(_) => "a boolean",
+		Value::Number(_) => "a number",
+		Value::String(_) => "a string",
+		Value::Array(_) => "an array",
+		Value::Object(_) => "an object",
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::assert_contains;
+	use crate::support::tomls::parse_toml_into_json;
+	use serde_json::json;
+
+	const SCHEMA_TOML: &str = r#"
+description = "The files"
+
+[[fields]]
+name = "path"
+type = "string"
+required = true
+
+[[fields]]
+name = "mode"
+default = "short"
+
+[[params]]
+name = "lang"
+default = "en"
+
+[[params]]
+name = "max"
+type = "integer"
+"#;
+
+	#[test]
+	fn test_agent_inputs_schema_validate_inputs_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let schema = InputsSchema::from_value(parse_toml_into_json(SCHEMA_TOML)?)?;
+		let inputs = vec![json!({"path": "a.md"}), json!({"path": "b.md", "mode": "long", "other": 1})];
+
+		// -- Exec
+		let inputs = schema.validate_inputs(inputs)?;
+
+		// -- Check
+		assert_eq!(schema.input_type(), InputType::Object);
+		assert_eq!(inputs[0], json!({"path": "a.md", "mode": "short"}));
+		assert_eq!(inputs[1], json!({"path": "b.md", "mode": "long", "other": 1}));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_agent_inputs_schema_validate_inputs_invalid() -> Result<()> {
+		// -- Setup & Fixtures
+		let schema = InputsSchema::from_value(parse_toml_into_json(SCHEMA_TOML)?)?;
+
+		// -- Exec & Check
+		let err = schema
+			.validate_inputs(vec![json!({"mode": "long"})])
+			.err()
+			.ok_or("Should fail")?;
+		assert_contains(&err.to_string(), "Input #0 'path' is required");
+		let err = schema.validate_inputs(vec![json!({"path": 12})]).err().ok_or("Should fail")?;
+		assert_contains(&err.to_string(), "'path' must be of type 'string' but was a number");
+		let err = schema.validate_inputs(vec![json!("a.md")]).err().ok_or("Should fail")?;
+		assert_contains(&err.to_string(), "Input #0 must be of type 'object' but was a string");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_agent_inputs_schema_validate_params_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let schema = InputsSchema::from_value(parse_toml_into_json(SCHEMA_TOML)?)?;
+		let mut params = Map::new();
+		params.insert("max".to_string(), json!("3"));
+
+		// -- Exec
+		let params = schema.validate_params(params)?;
+
+		// -- Check
+		assert_eq!(Value::Object(params), json!({"lang": "en", "max": 3}));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
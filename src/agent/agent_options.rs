@@ -1,4 +1,5 @@
 use crate::Result;
+use crate::script::{lua_value_to_serde_value, serde_value_to_lua_value};
 use genai::chat::ChatOptions;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -12,8 +13,8 @@ use value_ext::JsonValueExt;
 /// Note: The values are flattened for simplicity but may be nested in the future.
 #[derive(Debug, Clone, Deserialize, Default, Serialize)]
 pub struct AgentOptions {
-	// The raw model name of the configuration
-	model: Option<String>,
+	// The raw model name of the configuration (or the ordered list of models to fall back on)
+	model: Option<ModelChain>,
 
 	temperature: Option<f64>,
 
@@ -24,6 +25,33 @@ pub struct AgentOptions {
 
 	allow_run_on_task_fail: Option<bool>,
 
+	/// Serve the identical AI requests from the `.aipack/.cache/ai-responses/` disk cache
+	cache_responses: Option<bool>,
+
+	/// The maximum cost (in USD) of a run, no new tasks are started once reached
+	max_cost_usd: Option<f64>,
+
+	/// Number of retries of a failed AI call (for the `retry_on` errors)
+	retry_max: Option<u32>,
+
+	/// The backoff delay of the first retry (doubled for each next retry)
+	retry_backoff_ms: Option<u64>,
+
+	/// The AI call errors to retry (default all)
+	retry_on: Option<Vec<RetryOn>>,
+
+	/// The timeout (in seconds) of a task (Data, AI, and Output stages)
+	task_timeout: Option<f64>,
+
+	/// The timeout (in seconds) of a run (from the Before All to the After All stages)
+	run_timeout: Option<f64>,
+
+	/// The AI call errors which fall back on the next model of the `model` list (default all)
+	fallback_on: Option<Vec<FallbackOn>>,
+
+	/// The JSON schema of the AI response (structured output), inline or a schema file path
+	response_schema: Option<ResponseSchema>,
+
 	model_aliases: Option<ModelAliases>,
 }
 
@@ -45,14 +73,71 @@ impl AgentOptions {
 	}
 }
 
+// region:    --- ModelChain
+
+/// A model name, or an ordered list of model names to fall back on (see the `fallback_on` option)
+/// e.g., `model = "opus"` or `model = ["opus", "sonnet", "gpro"]`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ModelChain {
+	One(String),
+	List(Vec<String>),
+}
+
+impl ModelChain {
+	/// The first model (None if empty list)
+	pub fn first(&self) -> Option<&str> {
+		self.iter().next()
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &str> {
+		let models: &[String] = match self {
+			ModelChain::One(model) => std::slice::from_ref(model),
+			ModelChain::List(models) => models,
+		};
+		models.iter().map(|m| m.as_str())
+	}
+}
+
+impl mlua::FromLua for ModelChain {
+	fn from_lua(value: mlua::Value, _lua: &mlua::Lua) -> mlua::Result<Self> {
+		match value {
+			mlua::Value::String(model) => Ok(ModelChain::One(model.to_string_lossy())),
+			mlua::Value::Table(models) => {
+				let models = models
+					.sequence_values::<String>()
+					.collect::<mlua::Result<Vec<_>>>()
+					.map_err(|err| {
+						mlua::Error::runtime(format!("model list values should be strings.\n    Cause: {err}"))
+					})?;
+				Ok(ModelChain::List(models))
+			}
+			other => Err(mlua::Error::runtime(format!(
+				"model should be a string or a list of strings, but was {other:?}"
+			))),
+		}
+	}
+}
+
+impl mlua::IntoLua for &ModelChain {
+	fn into_lua(self, lua: &mlua::Lua) -> mlua::Result<mlua::Value> {
+		match self {
+			ModelChain::One(model) => model.as_str().into_lua(lua),
+			ModelChain::List(models) => models.as_slice().into_lua(lua),
+		}
+	}
+}
+
+// endregion: --- ModelChain
+
 // region:    --- ModelAliases
 
 /// TODO Must have a Arc<inner> for perf
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ModelAliases {
-	/// The `{name: model_name}` hashmap
+	/// The `{name: model_name}` hashmap (a model name can be a list of models to fall back on)
 	#[serde(flatten)]
-	inner: HashMap<String, String>,
+	inner: HashMap<String, ModelChain>,
 }
 
 impl ModelAliases {
@@ -66,7 +151,7 @@ impl ModelAliases {
 	}
 
 	pub fn merge_new(&self, aliases_ov: Option<ModelAliases>) -> ModelAliases {
-		let mut inner: HashMap<String, String> = self.inner.clone();
+		let mut inner: HashMap<String, ModelChain> = self.inner.clone();
 		if let Some(aliases) = aliases_ov {
 			for (k, v) in aliases.inner {
 				inner.insert(k, v);
@@ -77,14 +162,14 @@ impl ModelAliases {
 }
 
 impl mlua::FromLua for ModelAliases {
-	fn from_lua(value: mlua::Value, _lua: &mlua::Lua) -> mlua::Result<Self> {
+	fn from_lua(value: mlua::Value, lua: &mlua::Lua) -> mlua::Result<Self> {
 		match value {
 			mlua::Value::Table(aliases_table) => {
 				let mut aliases = HashMap::new();
-				for pair in aliases_table.pairs::<String, String>() {
-					let (k, v) = pair.map_err(|err| {
+				for pair in aliases_table.pairs::<String, mlua::Value>() {
+					let (k, v) = pair.and_then(|(k, v)| Ok((k, ModelChain::from_lua(v, lua)?))).map_err(|err| {
 						mlua::Error::runtime(format!(
-							"model_aliases value type is invalid. Should be string.\n    Cause: {err}"
+							"model_aliases value type is invalid. Should be string or list of strings.\n    Cause: {err}"
 						))
 					})?; // TODO: need to return informative error
 					aliases.insert(k, v);
@@ -102,7 +187,7 @@ impl mlua::IntoLua for &ModelAliases {
 	fn into_lua(self, lua: &mlua::Lua) -> mlua::Result<mlua::Value> {
 		let table = lua.create_table()?;
 		for (k, v) in self.inner.iter() {
-			table.set(k.as_str(), v.as_str())?;
+			table.set(k.as_str(), v)?;
 		}
 		Ok(mlua::Value::Table(table))
 	}
@@ -110,42 +195,237 @@ impl mlua::IntoLua for &ModelAliases {
 
 // endregion: --- ModelAliases
 
+// region:    --- ResponseSchema
+
+/// The `response_schema` option, the JSON schema of the AI response (structured output)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ResponseSchema {
+	/// The path of a `.json` (or `.toml`) schema file, relative to the agent file directory
+	/// (or an inline JSON string starting with `{`)
+	Path(String),
+	/// The inline schema (TOML table, JSON object, or Lua table)
+	Inline(Value),
+}
+
+impl mlua::FromLua for ResponseSchema {
+	fn from_lua(value: mlua::Value, _lua: &mlua::Lua) -> mlua::Result<Self> {
+		match value {
+			mlua::Value::String(path) => Ok(ResponseSchema::Path(path.to_string_lossy())),
+			mlua::Value::Table(_) => Ok(ResponseSchema::Inline(lua_value_to_serde_value(value)?)),
+			other => Err(mlua::Error::runtime(format!(
+				"response_schema should be a table (the schema) or a string (the schema file path), but was {other:?}"
+			))),
+		}
+	}
+}
+
+impl mlua::IntoLua for &ResponseSchema {
+	fn into_lua(self, lua: &mlua::Lua) -> mlua::Result<mlua::Value> {
+		match self {
+			ResponseSchema::Path(path) => path.as_str().into_lua(lua),
+			ResponseSchema::Inline(schema) => Ok(serde_value_to_lua_value(lua, schema.clone())?),
+		}
+	}
+}
+
+// endregion: --- ResponseSchema
+
+// region:    --- RetryOn
+
+/// The classes of AI call errors which can be retried (see the `retry_on` option)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryOn {
+	/// HTTP 429 Too Many Requests
+	RateLimit,
+	/// HTTP 5xx
+	Server,
+	/// Connection errors and timeouts (no HTTP status)
+	Network,
+}
+
+impl RetryOn {
+	pub const ALL: [RetryOn; 3] = [RetryOn::RateLimit, RetryOn::Server, RetryOn::Network];
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			RetryOn::RateLimit => "rate_limit",
+			RetryOn::Server => "server",
+			RetryOn::Network => "network",
+		}
+	}
+}
+
+impl std::fmt::Display for RetryOn {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl mlua::FromLua for RetryOn {
+	fn from_lua(value: mlua::Value, _lua: &mlua::Lua) -> mlua::Result<Self> {
+		let name = match &value {
+			mlua::Value::String(name) => name.to_string_lossy(),
+			other => {
+				return Err(mlua::Error::runtime(format!(
+					"retry_on value must be a string, but was {other:?}"
+				)));
+			}
+		};
+		RetryOn::ALL.into_iter().find(|r| r.as_str() == name).ok_or_else(|| {
+			mlua::Error::runtime(format!(
+				"retry_on value '{name}' is invalid. Should be one of 'rate_limit', 'server', 'network'"
+			))
+		})
+	}
+}
+
+impl mlua::IntoLua for RetryOn {
+	fn into_lua(self, lua: &mlua::Lua) -> mlua::Result<mlua::Value> {
+		lua.create_string(self.as_str()).map(mlua::Value::String)
+	}
+}
+
+// endregion: --- RetryOn
+
+// region:    --- FallbackOn
+
+/// The classes of AI call errors which fall back on the next model (see the `fallback_on` option)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FallbackOn {
+	/// HTTP 429 Too Many Requests (after the retries)
+	RateLimit,
+	/// HTTP 5xx (after the retries)
+	Server,
+	/// Connection errors and timeouts (after the retries)
+	Network,
+	/// HTTP 404, typically a deprecated or unknown model
+	Model,
+	/// HTTP 401 & 403, or missing API key
+	Auth,
+}
+
+impl FallbackOn {
+	pub const ALL: [FallbackOn; 5] = [
+		FallbackOn::RateLimit,
+		FallbackOn::Server,
+		FallbackOn::Network,
+		FallbackOn::Model,
+		FallbackOn::Auth,
+	];
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			FallbackOn::RateLimit => "rate_limit",
+			FallbackOn::Server => "server",
+			FallbackOn::Network => "network",
+			FallbackOn::Model => "model",
+			FallbackOn::Auth => "auth",
+		}
+	}
+}
+
+impl From<RetryOn> for FallbackOn {
+	fn from(retry_on: RetryOn) -> Self {
+		match retry_on {
+			RetryOn::RateLimit => FallbackOn::RateLimit,
+			RetryOn::Server => FallbackOn::Server,
+			RetryOn::Network => FallbackOn::Network,
+		}
+	}
+}
+
+impl std::fmt::Display for FallbackOn {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl mlua::FromLua for FallbackOn {
+	fn from_lua(value: mlua::Value, _lua: &mlua::Lua) -> mlua::Result<Self> {
+		let name = match &value {
+			mlua::Value::String(name) => name.to_string_lossy(),
+			other => {
+				return Err(mlua::Error::runtime(format!(
+					"fallback_on value must be a string, but was {other:?}"
+				)));
+			}
+		};
+		FallbackOn::ALL.into_iter().find(|r| r.as_str() == name).ok_or_else(|| {
+			mlua::Error::runtime(format!(
+				"fallback_on value '{name}' is invalid. Should be one of 'rate_limit', 'server', 'network', 'model', 'auth'"
+			))
+		})
+	}
+}
+
+impl mlua::IntoLua for FallbackOn {
+	fn into_lua(self, lua: &mlua::Lua) -> mlua::Result<mlua::Value> {
+		lua.create_string(self.as_str()).map(mlua::Value::String)
+	}
+}
+
+// endregion: --- FallbackOn
+
 // Getters
 impl AgentOptions {
 	/// Returns the raw model name from this options given in the config/options
 	/// (This name is not resolved with the model aliases)
+	///
+	/// NOTE: When `model` is a list, this is the first model of the list.
 	pub fn model(&self) -> Option<&str> {
-		self.model.as_deref()
+		self.model.as_ref().and_then(|model| model.first())
 	}
 
-	/// Returns the resolved model
+	/// Returns the resolved model (the first model of the resolved model chain)
 	pub fn resolve_model(&self) -> Option<Cow<'_, str>> {
-		let model = self.model.as_deref()?;
+		let model = self.model()?;
 
 		// 1) If there are no aliases, just return the original model.
 		let Some(_) = self.model_aliases.as_ref() else {
 			return Some(Cow::Borrowed(model));
 		};
 
-		// 2) Direct alias hit
-		if let Some(resolved) = self.get_model_for_alias(model) {
-			return Some(Cow::Borrowed(resolved));
+		self.resolve_model_chain().into_iter().next().map(Cow::Owned)
+	}
+
+	/// Returns the resolved models, in fallback order (without duplicates).
+	/// Each model of the `model` list is resolved with the model aliases (an alias can be a list as well).
+	pub fn resolve_model_chain(&self) -> Vec<String> {
+		let Some(model) = self.model.as_ref() else {
+			return Vec::new();
+		};
+
+		let mut chain: Vec<String> = Vec::new();
+		for model in model.iter() {
+			for resolved in self.resolve_model_alias(model) {
+				if !chain.contains(&resolved) {
+					chain.push(resolved);
+				}
+			}
 		}
+		chain
+	}
 
-		// 3) Try aliasing the base and re-attach a reasoning suffix
+	/// Resolve one model name with the model aliases
+	fn resolve_model_alias(&self, model: &str) -> Vec<String> {
+		// 1) Direct alias hit
+		if let Some(resolved) = self.get_model_chain_for_alias(model) {
+			return resolved.iter().map(|m| m.to_string()).collect();
+		}
+
+		// 2) Try aliasing the base and re-attach a reasoning suffix
 		let (base, suffix_opt) = extract_reasoning_suffix(model);
 		if let Some(suffix) = suffix_opt
-			&& let Some(resolved_base) = self.get_model_for_alias(base)
+			&& let Some(resolved_base) = self.get_model_chain_for_alias(base)
 		{
-			// Need an owned string because we're concatenating.
-			let mut owned = String::with_capacity(resolved_base.len() + suffix.len());
-			owned.push_str(resolved_base);
-			owned.push_str(suffix);
-			return Some(Cow::Owned(owned));
+			return resolved_base.iter().map(|m| format!("{m}{suffix}")).collect();
 		}
 
 		// 3) Fall back to the original model
-		Some(Cow::Borrowed(model))
+		vec![model.to_string()]
 	}
 
 	pub fn input_concurrency(&self) -> Option<usize> {
@@ -156,6 +436,42 @@ impl AgentOptions {
 		self.allow_run_on_task_fail
 	}
 
+	pub fn cache_responses(&self) -> Option<bool> {
+		self.cache_responses
+	}
+
+	pub fn max_cost_usd(&self) -> Option<f64> {
+		self.max_cost_usd
+	}
+
+	pub fn retry_max(&self) -> Option<u32> {
+		self.retry_max
+	}
+
+	pub fn retry_backoff_ms(&self) -> Option<u64> {
+		self.retry_backoff_ms
+	}
+
+	pub fn retry_on(&self) -> Option<&[RetryOn]> {
+		self.retry_on.as_deref()
+	}
+
+	pub fn task_timeout(&self) -> Option<f64> {
+		self.task_timeout
+	}
+
+	pub fn run_timeout(&self) -> Option<f64> {
+		self.run_timeout
+	}
+
+	pub fn fallback_on(&self) -> Option<&[FallbackOn]> {
+		self.fallback_on.as_deref()
+	}
+
+	pub fn response_schema(&self) -> Option<&ResponseSchema> {
+		self.response_schema.as_ref()
+	}
+
 	pub fn temperature(&self) -> Option<f64> {
 		self.temperature
 	}
@@ -166,9 +482,11 @@ impl AgentOptions {
 
 	#[allow(unused)]
 	fn get_model_for_alias(&self, alias: &str) -> Option<&str> {
-		self.model_aliases
-			.as_ref()
-			.and_then(|aliases| aliases.inner.get(alias).map(|s| s.as_str()))
+		self.get_model_chain_for_alias(alias).and_then(|chain| chain.first())
+	}
+
+	fn get_model_chain_for_alias(&self, alias: &str) -> Option<&ModelChain> {
+		self.model_aliases.as_ref().and_then(|aliases| aliases.inner.get(alias))
 	}
 }
 
@@ -195,7 +513,7 @@ impl AgentOptions {
 	/// TODO: Needs to have another function, from_options_value for when in the new `# Options` section which will follow the section format)
 	pub fn from_config_value(value: Value) -> Result<AgentOptions> {
 		let options = match Self::from_current_config(value)? {
-			OptionsParsing::Parsed(agent_options) => agent_options,
+			OptionsParsing::Parsed(agent_options) => *agent_options,
 			OptionsParsing::Unparsed(_) => AgentOptions::default(),
 		};
 
@@ -225,6 +543,15 @@ impl AgentOptions {
 			top_p: options_ov.top_p.or(self.top_p),
 			input_concurrency: options_ov.input_concurrency.or(self.input_concurrency),
 			allow_run_on_task_fail: options_ov.allow_run_on_task_fail.or(self.allow_run_on_task_fail),
+			cache_responses: options_ov.cache_responses.or(self.cache_responses),
+			max_cost_usd: options_ov.max_cost_usd.or(self.max_cost_usd),
+			retry_max: options_ov.retry_max.or(self.retry_max),
+			retry_backoff_ms: options_ov.retry_backoff_ms.or(self.retry_backoff_ms),
+			retry_on: options_ov.retry_on.or(self.retry_on),
+			task_timeout: options_ov.task_timeout.or(self.task_timeout),
+			run_timeout: options_ov.run_timeout.or(self.run_timeout),
+			fallback_on: options_ov.fallback_on.or(self.fallback_on),
+			response_schema: options_ov.response_schema.or(self.response_schema),
 			model_aliases,
 		})
 	}
@@ -241,6 +568,15 @@ impl AgentOptions {
 			top_p: options_ov.top_p.or(self.top_p),
 			input_concurrency: options_ov.input_concurrency.or(self.input_concurrency),
 			allow_run_on_task_fail: options_ov.allow_run_on_task_fail.or(self.allow_run_on_task_fail),
+			cache_responses: options_ov.cache_responses.or(self.cache_responses),
+			max_cost_usd: options_ov.max_cost_usd.or(self.max_cost_usd),
+			retry_max: options_ov.retry_max.or(self.retry_max),
+			retry_backoff_ms: options_ov.retry_backoff_ms.or(self.retry_backoff_ms),
+			retry_on: options_ov.retry_on.or(self.retry_on.clone()),
+			task_timeout: options_ov.task_timeout.or(self.task_timeout),
+			run_timeout: options_ov.run_timeout.or(self.run_timeout),
+			fallback_on: options_ov.fallback_on.or(self.fallback_on.clone()),
+			response_schema: options_ov.response_schema.or(self.response_schema.clone()),
 			model_aliases,
 		})
 	}
@@ -251,12 +587,21 @@ impl AgentOptions {
 impl mlua::IntoLua for &AgentOptions {
 	fn into_lua(self, lua: &mlua::Lua) -> mlua::Result<mlua::Value> {
 		let table = lua.create_table()?;
-		table.set("model", self.model())?;
+		table.set("model", self.model.as_ref())?;
 		table.set("resolved_model", self.resolve_model())?;
 		table.set("temperature", self.temperature)?;
 		table.set("top_p", self.top_p)?;
 		table.set("input_concurrency", self.input_concurrency)?;
 		table.set("allow_run_on_task_fail", self.allow_run_on_task_fail)?;
+		table.set("cache_responses", self.cache_responses)?;
+		table.set("max_cost_usd", self.max_cost_usd)?;
+		table.set("retry_max", self.retry_max)?;
+		table.set("retry_backoff_ms", self.retry_backoff_ms)?;
+		table.set("retry_on", self.retry_on.clone())?;
+		table.set("task_timeout", self.task_timeout)?;
+		table.set("run_timeout", self.run_timeout)?;
+		table.set("fallback_on", self.fallback_on.clone())?;
+		table.set("response_schema", self.response_schema.as_ref())?;
 
 		let model_aliases = self.model_aliases.as_ref();
 		table.set("model_aliases", model_aliases)?;
@@ -268,11 +613,20 @@ impl mlua::IntoLua for &AgentOptions {
 impl mlua::FromLua for AgentOptions {
 	fn from_lua(value: mlua::Value, lua: &mlua::Lua) -> mlua::Result<Self> {
 		if let mlua::Value::Table(table) = value {
-			let model = table.get::<Option<String>>("model")?;
+			let model = table.get::<Option<ModelChain>>("model")?;
 			let temperature = table.get::<Option<f64>>("temperature")?;
 			let top_p = table.get::<Option<f64>>("top_p")?;
 			let input_concurrency = table.get::<Option<usize>>("input_concurrency")?;
 			let allow_run_on_task_fail = table.get::<Option<bool>>("allow_run_on_task_fail")?;
+			let cache_responses = table.get::<Option<bool>>("cache_responses")?;
+			let max_cost_usd = table.get::<Option<f64>>("max_cost_usd")?;
+			let retry_max = table.get::<Option<u32>>("retry_max")?;
+			let retry_backoff_ms = table.get::<Option<u64>>("retry_backoff_ms")?;
+			let retry_on = table.get::<Option<Vec<RetryOn>>>("retry_on")?;
+			let task_timeout = table.get::<Option<f64>>("task_timeout")?;
+			let run_timeout = table.get::<Option<f64>>("run_timeout")?;
+			let fallback_on = table.get::<Option<Vec<FallbackOn>>>("fallback_on")?;
+			let response_schema = table.get::<Option<ResponseSchema>>("response_schema")?;
 
 			// --
 			let model_aliases = table.get::<Option<mlua::Value>>("model_aliases")?;
@@ -284,6 +638,15 @@ impl mlua::FromLua for AgentOptions {
 				top_p,
 				input_concurrency,
 				allow_run_on_task_fail,
+				cache_responses,
+				max_cost_usd,
+				retry_max,
+				retry_backoff_ms,
+				retry_on,
+				task_timeout,
+				run_timeout,
+				fallback_on,
+				response_schema,
 				model_aliases,
 			};
 
@@ -298,7 +661,7 @@ impl mlua::FromLua for AgentOptions {
 // region:    --- Parsing
 
 enum OptionsParsing {
-	Parsed(AgentOptions),
+	Parsed(Box<AgentOptions>),
 	#[allow(unused)]
 	Unparsed(Value),
 }
@@ -332,7 +695,7 @@ impl AgentOptions {
 
 		let options = Self::from_options_value(config_value)?;
 
-		Ok(OptionsParsing::Parsed(options))
+		Ok(OptionsParsing::Parsed(Box::new(options)))
 	}
 }
 
@@ -344,11 +707,20 @@ impl AgentOptions {
 	/// Creates a new `AgentOptions` with the specified model name. (for test)
 	pub fn new(model_name: impl Into<String>) -> Self {
 		AgentOptions {
-			model: Some(model_name.into()),
+			model: Some(ModelChain::One(model_name.into())),
 			temperature: None,
 			top_p: None,
 			input_concurrency: None,
 			allow_run_on_task_fail: None,
+			cache_responses: None,
+			max_cost_usd: None,
+			retry_max: None,
+			retry_backoff_ms: None,
+			retry_on: None,
+			task_timeout: None,
+			run_timeout: None,
+			fallback_on: None,
+			response_schema: None,
 			model_aliases: None,
 		}
 	}
@@ -386,6 +758,35 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_options_model_chain_resolve() -> Result<()> {
+		// -- Setup & Fixtures
+		let options = parse_toml_into_json(
+			r#"
+	model = ["opus-high", "sonnet", "gpro"]
+	model_aliases = { opus = "claude-opus-4-5", sonnet = ["claude-sonnet-4-5", "gpt-5"], gpro = "gemini-2.5-pro" }
+	fallback_on = ["server", "model"]
+		"#,
+		)?;
+
+		// -- Exec
+		let options = AgentOptions::from_options_value(options)?;
+
+		// -- Check
+		assert_eq!(options.model(), Some("opus-high"));
+		assert_eq!(options.resolve_model().as_deref(), Some("claude-opus-4-5-high"));
+		assert_eq!(
+			options.resolve_model_chain(),
+			["claude-opus-4-5-high", "claude-sonnet-4-5", "gpt-5", "gemini-2.5-pro"]
+		);
+		assert_eq!(
+			options.fallback_on(),
+			Some(&[FallbackOn::Server, FallbackOn::Model][..])
+		);
+
+		Ok(())
+	}
+
 	#[test]
 	fn test_options_lua_from() -> Result<()> {
 		// -- Setup & Fixtures
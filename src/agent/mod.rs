@@ -2,6 +2,7 @@
 
 mod agent_common;
 mod agent_doc;
+mod agent_inputs_schema;
 mod agent_locator;
 mod agent_options;
 mod agent_ref;
@@ -9,6 +10,7 @@ mod prompt_part;
 
 pub use agent_common::*;
 pub use agent_doc::*;
+pub use agent_inputs_schema::*;
 pub use agent_locator::*;
 pub use agent_options::*;
 pub use agent_ref::*;
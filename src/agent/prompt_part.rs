@@ -8,6 +8,8 @@ pub struct PromptPart {
 	pub kind: PartKind,
 	pub content: String,
 	pub options_str: Option<String>,
+	/// The 1-based line number of the first content line in the agent file
+	pub line: usize,
 }
 
 #[derive(Debug, Clone)]
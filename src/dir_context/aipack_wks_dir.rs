@@ -1,5 +1,7 @@
 use crate::Result;
-use crate::dir_context::path_consts::{AIPACK_DIR_NAME, CONFIG_FILE_NAME, PACK_CUSTOM};
+use crate::dir_context::path_consts::{
+	AI_RESPONSES_CACHE_DIR, AIPACK_DIR_NAME, CONFIG_FILE_NAME, COST_LEDGER_FILE, PACK_CUSTOM, RT_DB_FILE,
+};
 use simple_fs::SPath;
 use std::ops::Deref;
 
@@ -41,6 +43,21 @@ impl AipackWksDir {
 		let dir = self.join(PACK_CUSTOM);
 		Ok(dir)
 	}
+
+	pub fn get_rt_db_path(&self) -> Result<SPath> {
+		let path = self.join(RT_DB_FILE);
+		Ok(path)
+	}
+
+	pub fn get_ai_responses_cache_dir(&self) -> Result<SPath> {
+		let dir = self.join(AI_RESPONSES_CACHE_DIR);
+		Ok(dir)
+	}
+
+	pub fn get_cost_ledger_path(&self) -> Result<SPath> {
+		let path = self.join(COST_LEDGER_FILE);
+		Ok(path)
+	}
 	// endregion: --- Path Getters
 }
 
@@ -7,6 +7,7 @@ mod dir_context_impl;
 mod pack_dir;
 mod path_consts;
 mod path_resolvers;
+mod runtime_config;
 
 pub use aipack_base_dir::*;
 pub use aipack_paths::*;
@@ -15,5 +16,6 @@ pub use dir_context_impl::*;
 pub use pack_dir::*;
 pub use path_consts::*;
 pub use path_resolvers::*; // Export path constants
+pub use runtime_config::*;
 
 // endregion: --- Modules
@@ -14,6 +14,15 @@ pub const CONFIG_FILE_NAME: &str = "config.toml";
 pub const CONFIG_BASE_DEFAULT_FILE_NAME: &str = "config-default.toml";
 pub const CONFIG_BASE_USER_FILE_NAME: &str = "config-user.toml";
 
+/// The persisted runtime db (when `[runtime] persist_db = true`), relative to `.aipack/`
+pub const RT_DB_FILE: &str = ".session/rt-db.sqlite";
+
+/// The AI responses cache (when the `cache_responses` option is true), relative to `.aipack/`
+pub const AI_RESPONSES_CACHE_DIR: &str = ".cache/ai-responses";
+
+/// The workspace daily costs (when a `[budget]` is configured), relative to `.aipack/`
+pub const COST_LEDGER_FILE: &str = ".session/cost-ledger.json";
+
 // -- Common Path (for .aipack/ and ~/.aipack-base/)
 
 // TODO: probably need to add a common lua, or perhaps allow `require("jc@utils/lua/somefile")`
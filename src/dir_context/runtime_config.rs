@@ -0,0 +1,252 @@
+//! The `[runtime]`, `[budget]`, and `[rate_limits]` sections of the config tomls
+//!
+//! Unlike `[options]` (which are agent options), those are read once at the start of `aip`.
+//! They follow the same merge order, `config-default.toml`, `config-user.toml`, then the workspace `.aipack/config.toml`.
+
+use crate::dir_context::AipackPaths;
+use crate::support::files::current_dir;
+use crate::support::tomls::parse_toml_into_json;
+use crate::{Error, Result};
+use genai::adapter::AdapterKind;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use simple_fs::{SPath, read_to_string};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RuntimeConfig {
+	/// When true, the runtime db is persisted in `.aipack/.session/rt-db.sqlite`
+	persist_db: Option<bool>,
+}
+
+/// Constructors
+impl RuntimeConfig {
+	/// Load and merge the `[runtime]` section of the config files that exist.
+	pub fn load(aipack_paths: &AipackPaths) -> Result<Self> {
+		let items: Vec<RuntimeConfig> = load_config_sections(aipack_paths, "runtime")?;
+		Ok(items.into_iter().fold(RuntimeConfig::default(), RuntimeConfig::merge))
+	}
+
+	fn merge(self, config_ov: RuntimeConfig) -> RuntimeConfig {
+		RuntimeConfig {
+			persist_db: config_ov.persist_db.or(self.persist_db),
+		}
+	}
+}
+
+/// Getters
+impl RuntimeConfig {
+	pub fn persist_db(&self) -> bool {
+		self.persist_db.unwrap_or(false)
+	}
+}
+
+// region:    --- BudgetConfig
+
+/// The `[budget]` section, the workspace cost budgets (in USD) across all runs
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BudgetConfig {
+	daily_usd: Option<f64>,
+	monthly_usd: Option<f64>,
+}
+
+/// Constructors
+impl BudgetConfig {
+	/// Load and merge the `[budget]` section of the config files that exist.
+	pub fn load(aipack_paths: &AipackPaths) -> Result<Self> {
+		let items: Vec<BudgetConfig> = load_config_sections(aipack_paths, "budget")?;
+		Ok(items.into_iter().fold(BudgetConfig::default(), BudgetConfig::merge))
+	}
+
+	#[cfg(test)]
+	pub fn new(daily_usd: Option<f64>, monthly_usd: Option<f64>) -> Self {
+		Self { daily_usd, monthly_usd }
+	}
+
+	fn merge(self, config_ov: BudgetConfig) -> BudgetConfig {
+		BudgetConfig {
+			daily_usd: config_ov.daily_usd.or(self.daily_usd),
+			monthly_usd: config_ov.monthly_usd.or(self.monthly_usd),
+		}
+	}
+}
+
+/// Getters
+impl BudgetConfig {
+	pub fn daily_usd(&self) -> Option<f64> {
+		self.daily_usd
+	}
+
+	pub fn monthly_usd(&self) -> Option<f64> {
+		self.monthly_usd
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.daily_usd.is_none() && self.monthly_usd.is_none()
+	}
+}
+
+// endregion: --- BudgetConfig
+
+// region:    --- RateLimitsConfig
+
+/// The `[rate_limits]` section, the provider rate limits per adapter kind (e.g., `[rate_limits.openai]`),
+/// shared by all the runs and sub-agents of an `aip` session.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RateLimitsConfig {
+	#[serde(flatten, deserialize_with = "deserialize_adapter_limits")]
+	adapters: HashMap<&'static str, RateLimitConfig>,
+}
+
+/// The rate limits of one adapter kind
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct RateLimitConfig {
+	requests_per_min: Option<u32>,
+	tokens_per_min: Option<u64>,
+}
+
+/// Constructors
+impl RateLimitsConfig {
+	/// Load and merge the `[rate_limits]` section of the config files that exist.
+	pub fn load(aipack_paths: &AipackPaths) -> Result<Self> {
+		let items: Vec<RateLimitsConfig> = load_config_sections(aipack_paths, "rate_limits")?;
+		Ok(items.into_iter().fold(RateLimitsConfig::default(), RateLimitsConfig::merge))
+	}
+
+	#[cfg(test)]
+	pub fn new(adapters: impl IntoIterator<Item = (&'static str, RateLimitConfig)>) -> Self {
+		Self {
+			adapters: adapters.into_iter().collect(),
+		}
+	}
+
+	/// NOTE: Merged per adapter kind, and per limit.
+	fn merge(mut self, config_ov: RateLimitsConfig) -> RateLimitsConfig {
+		for (adapter, limit_ov) in config_ov.adapters {
+			let limit = self.adapters.remove(adapter).unwrap_or_default();
+			self.adapters.insert(adapter, limit.merge(limit_ov));
+		}
+		self
+	}
+}
+
+/// Getters
+impl RateLimitsConfig {
+	/// The rate limits of the adapter kind (by its lower name, e.g., `openai`), if any
+	pub fn get(&self, adapter: &str) -> Option<RateLimitConfig> {
+		self.adapters.get(adapter).copied()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.adapters.is_empty()
+	}
+}
+
+impl RateLimitConfig {
+	#[cfg(test)]
+	pub fn new(requests_per_min: Option<u32>, tokens_per_min: Option<u64>) -> Self {
+		Self {
+			requests_per_min,
+			tokens_per_min,
+		}
+	}
+
+	fn merge(self, config_ov: RateLimitConfig) -> RateLimitConfig {
+		RateLimitConfig {
+			requests_per_min: config_ov.requests_per_min.or(self.requests_per_min),
+			tokens_per_min: config_ov.tokens_per_min.or(self.tokens_per_min),
+		}
+	}
+
+	pub fn requests_per_min(&self) -> Option<u32> {
+		self.requests_per_min
+	}
+
+	pub fn tokens_per_min(&self) -> Option<u64> {
+		self.tokens_per_min
+	}
+}
+
+/// The keys must be the genai adapter kind lower names (e.g., `openai`, `anthropic`, `gemini`, `ollama`)
+fn deserialize_adapter_limits<'de, D>(
+	deserializer: D,
+) -> core::result::Result<HashMap<&'static str, RateLimitConfig>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	let limits = HashMap::<String, RateLimitConfig>::deserialize(deserializer)?;
+	limits
+		.into_iter()
+		.map(|(name, limit)| match AdapterKind::from_lower_str(&name) {
+			Some(adapter_kind) => Ok((adapter_kind.as_lower_str(), limit)),
+			None => Err(serde::de::Error::custom(format!(
+				"Unknown adapter kind '{name}' (e.g., openai, anthropic, gemini, ollama)"
+			))),
+		})
+		.collect()
+}
+
+// endregion: --- RateLimitsConfig
+
+/// Returns the `[section]` of each config file that exists, in merge order.
+fn load_config_sections<T: DeserializeOwned>(aipack_paths: &AipackPaths, section: &str) -> Result<Vec<T>> {
+	let mut items = Vec::new();
+
+	for config_path in aipack_paths.get_wks_config_toml_paths()? {
+		// NOTE: `config-default.toml` might not be there yet (before the first init base)
+		if !config_path.exists() {
+			continue;
+		}
+		let content = read_to_string(&config_path)?;
+		let value = parse_toml_into_json(&content).map_err(|err| Error::Config {
+			path: config_path.to_string(),
+			reason: err.to_string(),
+		})?;
+		let Some(section_value) = value.get(section) else {
+			continue;
+		};
+		let item: T = serde_json::from_value(section_value.clone()).map_err(|err| Error::Config {
+			path: config_path.to_string(),
+			reason: format!("Invalid [{section}] section. {err}"),
+		})?;
+		items.push(item);
+	}
+
+	Ok(items)
+}
+
+/// Returns the runtime db file path if `[runtime] persist_db = true` and the workspace `.aipack/` exists.
+///
+/// NOTE: This does not create anything. The file will be created by the `ModelManager`.
+pub fn find_rt_db_file() -> Result<Option<SPath>> {
+	let aipack_paths = current_dir_aipack_paths()?;
+	let Some(aipack_wks_dir) = aipack_paths.aipack_wks_dir().filter(|dir| dir.exists()) else {
+		return Ok(None);
+	};
+
+	let config = RuntimeConfig::load(&aipack_paths)?;
+	if config.persist_db() {
+		Ok(Some(aipack_wks_dir.get_rt_db_path()?))
+	} else {
+		Ok(None)
+	}
+}
+
+/// Returns the workspace runtime db file if it exists, regardless of the `persist_db` setting
+/// (it might have been persisted by previous sessions).
+pub fn find_existing_rt_db_file() -> Result<Option<SPath>> {
+	let aipack_paths = current_dir_aipack_paths()?;
+	let Some(aipack_wks_dir) = aipack_paths.aipack_wks_dir() else {
+		return Ok(None);
+	};
+	let db_file = aipack_wks_dir.get_rt_db_path()?;
+
+	Ok(db_file.exists().then_some(db_file))
+}
+
+/// Same rule as `init_wks`, only the `.aipack/` of the current dir is a workspace for a run,
+/// so the runtime db is the one of the current dir (not of an ancestor).
+fn current_dir_aipack_paths() -> Result<AipackPaths> {
+	let current_dir = current_dir()?;
+	AipackPaths::from_wks_dir(&current_dir)
+}
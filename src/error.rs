@@ -20,6 +20,38 @@ pub enum Error {
 	ModelMissing {
 		agent_path: String,
 	},
+	#[display("Agent '{agent_path}' has an invalid # Inputs section.\nCause: {cause}")]
+	AgentInputsSchemaInvalid {
+		agent_path: String,
+		cause: String,
+	},
+	#[display(
+		"Invalid inputs for agent '{agent_name}'.\nCause: {cause}\n(run with `--help-agent` to see the agent inputs and params)"
+	)]
+	AgentInputInvalid {
+		agent_name: String,
+		cause: String,
+	},
+
+	// -- Timeout
+	#[display("{what} timeout of {timeout} exceeded.\n(see the `{option}` agent option)")]
+	Timeout {
+		what: &'static str,
+		timeout: String,
+		option: &'static str,
+	},
+
+	// -- Response Schema
+	#[display("AI response does not match the agent `response_schema`.\nCause: {cause}")]
+	AiResponseSchema {
+		cause: String,
+	},
+
+	// -- Budget
+	#[display("Cost budget exceeded, no new tasks started.\nCause: {reason}")]
+	BudgetExceeded {
+		reason: String,
+	},
 
 	// -- Config
 	#[display("Config invalid (config path: {path})\n  reason: {reason}")]
@@ -106,6 +138,33 @@ pub enum Error {
 	DataFailWrongReturn {
 		cause: String,
 	},
+	#[display("Tools Lua block did not return a valid list of tools.\nCause: {cause}")]
+	ToolsFailWrongReturn {
+		cause: String,
+	},
+
+	// -- Run History
+	#[display(
+		"No run history for this workspace.\nSet `persist_db = true` in the `[runtime]` section of `.aipack/config.toml` to persist the runs."
+	)]
+	RunHistoryNotFound,
+	#[display("Run '{run_ref}' not found in the run history.")]
+	RunNotFound {
+		run_ref: String,
+	},
+	#[display("Task #{task_idx} not found in run '{run_ref}'.")]
+	RunTaskNotFound {
+		run_ref: String,
+		task_idx: i64,
+	},
+	#[display("Run {run_id} has no tasks to resume.")]
+	RunNothingToResume {
+		run_id: i64,
+	},
+	#[display("Run '{run_ref}' has no agent path, cannot replay it.")]
+	RunNoAgentPath {
+		run_ref: String,
+	},
 
 	// -- Genai
 	#[display("Environment API KEY missing: {env_name}")]
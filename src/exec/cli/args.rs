@@ -1,5 +1,5 @@
 use crate::exec::ExecActionEvent;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
 
 /// Simple program to greet a person
@@ -43,9 +43,14 @@ pub enum CliCommand {
 	)]
 	Run(RunArgs),
 
-	/// Create a new agent from a built-in template
-	/// Disabled for now
-	//New(NewArgs),
+	/// Create a new agent (or pack with `--pack`) from a template, e.g., `aip new agents/summarize`
+	New(NewArgs),
+
+	/// Run the agent tests of a pack (`tests/` dir), e.g., `aip test ns@pack` or `aip test path/to/pack`
+	Test(TestArgs),
+
+	/// Check (lint) the agent files without running them, e.g., `aip check path/to/agent.aip` or `aip check ns@pack`
+	Check(CheckArgs),
 
 	/// List the available aipacks `aip run list` or `aip run list demo@`
 	List(ListArgs),
@@ -59,6 +64,15 @@ pub enum CliCommand {
 	/// Unpack a repo pack into the workspace custom pack area
 	Unpack(UnpackArgs),
 
+	/// List the past runs of the workspace (persisted with `[runtime] persist_db = true`)
+	History(HistoryArgs),
+
+	/// Show the tasks, inputs/outputs, errors, and pins of a past run, e.g., `aip show 12`
+	Show(ShowArgs),
+
+	/// Re-execute one task of a past run with its recorded input, e.g., `aip replay 12 --task 3`
+	Replay(ReplayArgs),
+
 	/// Check available API keys in the environment
 	#[command(name = "check-keys", about = "Check available API keys in the environment")]
 	CheckKeys(CheckKeysArgs),
@@ -79,30 +93,45 @@ impl CliCommand {
 	/// For now, for all Run, the interactive is on by default, regardless if it watch.
 	pub fn is_interactive(&self) -> bool {
 		match self {
-			CliCommand::Run(run_args) => !run_args.single_shot,
+			CliCommand::Run(run_args) => !run_args.single_shot && !run_args.is_ndjson() && !run_args.help_agent,
 			CliCommand::Init(_) => false,
 			CliCommand::InitBase => false,
-			//CliCommand::New(_) => true,
+			CliCommand::New(_) => false,
+			CliCommand::Test(_) => false,
+			CliCommand::Check(_) => false,
 			CliCommand::List(_) => false,
 			CliCommand::Pack(_) => false,
 			CliCommand::Install(_) => false,
 			CliCommand::Unpack(_) => false,
+			CliCommand::History(_) => false,
+			CliCommand::Show(_) => false,
+			CliCommand::Replay(_) => false,
 			CliCommand::CheckKeys(_) => false,       // Non-interactive
 			CliCommand::CreateGitignore(_) => false, // Non-interactive
 			CliCommand::Xelf(_) => false,            // Non-interactive
 		}
 	}
 
+	/// Returns true if the events should be written as NDJSON on stdout (`aip run --output ndjson`)
+	pub fn is_ndjson(&self) -> bool {
+		matches!(self, CliCommand::Run(run_args) if run_args.is_ndjson())
+	}
+
 	pub fn is_tui(&self) -> bool {
 		match self {
 			CliCommand::Run(run_args) => run_args.is_tui(),
 			CliCommand::Init(_) => false,
 			CliCommand::InitBase => false,
-			//CliCommand::New(_) => false,
+			CliCommand::New(_) => false,
+			CliCommand::Test(_) => false,
+			CliCommand::Check(_) => false,
 			CliCommand::List(_) => false,
 			CliCommand::Pack(_) => false,
 			CliCommand::Install(_) => false,
 			CliCommand::Unpack(_) => false,
+			CliCommand::History(_) => false,
+			CliCommand::Show(_) => false,
+			CliCommand::Replay(_) => false,
 			CliCommand::CheckKeys(_) => false,       // Non-interactive
 			CliCommand::CreateGitignore(_) => false, // Non-interactive
 			CliCommand::Xelf(_) => false,            // Non-interactive
@@ -132,6 +161,17 @@ pub struct RunArgs {
 	#[arg(short = 'f', long = "on-files")]
 	pub on_files: Option<Vec<String>>,
 
+	/// Optional file with one input per record (`.ndjson`/`.jsonl` line, `.json` array item, or `.csv` row).
+	/// CSV rows are objects keyed by the header names.
+	/// NOTE: CANNOT be combined with -i/--input or -f/--on-files
+	#[arg(long = "inputs-file")]
+	pub inputs_file: Option<String>,
+
+	/// Optional run parameters, allowing multiple, e.g., `--param lang=fr --param out_dir=docs/`.
+	/// Available in all stages as `CTX.PARAMS` (or `params`) in Lua, and `params` in the prompt templates.
+	#[arg(long = "param", value_name = "KEY=VALUE")]
+	pub params: Option<Vec<String>>,
+
 	/// Optional watch flag
 	#[arg(short = 'w', long = "watch")]
 	pub watch: bool,
@@ -148,11 +188,38 @@ pub struct RunArgs {
 	#[arg(long = "dry", value_parser = ["req", "res"])]
 	pub dry_mode: Option<String>,
 
+	/// Resume a canceled or failed run (run id or uid, see `aip history`).
+	/// The tasks which ended Ok are not executed again, and their outputs are reused for the After All.
+	/// NOTE: CANNOT be combined with -i/--input, -f/--on-files, or --inputs-file
+	#[arg(long = "resume")]
+	pub resume: Option<String>,
+
+	/// Record every AI request/response of the run in this cassette directory
+	/// (one `<request_hash>.json` file per AI call).
+	/// NOTE: CANNOT be combined with --replay
+	#[arg(long = "record", value_name = "CASSETTE_DIR", conflicts_with = "replay")]
+	pub record: Option<String>,
+
+	/// Serve the AI responses from this cassette directory (see `--record`), without any provider call.
+	/// The run fails on a request that was not recorded.
+	#[arg(long = "replay", value_name = "CASSETTE_DIR")]
+	pub replay: Option<String>,
+
 	/// Single Shot execution (e.g., non-interactive).
 	/// (Was the `--ni` or `--non-interactive` in v0.6.x)
 	#[arg(short = 's', long = "single-shot", alias = "ni")]
 	pub single_shot: bool,
 
+	/// Print the agent inputs and params (from its `# Inputs` section) without running it.
+	#[arg(long = "help-agent")]
+	pub help_agent: bool,
+
+	/// Output format, `text` (default) or `ndjson`.
+	/// With `ndjson`, the run/task steps, prints, errors, costs, and outputs are written
+	/// as one JSON object per line on stdout (implies single shot).
+	#[arg(long = "output", value_enum, default_value_t = RunOutput::Text)]
+	pub output: RunOutput,
+
 	/// Not used in v0.8.x as tui is now the default
 	#[arg(long = "xp-tui")]
 	xp_tui: bool,
@@ -167,6 +234,18 @@ impl RunArgs {
 		// self.xp_tui // for 0.7.x
 		!self.old_term // for 0.8.x
 	}
+
+	pub fn is_ndjson(&self) -> bool {
+		matches!(self.output, RunOutput::Ndjson)
+	}
+}
+
+/// The output format of `aip run`
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunOutput {
+	#[default]
+	Text,
+	Ndjson,
 }
 /// Arguments for the `pack` subcommand
 #[derive(Parser, Debug)]
@@ -223,18 +302,88 @@ pub struct ListArgs {
 	pub open: bool,
 }
 
-/// DISABLED FOR NOW
 /// Arguments for the `new` subcommand
 #[derive(Parser, Debug)]
 pub struct NewArgs {
-	pub agent_path: Option<String>,
+	/// The path of the agent file to create (e.g., `agents/summarize` or `agents/summarize.aip`),
+	/// or the pack directory with `--pack`
+	pub path: String,
 
-	/// Open the .aipack file, and the target file if exists.
+	/// The template, either a built-in one (default `hello-world`),
+	/// or a template directory of an installed pack (e.g., `ns@pack/templates/summarize`)
+	#[arg(short = 't', long = "template")]
+	pub template: Option<String>,
+
+	/// Create a full pack (`pack.toml` and the template files) in the `path` directory
+	#[arg(long = "pack")]
+	pub pack: bool,
+
+	/// Overwrite the existing files
+	#[arg(long = "force")]
+	pub force: bool,
+
+	/// Open the created agent file
 	/// Note: For now assume vscode `code ...` is installed
 	#[arg(short = 'o', long = "open")]
 	pub open: bool,
 }
 
+/// Arguments for the `test` subcommand
+#[derive(Parser, Debug)]
+pub struct TestArgs {
+	/// The pack to test, either a pack ref (e.g., `ns@pack`) or a pack directory (e.g., `.` or `path/to/pack`)
+	pub target: String,
+
+	/// Only run the test cases whose name contains this string
+	#[arg(short = 'c', long = "case")]
+	pub case: Option<String>,
+}
+
+/// Arguments for the `check` subcommand
+#[derive(Parser, Debug)]
+pub struct CheckArgs {
+	/// The agent file (e.g., `path/to/agent.aip`), or the pack to check (e.g., `ns@pack` or `path/to/pack`)
+	pub target: String,
+}
+
+/// Arguments for the `history` subcommand
+#[derive(Parser, Debug)]
+pub struct HistoryArgs {
+	/// The max number of runs to list (most recent first)
+	#[arg(short = 'n', long = "limit", default_value_t = 20)]
+	pub limit: i64,
+}
+
+/// Arguments for the `show` subcommand
+#[derive(Parser, Debug)]
+pub struct ShowArgs {
+	/// The run id (as listed by `aip history`) or the run uid
+	pub run_ref: String,
+
+	/// Show the full input/output contents (truncated by default)
+	#[arg(long = "full")]
+	pub full: bool,
+}
+
+/// Arguments for the `replay` subcommand
+#[derive(Parser, Debug)]
+pub struct ReplayArgs {
+	/// The run id (as listed by `aip history`) or the run uid
+	pub run_ref: String,
+
+	/// The index of the task to replay (as shown by `aip show <run>`)
+	#[arg(short = 't', long = "task")]
+	pub task_idx: i64,
+
+	/// Verbose mode
+	#[arg(short = 'v', long = "verbose")]
+	pub verbose: bool,
+
+	/// Dry mode, takes either 'req' or 'res'
+	#[arg(long = "dry", value_parser = ["req", "res"])]
+	pub dry_mode: Option<String>,
+}
+
 /// Arguments for the `init` subcommand
 #[derive(Parser, Debug)]
 pub struct InitArgs {
@@ -292,12 +441,16 @@ impl From<CliCommand> for ExecActionEvent {
 			CliCommand::Init(init_args) => ExecActionEvent::CmdInit(init_args),
 			CliCommand::InitBase => ExecActionEvent::CmdInitBase,
 			CliCommand::Run(run_args) => ExecActionEvent::Run(run_args),
-			// CliCommand::New(new_args) => ExecActionEvent::CmdNew(new_args),
-			// CliCommand::New(new_args) => ExecCommand::NewCommandAgent(new_args),
+			CliCommand::New(new_args) => ExecActionEvent::CmdNew(new_args),
+			CliCommand::Test(test_args) => ExecActionEvent::CmdTest(test_args),
+			CliCommand::Check(check_args) => ExecActionEvent::CmdCheck(check_args),
 			CliCommand::List(list_args) => ExecActionEvent::CmdList(list_args),
 			CliCommand::Pack(pack_args) => ExecActionEvent::CmdPack(pack_args),
 			CliCommand::Install(install_args) => ExecActionEvent::CmdInstall(install_args),
 			CliCommand::Unpack(unpack_args) => ExecActionEvent::CmdUnpack(unpack_args),
+			CliCommand::History(history_args) => ExecActionEvent::CmdHistory(history_args),
+			CliCommand::Show(show_args) => ExecActionEvent::CmdShow(show_args),
+			CliCommand::Replay(replay_args) => ExecActionEvent::CmdReplay(replay_args),
 			CliCommand::CheckKeys(args) => ExecActionEvent::CmdCheckKeys(args),
 			CliCommand::CreateGitignore(args) => ExecActionEvent::CmdCreateGitignore(args),
 			CliCommand::Xelf(xelf_args) => {
@@ -3,8 +3,8 @@
 //!       but this will eventual change to have it's own
 
 use crate::exec::cli::{
-	CheckKeysArgs, CreateGitignoreArgs, InitArgs, InstallArgs, ListArgs, NewArgs, PackArgs, RunArgs, UnpackArgs,
-	XelfSetupArgs, XelfUpdateArgs,
+	CheckArgs, CheckKeysArgs, CreateGitignoreArgs, HistoryArgs, InitArgs, InstallArgs, ListArgs, NewArgs, PackArgs,
+	ReplayArgs, RunArgs, ShowArgs, TestArgs, UnpackArgs, XelfSetupArgs, XelfUpdateArgs,
 };
 use crate::model::Id;
 use crate::run::RunSubAgentParams;
@@ -33,6 +33,12 @@ pub enum ExecActionEvent {
 	CmdPack(PackArgs),
 	CmdInstall(InstallArgs),
 	CmdUnpack(UnpackArgs),
+	/// List the past runs (`aip history`)
+	CmdHistory(HistoryArgs),
+	/// Show one past run (`aip show <run>`)
+	CmdShow(ShowArgs),
+	/// Replay one task of a past run (`aip replay <run> --task <idx>`)
+	CmdReplay(ReplayArgs),
 	/// Check for API keys in the environment
 	CmdCheckKeys(CheckKeysArgs),
 	/// Create a .gitignore file from template
@@ -51,8 +57,9 @@ pub enum ExecActionEvent {
 	/// When press r
 	Redo,
 	/// When called from
-	#[from]
-	RunSubAgent(RunSubAgentParams),
+	/// NOTE: Boxed, as the params (with the agent options) are much larger than the other variants
+	#[from(forward)]
+	RunSubAgent(Box<RunSubAgentParams>),
 
 	CancelRun,
 
@@ -62,6 +69,14 @@ pub enum ExecActionEvent {
 
 	// -- New Agent
 	CmdNew(NewArgs),
+
+	// -- Agent Tests
+	/// Run the agent tests of a pack (`aip test <pack>`)
+	CmdTest(TestArgs),
+
+	// -- Agent Check
+	/// Lint the agent files (`aip check <agent|pack>`)
+	CmdCheck(CheckArgs),
 }
 
 impl ExecActionEvent {
@@ -0,0 +1,322 @@
+use crate::agent::{AgentDoc, AgentOptions, AgentSections, InputsSchema, PartKind, parse_prompt_part_options};
+use crate::exec::cli::CheckArgs;
+use crate::exec::support::resolve_pack_dir;
+use crate::hub::get_hub;
+use crate::runtime::Runtime;
+use crate::support::tomls::parse_toml_into_json;
+use crate::{Error, Result};
+use simple_fs::{SPath, list_files, read_to_string};
+
+/// Exec for the Check command
+///
+/// Lints the `.aip` files of an agent file or a pack (without running them),
+/// and reports the problems with their file and line numbers.
+pub async fn exec_check(args: CheckArgs, runtime: Runtime) -> Result<()> {
+	let hub = get_hub();
+
+	// -- List the agent files
+	let target_path = SPath::new(&args.target);
+	let agent_files = if target_path.is_file() {
+		vec![target_path]
+	} else {
+		let pack_dir = resolve_pack_dir(runtime.dir_context(), &args.target)?;
+		let mut files = list_files(&pack_dir, Some(&["**/*.aip"]), None)?;
+		files.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+		files
+	};
+	if agent_files.is_empty() {
+		return Err(Error::custom(format!("No .aip files found for '{}'", args.target)));
+	}
+
+	// -- Check the agent files
+	let mut problem_count = 0;
+	for agent_file in agent_files.iter() {
+		let problems = check_agent_file(agent_file);
+		for problem in problems.iter() {
+			hub.publish(format!("{agent_file}:{problem}")).await;
+		}
+		problem_count += problems.len();
+	}
+
+	// -- Report
+	hub.publish(format!(
+		"\n==== Check result: {} file(s) checked; {problem_count} problem(s)",
+		agent_files.len()
+	))
+	.await;
+
+	if problem_count > 0 {
+		return Err(Error::custom(format!(
+			"Found {problem_count} problem(s) in '{}'",
+			args.target
+		)));
+	}
+
+	Ok(())
+}
+
+// region:    --- Check
+
+/// A problem found in an agent file
+#[derive(Debug)]
+struct CheckProblem {
+	/// The 1-based line number in the agent file
+	line: usize,
+	section: String,
+	message: String,
+}
+
+impl std::fmt::Display for CheckProblem {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}: [{}] {}", self.line, self.section, self.message)
+	}
+}
+
+/// Returns the problems of the agent file (empty when valid)
+/// NOTE: A file that cannot be read or parsed is reported as a problem (so that the other files still get checked)
+fn check_agent_file(path: &SPath) -> Vec<CheckProblem> {
+	let res = read_to_string(path)
+		.map_err(Error::from)
+		.and_then(|content| check_agent_content(path, &content));
+	res.unwrap_or_else(|err| vec![problem(1, "agent", format!("Agent file error: {err}"))])
+}
+
+/// Returns the problems of the agent file content (empty when valid)
+fn check_agent_content(path: &SPath, content: &str) -> Result<Vec<CheckProblem>> {
+	let agent_doc = AgentDoc::from_content(path, content)?;
+	let AgentSections {
+		options_toml,
+		inputs_toml,
+		before_all_script,
+		data_script,
+		prompt_parts,
+		output_script,
+		after_all_script,
+		tools_script,
+		lines,
+	} = agent_doc.sections()?;
+
+	let mut problems: Vec<CheckProblem> = Vec::new();
+
+	// -- Options & Inputs
+	if let Some(options_toml) = options_toml {
+		let line = lines.options.unwrap_or(1);
+		let res = check_toml(&options_toml, line, "options")
+			.map(|value| serde_json::from_value::<AgentOptions>(value).map_err(|err| problem(line, "options", err)));
+		match res {
+			Ok(Ok(_)) => (),
+			Ok(Err(p)) | Err(p) => problems.push(p),
+		}
+	}
+	if let Some(inputs_toml) = inputs_toml {
+		let line = lines.inputs.unwrap_or(1);
+		let res = check_toml(&inputs_toml, line, "inputs")
+			.map(|value| serde_json::from_value::<InputsSchema>(value).map_err(|err| problem(line, "inputs", err)));
+		match res {
+			Ok(Ok(_)) => (),
+			Ok(Err(p)) | Err(p) => problems.push(p),
+		}
+	}
+
+	// -- Lua scripts
+	let scripts = [
+		("before_all", before_all_script, lines.before_all),
+		("data", data_script, lines.data),
+		("output", output_script, lines.output),
+		("after_all", after_all_script, lines.after_all),
+		("tools", tools_script, lines.tools),
+	];
+	for (section, script, line) in scripts {
+		if let Some(script) = script
+			&& let Err(p) = check_lua(&script, line.unwrap_or(1), section)
+		{
+			problems.push(p);
+		}
+	}
+
+	// -- Prompt parts
+	for part in prompt_parts.iter() {
+		let section = match part.kind {
+			PartKind::Instruction => "user",
+			PartKind::System => "system",
+			PartKind::Assistant => "assistant",
+		};
+		// The header is the line before the content
+		let header_line = part.line.saturating_sub(1).max(1);
+		if let Some(options_str) = part.options_str.as_deref()
+			// Options with handlebars are only known at run time
+			&& !options_str.contains("{{")
+			&& let Err(err) = parse_prompt_part_options(options_str)
+		{
+			let message = err.to_string().lines().next().unwrap_or_default().to_string();
+			problems.push(problem(header_line, section, message));
+		}
+		if let Err(err) = handlebars::Template::compile(&part.content) {
+			let line = err.pos().map(|(line, _)| part.line + line - 1).unwrap_or(part.line);
+			problems.push(problem(
+				line,
+				section,
+				format!("Handlebars template error: {}", err.reason()),
+			));
+		}
+	}
+
+	problems.sort_by_key(|p| p.line);
+
+	Ok(problems)
+}
+
+/// Parse the toml block, with the error line mapped to the agent file line
+fn check_toml(
+	toml_content: &str,
+	block_line: usize,
+	section: &str,
+) -> std::result::Result<serde_json::Value, CheckProblem> {
+	if let Err(err) = toml::from_str::<toml::Value>(toml_content) {
+		let line = err
+			.span()
+			.map(|span| block_line + toml_content[..span.start].matches('\n').count())
+			.unwrap_or(block_line);
+		return Err(problem(line, section, format!("TOML error: {}", err.message().trim())));
+	}
+	parse_toml_into_json(toml_content).map_err(|err| problem(block_line, section, err))
+}
+
+/// Compile (without executing) the lua script, with the error line mapped to the agent file line
+fn check_lua(script: &str, block_line: usize, section: &str) -> std::result::Result<(), CheckProblem> {
+	let lua = mlua::Lua::new();
+	let Err(err) = lua.load(script).set_name(format!("={section}")).into_function() else {
+		return Ok(());
+	};
+
+	let message = match &err {
+		mlua::Error::SyntaxError { message, .. } => message.clone(),
+		other => other.to_string(),
+	};
+
+	// The message is `section:LINE: cause`
+	let prefix = format!("{section}:");
+	let parsed = message.strip_prefix(&prefix).and_then(|rest| {
+		let (line, cause) = rest.split_once(':')?;
+		let line = line.parse::<usize>().ok()?;
+		Some((block_line + line - 1, cause.trim().to_string()))
+	});
+
+	let (line, cause) = parsed.unwrap_or((block_line, message));
+	Err(problem(line, section, format!("Lua syntax error: {cause}")))
+}
+
+fn problem(line: usize, section: &str, message: impl std::fmt::Display) -> CheckProblem {
+	CheckProblem {
+		line,
+		section: section.to_string(),
+		message: message.to_string(),
+	}
+}
+
+// endregion: --- Check
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::{gen_test_dir_path, remove_test_dir, save_file_content};
+
+	const AGENT_CONTENT: &str = r#"# Options
+
+```toml
+model = "gpt-5-mini"
+temperature = "hot
+```
+
+# Data
+
+```lua
+local a = 1
+if a then
+  return a +
+end
+```
+
+# System
+
+Valid {{data.name}}
+
+# User `cache = maybe`
+
+Some {{#if data.x}} content
+{{/each}}
+
+# Output
+
+```lua
+return "ok"
+```
+"#;
+
+	#[test]
+	fn test_exec_check_agent_content_problems() -> Result<()> {
+		// -- Setup & Fixtures
+		let path = SPath::new("test-agent.aip");
+
+		// -- Exec
+		let problems = check_agent_content(&path, AGENT_CONTENT)?;
+
+		// -- Check
+		let lines: Vec<(usize, &str)> = problems.iter().map(|p| (p.line, p.section.as_str())).collect();
+		assert_eq!(
+			lines,
+			vec![(5, "options"), (14, "data"), (21, "user"), (24, "user")],
+			"problems: {problems:#?}"
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_exec_check_agent_content_valid() -> Result<()> {
+		// -- Setup & Fixtures
+		let path = SPath::new("test-agent.aip");
+		let content = "# Data\n\n```lua\nreturn { name = \"a\" }\n```\n\n# User\n\nHello {{data.name}}\n";
+
+		// -- Exec
+		let problems = check_agent_content(&path, content)?;
+
+		// -- Check
+		assert!(problems.is_empty(), "problems: {problems:#?}");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_exec_check_agent_file_unreadable_is_problem() -> Result<()> {
+		// -- Setup & Fixtures
+		let dir = gen_test_dir_path();
+		let bad_file = dir.join("bad-agent.aip");
+		let good_file = dir.join("good-agent.aip");
+		std::fs::create_dir_all(dir.path())?;
+		// not valid utf8
+		std::fs::write(bad_file.path(), [0xff, 0xfe, b'#', b' ', b'D'])?;
+		save_file_content(&good_file, "# Output\n\n```lua\nreturn 1\n```\n")?;
+
+		// -- Exec
+		let bad_problems = check_agent_file(&bad_file);
+		let good_problems = check_agent_file(&good_file);
+
+		// -- Check
+		assert_eq!(bad_problems.len(), 1, "problems: {bad_problems:#?}");
+		assert_eq!(bad_problems[0].line, 1);
+		assert_eq!(bad_problems[0].section, "agent");
+		assert!(good_problems.is_empty(), "problems: {good_problems:#?}");
+
+		// -- Clean
+		remove_test_dir(&dir)?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
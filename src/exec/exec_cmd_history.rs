@@ -0,0 +1,18 @@
+use crate::Result;
+use crate::exec::cli::HistoryArgs;
+use crate::exec::support::open_persisted_mm;
+use crate::hub::get_hub;
+use crate::model::RunBmc;
+use crate::tui_v1::PrintEvent;
+
+/// Executes the `aip history` command, listing the most recent runs of the persisted runtime db.
+pub async fn exec_history(args: HistoryArgs) -> Result<()> {
+	let mm = open_persisted_mm().await?;
+
+	let limit = if args.limit > 0 { Some(args.limit) } else { None };
+	let runs = RunBmc::list_for_display(&mm, limit)?;
+
+	get_hub().publish(PrintEvent::RunHistory(runs)).await;
+
+	Ok(())
+}
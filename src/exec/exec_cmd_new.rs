@@ -1,67 +1,229 @@
-#![allow(unused)]
-use crate::dir_context::DirContext;
+use crate::dir_context::{DirContext, find_to_run_pack_dir};
 use crate::exec::cli::NewArgs;
-use crate::support::AsStrsExt;
-use crate::term::{init_term, prompt_input, prompt_select, safer_println};
-use crate::{Result, term};
-
-// (name, title)
-const AGENT_TEMPLATES: [(&str, &str); 2] = [
-	//
-	("hello-world", "Hello World Agent"),
-	("ask", "Generic Ask Agent with parametric prompt"),
-];
-
-/// exec for the New command
-/// Will create a new pack in base or workspace custom (not sure yet)
+use crate::exec::init::{
+	extract_template_agent_zfile, list_template_agent_file_paths, list_template_agent_names, render_template_pack_toml,
+};
+use crate::hub::get_hub;
+use crate::support::editor;
+use crate::types::PackRef;
+use crate::{Error, Result};
+use simple_fs::{SPath, ensure_dir, list_files};
+use std::fs;
+use tracing::info;
+
+/// The built-in template used when no `--template`
+const DEFAULT_TEMPLATE: &str = "hello-world";
+
+/// The agent file of a template
+const TEMPLATE_MAIN_AIP: &str = "main.aip";
+
+/// Exec for the New command
 ///
-/// NOTE: THIS IS DISABLED FOR NOW
-pub async fn exec_new(new_args: NewArgs, _dir_context: DirContext) -> Result<()> {
-	// let aipack_paths = dir_context.aipack_paths();
-
-	let mut stdout = init_term()?;
-
-	// -- Prompt the template
-	let max_len = AGENT_TEMPLATES.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
-	let labels = AGENT_TEMPLATES
-		.iter()
-		.map(|(name, title)| format!(" {name:>max_len$} - {title}"))
-		.collect::<Vec<_>>();
-	let template_idx = prompt_select(&mut stdout, "Select the agent template you want", &labels.x_as_strs())?;
-
-	// -- Prompt the name
-	let name_prompt = if let Some(name) = new_args.agent_path {
-		format!("Agent path/name ({name}):")
+/// Scaffolds an agent file (or a full pack with `--pack`) from a built-in template
+/// (`_init/_template/agent-NAME/`) or from a template dir of an installed pack (`ns@pack/sub/dir`).
+pub async fn exec_new(new_args: NewArgs, dir_context: DirContext) -> Result<()> {
+	let hub = get_hub();
+
+	let template_ref = new_args.template.as_deref().unwrap_or(DEFAULT_TEMPLATE);
+	let template_files = load_template_files(&dir_context, template_ref)?;
+
+	// -- Compute the files to create (dest path, content)
+	let dest = SPath::new(&new_args.path);
+	let mut to_create: Vec<(SPath, Vec<u8>)> = Vec::new();
+	let agent_path = if new_args.pack {
+		let pack_name = dest
+			.file_name()
+			.ok_or_else(|| Error::custom(format!("Cannot get the pack name from '{dest}'")))?;
+
+		let has_pack_toml = template_files.iter().any(|(path, _)| path == "pack.toml");
+		if !has_pack_toml {
+			to_create.push((
+				dest.join("pack.toml"),
+				render_template_pack_toml(pack_name)?.into_bytes(),
+			));
+		}
+		for (path, content) in template_files {
+			to_create.push((dest.join(&path), content));
+		}
+
+		dest.join(TEMPLATE_MAIN_AIP)
 	} else {
-		"Agent path/name:".to_string()
+		let agent_path = if dest.ext() == "aip" {
+			dest
+		} else {
+			SPath::new(format!("{dest}.aip"))
+		};
+		// The other template files (e.g., `lua/...`) are relative to the agent file dir
+		let agent_dir = agent_path.parent().unwrap_or_else(|| SPath::new("./"));
+		for (path, content) in template_files {
+			if path == TEMPLATE_MAIN_AIP {
+				to_create.push((agent_path.clone(), content));
+			} else if path != "pack.toml" {
+				to_create.push((agent_dir.join(&path), content));
+			}
+		}
+
+		agent_path
 	};
-	let agent_name = prompt_input(&mut stdout, &name_prompt, false)?;
-	let agent_name = agent_name.trim();
-
-	let agent_name = if agent_name.is_empty() { "my-agent" } else { agent_name };
-
-	let template_name = AGENT_TEMPLATES
-		.get(template_idx)
-		.map(|(name, _)| name)
-		.ok_or_else(|| format!("No template found for index {template_idx}"))?;
-
-	let confirm_prompt = format!(
-		r#"
-Creating new agent
-    name: {agent_name}
-template: {template_name}
-"#
-	);
-	safer_println(&confirm_prompt, true);
-
-	let confirm = prompt_input(&mut stdout, "Confirm creation (Y/n)", false)?;
-
-	// TODO: NOT IMPLEMENTED YET
-	if term::is_input_yes(&confirm) {
-		safer_println("Will do the create", true);
-	} else {
-		safer_println("Agent creation cancelled", true);
+
+	// -- Check existing files (before writing any)
+	if !new_args.force {
+		let existing: Vec<String> = to_create
+			.iter()
+			.filter(|(path, _)| path.exists())
+			.map(|(path, _)| format!("  - {path}"))
+			.collect();
+		if !existing.is_empty() {
+			return Err(Error::custom(format!(
+				"Cannot create the new agent, some files already exist (use --force to overwrite):\n{}",
+				existing.join("\n")
+			)));
+		}
+	}
+
+	// -- Write the files
+	for (path, content) in to_create {
+		if let Some(parent_dir) = path.parent() {
+			ensure_dir(parent_dir)?;
+		}
+		fs::write(&path, content)?;
+		hub.publish(format!("-> {:<18} '{path}'", "Create file")).await;
+	}
+
+	hub.publish(format!(
+		"\nNew agent created from template '{template_ref}'. Run it with:\n\n  aip run {agent_path}\n"
+	))
+	.await;
+
+	if new_args.open
+		&& let Err(err) = editor::open_file_auto(&agent_path)
+	{
+		info!("Cannot open agent file.\nCause: {err}")
 	}
 
 	Ok(())
 }
+
+// region:    --- Support
+
+/// Load the template files as `(relative path, content)`.
+///
+/// - `ns@pack/sub/dir` - The `sub/dir` directory of the installed (or custom) pack
+/// - `name` - The built-in `_init/_template/agent-NAME/` template
+fn load_template_files(dir_context: &DirContext, template_ref: &str) -> Result<Vec<(String, Vec<u8>)>> {
+	let files = if template_ref.contains('@') {
+		load_pack_template_files(dir_context, template_ref)?
+	} else {
+		let paths = list_template_agent_file_paths(template_ref)?;
+		if paths.is_empty() {
+			let names = list_template_agent_names()?.join(", ");
+			return Err(Error::custom(format!(
+				"No built-in template '{template_ref}'. Available templates: {names}\n(or use a pack template, e.g., `--template ns@pack/template-dir`)"
+			)));
+		}
+		paths
+			.into_iter()
+			.map(|path| extract_template_agent_zfile(template_ref, &path).map(|zfile| (path, zfile.content)))
+			.collect::<Result<Vec<_>>>()?
+	};
+
+	if !files.iter().any(|(path, _)| path == TEMPLATE_MAIN_AIP) {
+		return Err(Error::custom(format!(
+			"Template '{template_ref}' does not have a '{TEMPLATE_MAIN_AIP}' file"
+		)));
+	}
+
+	Ok(files)
+}
+
+fn load_pack_template_files(dir_context: &DirContext, template_ref: &str) -> Result<Vec<(String, Vec<u8>)>> {
+	let pack_ref: PackRef = template_ref.parse()?;
+	let sub_path = pack_ref.sub_path.as_deref().ok_or_else(|| {
+		Error::custom(format!(
+			"Pack template '{template_ref}' must have the template directory, e.g., '{}@{}/template-dir'",
+			pack_ref.namespace, pack_ref.name
+		))
+	})?;
+
+	let pack_dir = find_to_run_pack_dir(dir_context, &pack_ref)?;
+	let template_dir = pack_dir.path.join(sub_path);
+	if !template_dir.is_dir() {
+		return Err(Error::custom(format!(
+			"Pack template directory '{sub_path}' not found in {pack_ref} ({})",
+			pack_dir.pretty_path()
+		)));
+	}
+
+	let mut files = Vec::new();
+	for file in list_files(&template_dir, Some(&["**/*"]), None)? {
+		let rel_path = file.try_diff(&template_dir)?;
+		let content = fs::read(&file)?;
+		files.push((rel_path.to_string(), content));
+	}
+
+	Ok(files)
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::{gen_test_dir_path, remove_test_dir};
+	use crate::runtime::Runtime;
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_exec_new_agent_and_pack() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let dir = gen_test_dir_path();
+		let new_args = |path: SPath, pack: bool| NewArgs {
+			path: path.to_string(),
+			template: None,
+			pack,
+			force: false,
+			open: false,
+		};
+
+		// -- Exec
+		exec_new(
+			new_args(dir.join("agents/summarize"), false),
+			runtime.dir_context().clone(),
+		)
+		.await?;
+		exec_new(new_args(dir.join("my-pack"), true), runtime.dir_context().clone()).await?;
+
+		// -- Check
+		// agent file (with the `.aip` extension added)
+		let agent_file = dir.join("agents/summarize.aip");
+		assert!(agent_file.is_file(), "Should have created '{agent_file}'");
+		assert!(
+			!dir.join("agents/pack.toml").exists(),
+			"An agent should not have a pack.toml"
+		);
+		// pack (with its pack.toml named after the pack dir)
+		assert!(dir.join("my-pack/main.aip").is_file());
+		let pack_toml = fs::read_to_string(dir.join("my-pack/pack.toml"))?;
+		assert!(pack_toml.contains(r#"name = "my-pack""#), "pack.toml:\n{pack_toml}");
+		// existing files are not overwritten without `--force`
+		let err = exec_new(
+			new_args(dir.join("agents/summarize.aip"), false),
+			runtime.dir_context().clone(),
+		)
+		.await
+		.err()
+		.ok_or("Should fail")?;
+		assert!(err.to_string().contains("already exist"), "err: {err}");
+
+		// -- Clean
+		remove_test_dir(&dir)?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
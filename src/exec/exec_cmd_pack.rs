@@ -1,9 +1,8 @@
-use super::init::extract_template_pack_toml_zfile;
+use super::init::render_template_pack_toml;
 use super::packer::pack_dir;
 use crate::exec::cli::PackArgs;
 use crate::hub::get_hub;
 use crate::{Error, Result, term};
-use aho_corasick::AhoCorasick;
 use simple_fs::SPath;
 use std::fs;
 use std::io::{self};
@@ -96,22 +95,12 @@ pub async fn exec_pack(pack_args: &PackArgs) -> Result<()> {
 async fn generate_pack_toml(dir_path: &SPath) -> Result<()> {
 	let hub = get_hub();
 
-	// Extract the template pack.toml
-	let pack_toml_zfile = extract_template_pack_toml_zfile()?;
-	let mut content =
-		String::from_utf8(pack_toml_zfile.content).map_err(|_| Error::custom("template pack.toml is not UTF8 ??"))?;
-
 	// Get the directory name
 	let dir_name = dir_path
 		.file_name()
 		.ok_or_else(|| Error::custom("Unable to extract directory name"))?;
 
-	// Replace DIR_NAME with actual directory name using aho-corasick
-	let patterns = &["DIR_NAME"];
-	let replacements = &[dir_name];
-	let ac =
-		AhoCorasick::new(patterns).map_err(|err| Error::custom(format!("AhoCorasick pattern fail.\nCause: {err}")))?;
-	content = ac.replace_all(&content, replacements);
+	let content = render_template_pack_toml(dir_name)?;
 
 	// Write the file
 	let toml_path = dir_path.join("pack.toml");
@@ -0,0 +1,152 @@
+use crate::agent::{Agent, AgentOptions, find_agent};
+use crate::exec::cli::ReplayArgs;
+use crate::exec::exec_cmd_show::find_run;
+use crate::hub::get_hub;
+use crate::model::{Run, RunBmc, Task, TaskBmc};
+use crate::run::{RunAgentExecution, RunBaseOptions, run_agent_task_replay};
+use crate::runtime::Runtime;
+use crate::{Error, Result};
+
+/// Executes the `aip replay <run> --task <idx>` command.
+///
+/// Reruns only this task, with its recorded input and the before all value, options overrides, and params of the run,
+/// and records it as a new run linked to the original one.
+pub async fn exec_replay(args: ReplayArgs, runtime: Runtime) -> Result<()> {
+	let hub = get_hub();
+	let mm = runtime.mm();
+
+	// The replayed run and the new one must be in the persisted runtime db
+	if !mm.db().is_persistent() {
+		return Err(Error::RunHistoryNotFound);
+	}
+
+	// -- Load the run & task
+	let run = find_run(mm, &args.run_ref)?;
+	let task = TaskBmc::list_for_run(mm, run.id)?
+		.into_iter()
+		.find(|task| task.idx == Some(args.task_idx))
+		.ok_or_else(|| Error::RunTaskNotFound {
+			run_ref: args.run_ref.clone(),
+			task_idx: args.task_idx,
+		})?;
+	let agent_path = run.agent_path.as_deref().ok_or_else(|| Error::RunNoAgentPath {
+		run_ref: args.run_ref.clone(),
+	})?;
+
+	// -- Load the agent (the agent path is relative to the workspace dir)
+	let agent = find_agent(agent_path, &runtime, runtime.dir_context().wks_dir())?;
+
+	hub.publish(format!(
+		"\n==== Replaying task #{} of run {} ({agent_path})",
+		args.task_idx, run.id
+	))
+	.await;
+
+	replay_run_task(&runtime, agent, &run, &task, RunBaseOptions::from(&args)).await?;
+
+	Ok(())
+}
+
+/// Replay the task of the run with its recorded values
+/// (input, and the before all value, options overrides, and params of the run).
+async fn replay_run_task(
+	runtime: &Runtime,
+	agent: Agent,
+	run: &Run,
+	task: &Task,
+	run_base_options: RunBaseOptions,
+) -> Result<RunAgentExecution> {
+	let mm = runtime.mm();
+
+	// -- Load the recorded values
+	let input = TaskBmc::get_input_value(mm, task)?;
+	let before_all = RunBmc::get_before_all(mm, run)?;
+	let params = RunBmc::get_params(mm, run)?;
+	let options = RunBmc::get_options(mm, run)?
+		.map(serde_json::from_value::<AgentOptions>)
+		.transpose()?;
+
+	// -- The agent with the options overrides of the Before All of the run (e.g., model, temperature)
+	let agent = match options {
+		Some(options) => agent.new_merge(options)?,
+		None => agent,
+	};
+
+	let run_base_options = run_base_options.with_params(params);
+	run_agent_task_replay(
+		runtime,
+		agent,
+		run.id,
+		task.idx.unwrap_or_default() as usize,
+		before_all,
+		input,
+		&run_base_options,
+	)
+	.await
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::run::run_agent;
+	use serde_json::json;
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_exec_replay_run_task_before_all_options() -> Result<()> {
+		// -- Setup & Fixtures
+		// The Before All overrides the model, which must be kept when replaying (the Before All is not executed again)
+		let content = r#"
+# Options
+
+```toml
+model = "agent-model"
+```
+
+# Before All
+
+```lua
+return aip.flow.before_all_response({ before_all = "ba", options = { model = "ba-model" } })
+```
+
+# Output
+
+```lua
+return before_all .. " - " .. options.model .. " - " .. input
+```
+"#;
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let agent = Agent::mock_from_content(content)?;
+		run_agent(
+			&runtime,
+			None,
+			agent.clone(),
+			Some(vec!["one".into(), "two".into()]),
+			&RunBaseOptions::default(),
+			true,
+		)
+		.await?;
+		let mm = runtime.mm();
+		let run = RunBmc::list(mm, None)?.pop().ok_or("Should have one run")?;
+		let task = TaskBmc::list_for_run(mm, run.id)?
+			.into_iter()
+			.find(|task| task.idx == Some(1))
+			.ok_or("Should have task #1")?;
+
+		// -- Exec
+		let replay = replay_run_task(&runtime, agent, &run, &task, RunBaseOptions::default()).await?;
+
+		// -- Check
+		let outputs = replay.response.outputs.ok_or("Should have outputs")?;
+		assert_eq!(outputs, vec![json!("ba - ba-model - two")]);
+		let replay_run = RunBmc::get(mm, replay.run_id)?;
+		assert_eq!(replay_run.replay_of_id, Some(run.id));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
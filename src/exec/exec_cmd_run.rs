@@ -1,14 +1,17 @@
-use crate::agent::{Agent, find_agent};
+use crate::agent::{Agent, FieldSpec, InputType, find_agent};
 use crate::exec::cli::RunArgs;
+use crate::exec::exec_cmd_show::find_run;
 use crate::hub::{HubEvent, get_hub};
 use crate::model::Id;
-use crate::run::{RunAgentExecution, RunRedoCtx, RunTopAgentParams, run_agent_with_identity};
+use crate::run::{RunAgentExecution, RunRedoCtx, RunResume, RunTopAgentParams, run_agent_with_identity};
 use crate::runtime::Runtime;
-use crate::support::jsons::into_values;
+use crate::support::csvs::{csv_content_to_records, load_csv};
+use crate::support::jsons::{into_values, load_json_to_serde_value};
 use crate::support::{editor, text};
 use crate::types::FileInfo;
 use crate::{Error, Result, term};
-use simple_fs::{SEventKind, SPath, list_files, watch};
+use serde_json::Value;
+use simple_fs::{SEventKind, SPath, list_files, load_ndjson, watch};
 use tracing::info;
 
 /// Exec for the Run command
@@ -82,6 +85,12 @@ pub async fn exec_run_first(run_args: RunArgs, runtime: Runtime) -> Result<(RunR
 	))
 }
 
+/// Exec for `aip run <agent> --help-agent`
+/// Prints the agent inputs and params contract (from its `# Inputs` section) without running it.
+pub async fn exec_help_agent(agent: &Agent) {
+	get_hub().publish(format_agent_help(agent)).await;
+}
+
 /// Redo the exec_run, with its context
 /// NOTE: The redo pattern just take one ctx arg, and handle its own error
 pub async fn exec_run_redo(run_redo_ctx: &RunRedoCtx) -> Option<RunRedoCtx> {
@@ -176,8 +185,44 @@ async fn do_run(
 	loop_id: Option<Id>,
 	reopen_loop: bool,
 ) -> Result<RunAgentExecution> {
+	// -- When resuming, the inputs are the ones of the resumed run
+	if let Some(run_ref) = run_command_options.resume_run_ref() {
+		let mm = runtime.mm();
+		if !mm.db().is_persistent() {
+			return Err(Error::RunHistoryNotFound);
+		}
+		let run = find_run(mm, run_ref)?;
+		if run.agent_name.as_deref() != Some(agent.name()) {
+			get_hub()
+				.publish(format!(
+					"WARNING - Run {} was for agent '{}', resuming it with agent '{}'",
+					run.id,
+					run.agent_name.as_deref().unwrap_or_default(),
+					agent.name()
+				))
+				.await;
+		}
+		let resume = RunResume::load(mm, &run)?;
+		let inputs = resume.inputs().to_vec();
+		let base_run_options = run_command_options.base_run_options().with_resume(resume);
+
+		return run_agent_with_identity(
+			runtime,
+			None,
+			agent.clone(),
+			Some(inputs),
+			&base_run_options,
+			false,
+			loop_id,
+			reopen_loop,
+		)
+		.await;
+	}
+
 	let inputs = if let Some(on_inputs) = run_command_options.on_inputs() {
 		Some(into_values(on_inputs)?)
+	} else if let Some(inputs_file) = run_command_options.inputs_file() {
+		Some(load_inputs_file(inputs_file)?)
 	} else if let Some(on_file_globs) = run_command_options.on_file_globs() {
 		// -- First, normalize the globs
 		// Note: here we add the eventual `./` for relative globs so that it works both ways
@@ -234,3 +279,154 @@ async fn do_run(
 
 	Ok(res)
 }
+
+// region:    --- Support
+
+fn format_agent_help(agent: &Agent) -> String {
+	let mut help = format!("\nAgent: {}\nPath:  {}\n", agent.name(), agent.file_path());
+
+	let Some(schema) = agent.inputs_schema() else {
+		help.push_str("\nNo # Inputs section (inputs and params are not documented).\n");
+		return help;
+	};
+
+	help.push_str(&format!("\nInputs ({}):\n", schema.input_type()));
+	if let Some(description) = schema.description.as_deref() {
+		help.push_str(&format!("  {description}\n"));
+	}
+	push_field_specs(&mut help, &schema.fields);
+
+	if !schema.params.is_empty() {
+		help.push_str("\nParams (--param KEY=VALUE):\n");
+		push_field_specs(&mut help, &schema.params);
+	}
+
+	help
+}
+
+fn push_field_specs(help: &mut String, specs: &[FieldSpec]) {
+	for spec in specs {
+		let typ = spec.typ.unwrap_or(InputType::Any);
+		let mut line = format!("  - {} ({typ}", spec.name);
+		if spec.required {
+			line.push_str(", required");
+		}
+		if let Some(default) = &spec.default {
+			line.push_str(&format!(", default: {default}"));
+		}
+		line.push(')');
+		if let Some(description) = spec.description.as_deref() {
+			line.push_str(&format!(" - {description}"));
+		}
+		help.push_str(&line);
+		help.push('\n');
+	}
+}
+
+/// Load the inputs of `--inputs-file`, one input per record.
+///
+/// - `.ndjson` / `.jsonl` - One input per line
+/// - `.json` - One input per item of the top array
+/// - `.csv` - One input per row, as `{header: cell}` objects
+fn load_inputs_file(path: &str) -> Result<Vec<Value>> {
+	let file = SPath::new(path);
+	if !file.is_file() {
+		return Err(Error::custom(format!("Inputs file '{file}' not found")));
+	}
+
+	let inputs = match file.ext() {
+		"ndjson" | "jsonl" => load_ndjson(&file)?,
+		"json" => match load_json_to_serde_value(&file)? {
+			Some(Value::Array(items)) => items,
+			_ => {
+				return Err(Error::custom(format!(
+					"Inputs file '{file}' must contain a JSON array (one item per input)"
+				)));
+			}
+		},
+		"csv" => csv_content_to_records(load_csv(&file, None)?),
+		other => {
+			return Err(Error::custom(format!(
+				"Inputs file '{file}' extension '{other}' not supported (must be .ndjson, .jsonl, .json, or .csv)"
+			)));
+		}
+	};
+
+	Ok(inputs)
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::{gen_test_dir_path, remove_test_dir, save_file_content};
+	use serde_json::json;
+
+	#[test]
+	fn test_exec_run_load_inputs_file_records() -> Result<()> {
+		// -- Setup & Fixtures
+		let dir = gen_test_dir_path();
+		let fx_files = [
+			("inputs.ndjson", "{\"name\": \"a\"}\n\n{\"name\": \"b\"}\n"),
+			("inputs.jsonl", "\"one\"\n2\n"),
+			("inputs.json", r#"[{"name": "a"}, "two"]"#),
+			("inputs.csv", "name,age\nJen,30\nMike,42\n"),
+		];
+		for (name, content) in fx_files {
+			save_file_content(&dir.join(name), content)?;
+		}
+
+		// -- Exec
+		let ndjson = load_inputs_file(dir.join("inputs.ndjson").as_str())?;
+		let jsonl = load_inputs_file(dir.join("inputs.jsonl").as_str())?;
+		let json = load_inputs_file(dir.join("inputs.json").as_str())?;
+		let csv = load_inputs_file(dir.join("inputs.csv").as_str())?;
+
+		// -- Check
+		assert_eq!(ndjson, vec![json!({"name": "a"}), json!({"name": "b"})]);
+		assert_eq!(jsonl, vec![json!("one"), json!(2)]);
+		assert_eq!(json, vec![json!({"name": "a"}), json!("two")]);
+		assert_eq!(
+			csv,
+			vec![json!({"name": "Jen", "age": "30"}), json!({"name": "Mike", "age": "42"})]
+		);
+
+		// -- Clean
+		remove_test_dir(&dir)?;
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_exec_run_load_inputs_file_errors() -> Result<()> {
+		// -- Setup & Fixtures
+		let dir = gen_test_dir_path();
+		save_file_content(&dir.join("inputs.json"), r#"{"name": "a"}"#)?;
+		save_file_content(&dir.join("inputs.txt"), "one\ntwo\n")?;
+
+		// -- Exec
+		let json_err = load_inputs_file(dir.join("inputs.json").as_str()).err().ok_or("Should fail")?;
+		let txt_err = load_inputs_file(dir.join("inputs.txt").as_str()).err().ok_or("Should fail")?;
+		let missing_err = load_inputs_file(dir.join("missing.json").as_str()).err().ok_or("Should fail")?;
+
+		// -- Check
+		assert!(json_err.to_string().contains("must contain a JSON array"), "{json_err}");
+		assert!(
+			txt_err.to_string().contains("extension 'txt' not supported"),
+			"{txt_err}"
+		);
+		assert!(missing_err.to_string().contains("not found"), "{missing_err}");
+
+		// -- Clean
+		remove_test_dir(&dir)?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,88 @@
+use crate::exec::cli::ShowArgs;
+use crate::exec::support::open_persisted_mm;
+use crate::hub::get_hub;
+use crate::model::{ErrBmc, ErrRec, Id, ModelManager, Pin, PinBmc, Run, RunBmc, Task, TaskBmc};
+use crate::tui_v1::PrintEvent;
+use crate::{Error, Result};
+use uuid::Uuid;
+
+/// The data of a past run, as shown by `aip show <run>`
+#[derive(Debug)]
+pub struct RunReport {
+	pub run: Run,
+	pub tasks: Vec<TaskReport>,
+	/// All errors of the run (including task errors)
+	pub errs: Vec<ErrRec>,
+	/// The run pins (task pins are in their TaskReport)
+	pub pins: Vec<Pin>,
+	/// Show the full input/output contents
+	pub full: bool,
+}
+
+#[derive(Debug)]
+pub struct TaskReport {
+	pub task: Task,
+	/// The input for display (short or full content)
+	pub input: Option<String>,
+	/// The output for display (short or full content)
+	pub output: Option<String>,
+	pub pins: Vec<Pin>,
+}
+
+/// Executes the `aip show <run>` command.
+pub async fn exec_show(args: ShowArgs) -> Result<()> {
+	let mm = open_persisted_mm().await?;
+
+	let run = find_run(&mm, &args.run_ref)?;
+	let report = load_run_report(&mm, run, args.full)?;
+
+	get_hub().publish(PrintEvent::RunReport(Box::new(report))).await;
+
+	Ok(())
+}
+
+/// Load the RunReport for this run.
+pub fn load_run_report(mm: &ModelManager, run: Run, full: bool) -> Result<RunReport> {
+	let mut tasks = Vec::new();
+	for task in TaskBmc::list_for_run(mm, run.id)? {
+		let input = TaskBmc::get_input_for_display(mm, &task)?;
+		let output = TaskBmc::get_output_for_display(mm, &task)?;
+		let pins = PinBmc::list_for_task(mm, task.id)?;
+		tasks.push(TaskReport {
+			task,
+			input,
+			output,
+			pins,
+		});
+	}
+
+	let errs = ErrBmc::list_for_run(mm, run.id)?;
+	let pins = PinBmc::list_for_run(mm, run.id)?;
+
+	Ok(RunReport {
+		run,
+		tasks,
+		errs,
+		pins,
+		full,
+	})
+}
+
+/// Find the run from the run id (e.g., `12`) or the run uid.
+pub fn find_run(mm: &ModelManager, run_ref: &str) -> Result<Run> {
+	let run_ref = run_ref.trim();
+
+	let run = if let Ok(id) = run_ref.parse::<i64>() {
+		RunBmc::get(mm, Id::from(id))
+	} else if let Ok(uid) = Uuid::parse_str(run_ref) {
+		RunBmc::get_by_uid(mm, uid)
+	} else {
+		return Err(Error::RunNotFound {
+			run_ref: run_ref.to_string(),
+		});
+	};
+
+	run.map_err(|_| Error::RunNotFound {
+		run_ref: run_ref.to_string(),
+	})
+}
@@ -0,0 +1,306 @@
+use crate::agent::{AgentOptions, find_agent};
+use crate::exec::cli::TestArgs;
+use crate::exec::support::resolve_pack_dir;
+use crate::hub::get_hub;
+use crate::run::{RunBaseOptions, run_agent};
+use crate::runtime::Runtime;
+use crate::support::text::line_diff;
+use crate::support::tomls::parse_toml_into_json;
+use crate::{Error, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use simple_fs::{SPath, list_files, read_to_string};
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+/// The dir of the test cases in the pack
+const TESTS_DIR: &str = "tests";
+/// The file of a test case (`tests/<case_name>/test.toml`)
+const TEST_CASE_FILE: &str = "test.toml";
+/// The `mock::` model responses of a test case (`tests/<case_name>/mocks/`)
+const TEST_CASE_MOCKS_DIR: &str = "mocks";
+
+const DEFAULT_AGENT: &str = "main.aip";
+const DEFAULT_MODEL: &str = "mock::default";
+
+/// Exec for the Test command
+///
+/// Runs each test case of the pack `tests/` dir through `run_agent`
+/// and reports the pass/fail with the diffs of the expected values.
+pub async fn exec_test(args: TestArgs, runtime: Runtime) -> Result<()> {
+	let hub = get_hub();
+
+	let pack_dir = resolve_pack_dir(runtime.dir_context(), &args.target)?;
+	let tests_dir = pack_dir.join(TESTS_DIR);
+	if !tests_dir.is_dir() {
+		return Err(Error::custom(format!(
+			"No '{TESTS_DIR}/' directory in '{pack_dir}' (test cases are '{TESTS_DIR}/<case_name>/{TEST_CASE_FILE}')"
+		)));
+	}
+
+	// -- List the test cases
+	let glob = format!("*/{TEST_CASE_FILE}");
+	let mut case_files = list_files(&tests_dir, Some(&[glob.as_str()]), None)?;
+	case_files.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+	let case_dirs: Vec<SPath> = case_files
+		.into_iter()
+		.filter_map(|file| file.parent())
+		.filter(|dir| args.case.as_deref().is_none_or(|case| dir.name().contains(case)))
+		.collect();
+	if case_dirs.is_empty() {
+		return Err(Error::custom(format!("No test cases found in '{tests_dir}'")));
+	}
+
+	hub.publish(format!(
+		"\n==== Running {} test case(s) of '{}'",
+		case_dirs.len(),
+		args.target
+	))
+	.await;
+
+	// -- Run the test cases
+	let mut failed_names: Vec<String> = Vec::new();
+	for case_dir in case_dirs.iter() {
+		let name = case_dir.name();
+		let failures = match run_test_case(&runtime, &pack_dir, case_dir).await {
+			Ok(failures) => failures,
+			Err(err) => vec![format!("Error: {err}")],
+		};
+
+		if failures.is_empty() {
+			hub.publish(format!("\n✔ {name} - ok")).await;
+		} else {
+			hub.publish(format!("\n✘ {name} - FAILED\n{}", failures.join("\n"))).await;
+			failed_names.push(name.to_string());
+		}
+	}
+
+	// -- Report
+	let passed = case_dirs.len() - failed_names.len();
+	hub.publish(format!(
+		"\n==== Test result: {passed} passed; {} failed",
+		failed_names.len()
+	))
+	.await;
+
+	if !failed_names.is_empty() {
+		return Err(Error::custom(format!(
+			"Failed test case(s): {}",
+			failed_names.join(", ")
+		)));
+	}
+
+	Ok(())
+}
+
+// region:    --- Test Case
+
+/// The `tests/<case_name>/test.toml` file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestCase {
+	/// The agent file, relative to the pack dir (default `main.aip`)
+	agent: Option<String>,
+	/// The model (default `mock::default`, with the responses in the `mocks/` dir of the test case)
+	model: Option<String>,
+	inputs: Option<Vec<Value>>,
+	#[serde(default)]
+	params: Map<String, Value>,
+	#[serde(default)]
+	expect: TestExpect,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestExpect {
+	/// The task outputs (in the inputs order)
+	outputs: Option<Vec<Value>>,
+	/// The value returned by `# After All`
+	after_all: Option<Value>,
+	/// The run is expected to fail with an error containing this text
+	error: Option<String>,
+	/// The files written by the run (path relative to the workspace dir, and content)
+	/// NOTE: A file must be (re)written by the run, an existing file left unchanged fails the check.
+	#[serde(default)]
+	files: BTreeMap<String, String>,
+}
+
+/// Runs the test case, and returns the failure messages (empty when passed)
+async fn run_test_case(runtime: &Runtime, pack_dir: &SPath, case_dir: &SPath) -> Result<Vec<String>> {
+	// -- Load the test case
+	let case_file = case_dir.join(TEST_CASE_FILE);
+	let case: TestCase = parse_toml_into_json(&read_to_string(&case_file)?)
+		.and_then(|value| Ok(serde_json::from_value(value)?))
+		.map_err(|err| Error::custom(format!("Invalid test case file '{case_file}'. Cause: {err}")))?;
+
+	// -- Load the agent (with the test model)
+	let agent_path = pack_dir.join(case.agent.as_deref().unwrap_or(DEFAULT_AGENT));
+	let agent = find_agent(agent_path.as_str(), runtime, None)?;
+	let model = case.model.as_deref().unwrap_or(DEFAULT_MODEL);
+	let agent = agent.new_merge(AgentOptions::from_options_value(json!({ "model": model }))?)?;
+
+	// -- Snapshot the modification times of the expected files
+	// NOTE: So that a file left by a previous run does not pass the check
+	let base_dir = runtime.dir_context().wks_dir().cloned().unwrap_or_else(|| SPath::new("."));
+	let files_modified: Vec<Option<SystemTime>> = case
+		.expect
+		.files
+		.keys()
+		.map(|path| file_modified(&base_dir.join(path)))
+		.collect();
+
+	// -- Run
+	let run_base_options = RunBaseOptions::default()
+		.with_params(case.params)
+		.with_mock_dir(case_dir.join(TEST_CASE_MOCKS_DIR));
+	let res = run_agent(runtime, None, agent, case.inputs, &run_base_options, true).await;
+
+	// -- Check
+	let expect = case.expect;
+	let mut failures: Vec<String> = Vec::new();
+
+	let response = match (res, expect.error.as_deref()) {
+		(Ok(response), None) => response,
+		(Ok(_), Some(expected_err)) => {
+			failures.push(format!(
+				"  Expected the run to fail with '{expected_err}', but it succeeded"
+			));
+			return Ok(failures);
+		}
+		(Err(err), Some(expected_err)) => {
+			let err = err.to_string();
+			if !err.contains(expected_err) {
+				failures.push(format!(
+					"  Expected the run to fail with '{expected_err}', but failed with:\n{err}"
+				));
+			}
+			return Ok(failures);
+		}
+		(Err(err), None) => {
+			failures.push(format!("  Run failed: {err}"));
+			return Ok(failures);
+		}
+	};
+
+	if let Some(expected_outputs) = expect.outputs {
+		let outputs = response.outputs.unwrap_or_default();
+		if outputs.len() != expected_outputs.len() {
+			failures.push(format!(
+				"  Expected {} output(s), but got {}",
+				expected_outputs.len(),
+				outputs.len()
+			));
+		}
+		for (idx, (expected, actual)) in expected_outputs.iter().zip(outputs.iter()).enumerate() {
+			if let Some(diff) = diff_values(expected, actual) {
+				failures.push(format!("  Output #{idx} mismatch:\n{diff}"));
+			}
+		}
+	}
+
+	if let Some(expected) = expect.after_all {
+		let actual = response.after_all.unwrap_or(Value::Null);
+		if let Some(diff) = diff_values(&expected, &actual) {
+			failures.push(format!("  After All mismatch:\n{diff}"));
+		}
+	}
+
+	for ((path, expected), modified_before) in expect.files.iter().zip(files_modified) {
+		let file = base_dir.join(path);
+		let modified = file_modified(&file);
+		if modified.is_none() || modified == modified_before {
+			failures.push(format!("  Expected file '{path}' was not written by the run"));
+			continue;
+		}
+		let actual = read_to_string(&file)?;
+		if actual.trim_end() != expected.trim_end() {
+			failures.push(format!(
+				"  File '{path}' mismatch:\n{}",
+				indent_diff(&line_diff(expected.trim_end(), actual.trim_end()))
+			));
+		}
+	}
+
+	Ok(failures)
+}
+
+// endregion: --- Test Case
+
+// region:    --- Support
+
+/// Returns the diff of the two values if they are not equal
+/// (strings are compared as text, other values as pretty json)
+fn diff_values(expected: &Value, actual: &Value) -> Option<String> {
+	if expected == actual {
+		return None;
+	}
+	let to_text = |value: &Value| match value {
+		Value::String(s) => s.clone(),
+		other => serde_json::to_string_pretty(other).unwrap_or_default(),
+	};
+	Some(indent_diff(&line_diff(&to_text(expected), &to_text(actual))))
+}
+
+/// The modification time of the file (None if it does not exist)
+fn file_modified(file: &SPath) -> Option<SystemTime> {
+	std::fs::metadata(file.path()).and_then(|meta| meta.modified()).ok()
+}
+
+fn indent_diff(diff: &str) -> String {
+	diff.lines().map(|line| format!("    {line}")).collect::<Vec<_>>().join("\n")
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::{assert_contains, clean_sanbox_01_tmp_file};
+
+	const FX_PACK_DIR: &str = "./tests-data/test_packs_folder/test_pack_tests";
+	/// The file written by the `case-files` test case (relative to the sandbox-01 workspace)
+	const FX_CASE_FILE: &str = ".tmp/test_pack_tests/case-files/hello.md";
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_exec_test_run_test_case_fixture_pack() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		// NOTE: Absolute, as the agent paths are relative to the pack dir
+		let pack_dir = SPath::from_std_path(std::fs::canonicalize(FX_PACK_DIR)?)?;
+		let tests_dir = pack_dir.join(TESTS_DIR);
+		clean_sanbox_01_tmp_file(SPath::new(FX_CASE_FILE))?;
+
+		// -- Exec
+		let ok_failures = run_test_case(&runtime, &pack_dir, &tests_dir.join("case-ok")).await?;
+		let fail_failures = run_test_case(&runtime, &pack_dir, &tests_dir.join("case-fail")).await?;
+		let error_failures = run_test_case(&runtime, &pack_dir, &tests_dir.join("case-fail-error")).await?;
+		let files_failures = run_test_case(&runtime, &pack_dir, &tests_dir.join("case-files")).await?;
+		let stale_failures = run_test_case(&runtime, &pack_dir, &tests_dir.join("case-files-stale")).await?;
+
+		// -- Check
+		assert!(ok_failures.is_empty(), "failures: {ok_failures:#?}");
+		assert_eq!(fail_failures.len(), 2, "failures: {fail_failures:#?}");
+		assert_contains(&fail_failures[0], "Output #0 mismatch");
+		assert_contains(&fail_failures[0], "Bonjour Jen");
+		assert_contains(&fail_failures[1], "After All mismatch");
+		assert_eq!(error_failures.len(), 1, "failures: {error_failures:#?}");
+		assert_contains(
+			&error_failures[0],
+			"Expected the run to fail with 'Some expected error'",
+		);
+		assert!(files_failures.is_empty(), "failures: {files_failures:#?}");
+		assert_eq!(stale_failures.len(), 1, "failures: {stale_failures:#?}");
+		assert_contains(&stale_failures[0], "was not written by the run");
+
+		// -- Clean
+		clean_sanbox_01_tmp_file(SPath::new(FX_CASE_FILE))?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,5 +1,5 @@
 use crate::agent::find_agent;
-use crate::run::{RunBaseOptions, RunSubAgentParams, run_agent};
+use crate::run::{RunSubAgentParams, run_agent};
 use crate::types::RunAgentResponse;
 use crate::{Error, Result};
 
@@ -12,13 +12,12 @@ pub async fn exec_run_sub_agent(params: RunSubAgentParams) -> Result<()> {
 		agent_name,
 		inputs,
 		agent_options,
+		params: run_params,
+		run_base_options,
 		response_shot,
 	} = params;
 
 	// -- Find agent and build run base options
-	// NOTE: For now, do not pass through the caller baseOptions.
-	// NOTE: Might need to find a way to pass it through (perhaps via CTX, or a _aipack_.run_base_options)
-
 	let result: Result<RunAgentResponse> = (async || {
 		// find the agent
 		let agent = find_agent(&agent_name, &runtime, agent_dir.as_ref())
@@ -30,9 +29,8 @@ pub async fn exec_run_sub_agent(params: RunSubAgentParams) -> Result<()> {
 			None => agent,
 		};
 
-		// -- Build the environment
-		// NOTE: For now, do not inherit the parent run, But eventually mgith be past in the RunAgentParams
-		let run_base_options = RunBaseOptions::default();
+		// -- Build the environment (from the parent run base options, with the sub-agent params)
+		let run_base_options = run_base_options.for_sub_agent(run_params.unwrap_or_default());
 
 		let res = run_agent(&runtime, Some(parent_uid), agent, inputs, &run_base_options, true)
 			.await
@@ -9,14 +9,20 @@ use crate::exec::exec_sub_agent::exec_run_sub_agent;
 use crate::exec::init::{init_base, init_base_and_dir_context, init_wks};
 use crate::exec::{
 	ExecStatusEvent,
+	exec_check,
 	exec_check_keys,
 	exec_create_gitignore,
+	exec_help_agent,
+	exec_history,
 	exec_install,
 	exec_list,
 	exec_new,
 	exec_pack,
+	exec_replay,
 	exec_run,
 	exec_run_redo,
+	exec_show,
+	exec_test,
 	exec_unpack,
 	exec_xelf_setup, // Added import
 };
@@ -33,7 +39,7 @@ use crate::{Error, Result};
 use flume::{Receiver, Sender};
 use simple_fs::SPath;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::Duration;
 use tokio::runtime::Handle;
 use tokio::sync::Mutex;
@@ -70,6 +76,9 @@ pub struct Executor {
 
 	cancel_trx: Option<CancelTrx>,
 
+	/// Set when a CLI command failed (e.g., `aip test` with failed test cases), for the process exit code
+	failed: Arc<AtomicBool>,
+
 	/// NOT USED YET
 	#[allow(unused)]
 	run_queue_tx: RunQueueTx,
@@ -91,6 +100,7 @@ impl Executor {
 			current_redo_ctx: Default::default(),
 			active_actions: Arc::new(AtomicUsize::new(0)),
 			cancel_trx: Some(cancel_trx),
+			failed: Arc::new(AtomicBool::new(false)),
 			run_queue_tx,
 		}
 	}
@@ -102,6 +112,11 @@ impl Executor {
 		self.action_sender.clone()
 	}
 
+	/// The flag set when a CLI command failed (to be read once the UI is done)
+	pub fn failed_flag(&self) -> Arc<AtomicBool> {
+		self.failed.clone()
+	}
+
 	/// Return the latest agent file_path that was executed
 	async fn get_agent_file_path(&self) -> Option<SPath> {
 		let redo_ctx = self.current_redo_ctx.lock().await;
@@ -207,16 +222,7 @@ impl Executor {
 			}
 
 			ExecActionEvent::CmdNew(new_args) => {
-				if let Err(err) = exec_new(new_args, init_wks(None, false).await?).await {
-					if matches!(err, Error::UserInterrupted) {
-						hub.publish(HubEvent::InfoShort("New agent creation cancelled by user".into()))
-							.await;
-						hub.publish(HubEvent::Quit).await;
-					} else {
-						return Err(err);
-					}
-				}
-				hub.publish(HubEvent::Quit).await;
+				exec_new(new_args, init_base_and_dir_context(false).await?).await?;
 			}
 
 			ExecActionEvent::CmdList(list_args) => {
@@ -233,6 +239,62 @@ impl Executor {
 				exec_unpack(init_base_and_dir_context(false).await?, unpack_args).await?;
 			}
 
+			ExecActionEvent::CmdHistory(args) => {
+				// Only needs the persisted runtime db
+				exec_history(args).await?;
+			}
+
+			ExecActionEvent::CmdShow(args) => {
+				exec_show(args).await?;
+			}
+
+			ExecActionEvent::CmdReplay(args) => {
+				hub.publish(ExecStatusEvent::RunStart).await;
+				init_base(false).await?;
+
+				let dir_ctx = init_wks(None, false).await?;
+				let mm = self.once_mm.get().await?;
+				let runtime = Runtime::new(dir_ctx, self.sender(), mm, self.cancel_trx.clone()).await?;
+
+				if let Err(err) = exec_replay(args, runtime).await {
+					hub.publish(err).await;
+				}
+
+				hub.publish(ExecStatusEvent::RunEnd).await;
+			}
+
+			ExecActionEvent::CmdTest(args) => {
+				hub.publish(ExecStatusEvent::RunStart).await;
+				init_base(false).await?;
+
+				let dir_ctx = init_wks(None, false).await?;
+				let mm = self.once_mm.get().await?;
+				let runtime = Runtime::new(dir_ctx, self.sender(), mm, self.cancel_trx.clone()).await?;
+
+				if let Err(err) = exec_test(args, runtime).await {
+					self.failed.store(true, Ordering::SeqCst);
+					hub.publish(err).await;
+				}
+
+				hub.publish(ExecStatusEvent::RunEnd).await;
+			}
+
+			ExecActionEvent::CmdCheck(args) => {
+				hub.publish(ExecStatusEvent::RunStart).await;
+				init_base(false).await?;
+
+				let dir_ctx = init_wks(None, false).await?;
+				let mm = self.once_mm.get().await?;
+				let runtime = Runtime::new(dir_ctx, self.sender(), mm, self.cancel_trx.clone()).await?;
+
+				if let Err(err) = exec_check(args, runtime).await {
+					self.failed.store(true, Ordering::SeqCst);
+					hub.publish(err).await;
+				}
+
+				hub.publish(ExecStatusEvent::RunEnd).await;
+			}
+
 			ExecActionEvent::CmdCheckKeys(args) => {
 				// Does not require dir_context or runtime
 				exec_check_keys(args).await?;
@@ -289,6 +351,7 @@ impl Executor {
 				let agent_res = find_agent(&agent_name, &runtime, None);
 
 				match agent_res {
+					Ok(agent) if run_args.help_agent => exec_help_agent(&agent).await,
 					Ok(_agent) => {
 						let (redo_ctx, redo_requested) = exec_run(run_args, runtime).await?;
 						self.set_current_redo_ctx(redo_ctx).await;
@@ -358,7 +421,7 @@ impl Executor {
 			}
 
 			ExecActionEvent::RunSubAgent(run_agent_params) => {
-				if let Err(err) = exec_run_sub_agent(run_agent_params).await {
+				if let Err(err) = exec_run_sub_agent(*run_agent_params).await {
 					hub.publish(Error::cc("Fail to run agent", err)).await;
 				}
 			}
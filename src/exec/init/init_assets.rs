@@ -1,5 +1,6 @@
-use crate::Result;
 use crate::exec::assets::{self, ZFile};
+use crate::{Error, Result};
+use aho_corasick::AhoCorasick;
 
 // region:    --- Workspace ZFiles
 pub fn extract_workspace_config_toml_zfile() -> Result<ZFile> {
@@ -27,6 +28,49 @@ pub fn extract_template_pack_toml_zfile() -> Result<ZFile> {
 	assets::extract_template_zfile("pack.toml")
 }
 
+/// Returns the template `pack.toml` content with the pack name (the `DIR_NAME` placeholder)
+pub fn render_template_pack_toml(pack_name: &str) -> Result<String> {
+	let pack_toml_zfile = extract_template_pack_toml_zfile()?;
+	let content =
+		String::from_utf8(pack_toml_zfile.content).map_err(|_| Error::custom("template pack.toml is not UTF8 ??"))?;
+
+	// Replace DIR_NAME with actual pack name using aho-corasick
+	let patterns = &["DIR_NAME"];
+	let replacements = &[pack_name];
+	let ac =
+		AhoCorasick::new(patterns).map_err(|err| Error::custom(format!("AhoCorasick pattern fail.\nCause: {err}")))?;
+
+	Ok(ac.replace_all(&content, replacements))
+}
+
+/// List the built-in agent template names (the `_template/agent-NAME/` dirs)
+pub fn list_template_agent_names() -> Result<Vec<String>> {
+	let mut names: Vec<String> = assets::list_file_paths_start_with("_template", "agent-")?
+		.into_iter()
+		.filter_map(|path| {
+			let (dir, _) = path.split_once('/')?;
+			dir.strip_prefix("agent-").map(|name| name.to_string())
+		})
+		.collect();
+	names.sort();
+	names.dedup();
+	Ok(names)
+}
+
+/// List the file paths of a built-in agent template (relative to the template dir)
+pub fn list_template_agent_file_paths(name: &str) -> Result<Vec<String>> {
+	let prefix = format!("agent-{name}/");
+	let paths = assets::list_file_paths_start_with("_template", &prefix)?
+		.into_iter()
+		.filter_map(|path| path.strip_prefix(&prefix).map(|p| p.to_string()))
+		.collect();
+	Ok(paths)
+}
+
+pub fn extract_template_agent_zfile(name: &str, path: &str) -> Result<ZFile> {
+	assets::extract_template_zfile(&format!("agent-{name}/{path}"))
+}
+
 // endregion: --- Template ZFiles
 
 // region:    --- Base ZFiles
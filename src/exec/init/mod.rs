@@ -4,7 +4,10 @@ mod init_assets;
 mod init_base;
 mod init_wks;
 
-pub use init_assets::{extract_setup_aip_env_sh_zfile, extract_template_pack_toml_zfile};
+pub use init_assets::{
+	extract_setup_aip_env_sh_zfile, extract_template_agent_zfile, list_template_agent_file_paths,
+	list_template_agent_names, render_template_pack_toml,
+};
 pub use init_base::*;
 pub use init_wks::*;
 
@@ -5,13 +5,18 @@ mod support;
 
 mod event_action;
 mod event_status;
+mod exec_cmd_check;
 mod exec_cmd_check_keys;
 mod exec_cmd_create_gitignore;
+mod exec_cmd_history;
 mod exec_cmd_install;
 mod exec_cmd_list;
 mod exec_cmd_new;
 mod exec_cmd_pack;
+mod exec_cmd_replay;
 mod exec_cmd_run;
+mod exec_cmd_show;
+mod exec_cmd_test;
 mod exec_cmd_unpack;
 mod exec_cmd_xelf;
 mod exec_sub_agent;
@@ -19,13 +24,19 @@ mod executor;
 
 pub use event_action::*;
 pub use event_status::*;
+use exec_cmd_check::*;
 use exec_cmd_check_keys::*;
 use exec_cmd_create_gitignore::*;
+use exec_cmd_history::*;
 use exec_cmd_install::*;
 use exec_cmd_list::*;
 use exec_cmd_new::*;
 use exec_cmd_pack::*;
+use exec_cmd_replay::*;
 use exec_cmd_run::*;
+pub use exec_cmd_show::RunReport;
+use exec_cmd_show::*;
+use exec_cmd_test::*;
 use exec_cmd_unpack::*;
 use exec_cmd_xelf::*;
 #[allow(unused)]
@@ -1,5 +1,9 @@
-use crate::Error;
+use crate::dir_context::{DirContext, find_existing_rt_db_file, find_to_run_pack_dir};
 use crate::hub::get_hub;
+use crate::model::ModelManager;
+use crate::types::{PackRef, looks_like_pack_ref};
+use crate::{Error, Result};
+use simple_fs::SPath;
 use std::collections::HashSet;
 use std::path::Path;
 use std::process::Command;
@@ -67,3 +71,30 @@ pub async fn open_vscode(path: impl AsRef<Path>) {
 		}
 	}
 }
+
+/// Open the persisted runtime db of the workspace (used by `aip history`, `aip show`, ...)
+///
+/// NOTE: This opens the db file even if `persist_db` is now off, as long as the file exists.
+pub async fn open_persisted_mm() -> Result<ModelManager> {
+	let Some(db_file) = find_existing_rt_db_file()? else {
+		return Err(Error::RunHistoryNotFound);
+	};
+	let mm = ModelManager::new_with_db_file(&db_file).await?;
+	Ok(mm)
+}
+
+/// The pack dir from a pack ref (e.g., `ns@pack`) or a directory path
+/// (for the `aip test` and `aip check` targets)
+pub fn resolve_pack_dir(dir_context: &DirContext, target: &str) -> Result<SPath> {
+	let target_path = SPath::new(target);
+	if looks_like_pack_ref(&target_path) {
+		let pack_ref: PackRef = target.parse()?;
+		Ok(find_to_run_pack_dir(dir_context, &pack_ref)?.path)
+	} else if target_path.is_dir() {
+		Ok(target_path)
+	} else {
+		Err(Error::custom(format!(
+			"Target '{target}' is not a pack ref (e.g., 'ns@pack') or a pack directory"
+		)))
+	}
+}
@@ -0,0 +1,39 @@
+use crate::model::Id;
+use std::sync::Arc;
+
+/// A streamed AI response event of a task (published while the AI responds, for the live task view)
+#[derive(Debug, Clone)]
+pub struct AiStreamEvent {
+	pub task_id: Id,
+	pub kind: AiStreamKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum AiStreamKind {
+	/// The AI call started (resets the streamed content, e.g., on retry or fallback)
+	Start,
+	/// A chunk of the response content
+	Content(Arc<str>),
+	/// A chunk of the reasoning content
+	Reasoning(Arc<str>),
+	/// The AI call ended (successfully or not)
+	End,
+}
+
+impl AiStreamEvent {
+	/// Append the chunk of the other event if both are chunks of the same kind for the same task.
+	/// Returns false if not merged.
+	pub fn merge(&mut self, other: &AiStreamEvent) -> bool {
+		if self.task_id != other.task_id {
+			return false;
+		}
+		match (&mut self.kind, &other.kind) {
+			(AiStreamKind::Content(chunk), AiStreamKind::Content(other_chunk))
+			| (AiStreamKind::Reasoning(chunk), AiStreamKind::Reasoning(other_chunk)) => {
+				*chunk = format!("{chunk}{other_chunk}").into();
+				true
+			}
+			_ => false,
+		}
+	}
+}
@@ -1,5 +1,6 @@
 use crate::Error;
 use crate::exec::ExecStatusEvent;
+use crate::hub::AiStreamEvent;
 use crate::model::ModelEvent;
 use crate::tui_v1::{PrintEvent, PromptParams};
 use derive_more::derive::From;
@@ -39,6 +40,10 @@ pub enum HubEvent {
 	#[from]
 	Model(ModelEvent),
 
+	// -- The streamed AI response chunks (for the live task view)
+	#[from]
+	AiStream(AiStreamEvent),
+
 	// Used to ping the tui2 AppEvent to refresh
 	RtModelChange,
 
@@ -2,6 +2,7 @@ use crate::event::{Rx, Tx, new_channel};
 use crate::hub::hub_event::HubEvent;
 use crate::{Error, Result};
 use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, LazyLock, Mutex};
 
 /// Hub for receiving and broadcasting all OutEvent to the systems.
@@ -9,6 +10,8 @@ use std::sync::{Arc, LazyLock, Mutex};
 pub struct Hub {
 	tx: Tx<HubEvent>,
 	rx_holder: Arc<Mutex<Option<Rx<HubEvent>>>>,
+	/// Set when a UI consumes the `AiStream` events (the TUI), otherwise the AI calls are not streamed
+	ai_stream_consumer: AtomicBool,
 }
 
 /// Core Hub Methods
@@ -18,7 +21,11 @@ impl Hub {
 
 		let rx_holder = Mutex::new(Some(rx)).into();
 
-		Self { tx, rx_holder }
+		Self {
+			tx,
+			rx_holder,
+			ai_stream_consumer: AtomicBool::new(false),
+		}
 	}
 
 	pub fn take_rx(&self) -> Result<Rx<HubEvent>> {
@@ -32,6 +39,17 @@ impl Hub {
 	}
 }
 
+/// AI Stream consumer
+impl Hub {
+	pub fn set_ai_stream_consumer(&self, consumer: bool) {
+		self.ai_stream_consumer.store(consumer, Ordering::Relaxed);
+	}
+
+	pub fn has_ai_stream_consumer(&self) -> bool {
+		self.ai_stream_consumer.load(Ordering::Relaxed)
+	}
+}
+
 /// Publish event
 impl Hub {
 	pub async fn publish(&self, event: impl Into<HubEvent>) {
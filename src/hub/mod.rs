@@ -1,9 +1,11 @@
 // region:    --- Modules
 
+pub mod ai_stream_event;
 pub mod helpers;
 pub mod hub_event;
 pub mod hub_impl;
 
+pub use ai_stream_event::*;
 pub use helpers::*;
 pub use hub_event::*;
 pub use hub_impl::*;
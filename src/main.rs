@@ -20,6 +20,7 @@ mod types;
 #[cfg(test)]
 mod _test_support;
 
+use crate::dir_context::find_rt_db_file;
 use crate::exec::Executor;
 use crate::exec::cli::CliArgs;
 use crate::hub::{HubEvent, get_hub};
@@ -28,6 +29,8 @@ use crate::tui_v1::TuiAppV1;
 use clap::{Parser, crate_version};
 use derive_aliases::*;
 use error::{Error, Result};
+use std::process::ExitCode;
+use std::sync::atomic::Ordering;
 use tracing_appender::rolling::never;
 use tracing_subscriber::EnvFilter;
 
@@ -38,7 +41,7 @@ pub static VERSION: &str = crate_version!();
 const DEBUG_LOG: bool = false;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
 	// -- Command arguments
 	let args = CliArgs::parse(); // Will fail early, but that’s okay.
 
@@ -64,11 +67,14 @@ async fn main() -> Result<()> {
 
 	// -- The OnceModelManager
 	// This way, ModelManager is only created when needed
-	let once_mm = OnceModelManager;
+	// NOTE: Persisted in the workspace when `[runtime] persist_db = true`
+	let rt_db_file = find_rt_db_file()?;
+	let once_mm = OnceModelManager::new(rt_db_file);
 
 	// -- Start executor
-	let executor = Executor::new(once_mm);
+	let executor = Executor::new(once_mm.clone());
 	let exec_tx = executor.sender();
+	let exec_failed = executor.failed_flag();
 
 	// TODO: Probably want to move the spawn inside executor.start
 	tokio::spawn(async move {
@@ -87,7 +93,10 @@ async fn main() -> Result<()> {
 		let mm = once_mm.get().await?;
 		tui::start_tui(mm, exec_tx, args).await?;
 	} else {
-		let tui_v1 = TuiAppV1::new(exec_tx);
+		let mut tui_v1 = TuiAppV1::new(exec_tx);
+		if args.cmd.is_ndjson() {
+			tui_v1 = tui_v1.with_ndjson(once_mm.get().await?);
+		}
 		// This will wait until all done
 		tui_v1.start_with_args(args).await?;
 	}
@@ -100,5 +109,10 @@ async fn main() -> Result<()> {
 	tokio::time::sleep(std::time::Duration::from_millis(100)).await;
 	//println!("\n---- Until next time, happy coding! ----");
 
-	Ok(())
+	// NOTE: A failed command (e.g., `aip test` with failed test cases) exits with a non-zero status (e.g., for CI)
+	if exec_failed.load(Ordering::SeqCst) {
+		Ok(ExitCode::FAILURE)
+	} else {
+		Ok(ExitCode::SUCCESS)
+	}
 }
@@ -1,13 +1,17 @@
-use crate::model::Result;
-use crate::model::db::rt_db_setup::recreate_db;
+use crate::model::db::rt_db_setup::migrate_db;
+use crate::model::{Error, Result};
 use modql::SqliteFromRow;
 use rusqlite::types::FromSql;
 use rusqlite::{Connection, OptionalExtension, Params};
+use simple_fs::{SPath, ensure_dir};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub struct Db {
 	con: Arc<Mutex<Connection>>,
+	/// The sqlite file when persisted on disk (None when in-memory)
+	file: Option<SPath>,
 }
 
 pub struct DbTx<'a> {
@@ -17,16 +21,36 @@ pub struct DbTx<'a> {
 /// Constructor & Setup
 impl Db {
 	pub fn new() -> Result<Self> {
-		// let con = Connection::open(".mock-db.sqlite")?;
 		let con = Connection::open_in_memory()?;
 		let con = Arc::new(Mutex::new(con));
 
-		Ok(Self { con })
+		Ok(Self { con, file: None })
 	}
 
-	pub fn recreate(&self) -> Result<()> {
+	/// Open (or create) the on-disk sqlite file.
+	///
+	/// NOTE: Multiple `aip` processes might share the same file, hence the WAL mode and busy timeout.
+	pub fn new_with_file(file: &SPath) -> Result<Self> {
+		if let Some(parent_dir) = file.parent() {
+			ensure_dir(&parent_dir).map_err(|err| Error::cc(format!("Cannot create db dir '{parent_dir}'"), err))?;
+		}
+
+		let con = Connection::open(file.std_path())?;
+		con.busy_timeout(Duration::from_secs(5))?;
+		con.pragma_update(None, "journal_mode", "WAL")?;
+		con.pragma_update(None, "synchronous", "NORMAL")?;
+		let con = Arc::new(Mutex::new(con));
+
+		Ok(Self {
+			con,
+			file: Some(file.clone()),
+		})
+	}
+
+	/// Create or upgrade the schema to the latest version.
+	pub fn migrate(&self) -> Result<()> {
 		let con = self.con.lock()?;
-		recreate_db(&con)?;
+		migrate_db(&con)?;
 		Ok(())
 	}
 
@@ -51,6 +75,17 @@ impl Db {
 	}
 }
 
+/// Getters
+impl Db {
+	pub fn file(&self) -> Option<&SPath> {
+		self.file.as_ref()
+	}
+
+	pub fn is_persistent(&self) -> bool {
+		self.file.is_some()
+	}
+}
+
 // Executors
 impl Db {
 	/// Execute a parameterized sql with its params, and return the number of rows affected
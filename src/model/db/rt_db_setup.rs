@@ -1,8 +1,11 @@
-use crate::model::Result;
+use crate::model::{Error, Result};
 use rusqlite::Connection;
 
 // Some notes:
-// - Currently, the database is in-memory only, but it may become persistent at the session level.
+// - By default, the database is in-memory only. When `[runtime] persist_db = true`,
+//   it is persisted in the workspace `.aipack/.session/rt-db.sqlite` file.
+// - The schema version is stored in the sqlite `user_version` pragma, and `MIGRATIONS` are applied in order.
+//   Once released, a migration must never be changed. Schema changes must be a new migration.
 // - All tables have an `id` used for same-db joins, and a `uid` which is a UUID blob,
 //   intended for sharing outside of Rust or across databases.
 // - `id` uses `AUTOINCREMENT` to ensure IDs are not reused if a row is deleted.
@@ -11,11 +14,53 @@ use rusqlite::Connection;
 //    - A future strategy could involve having a set of content tables per run, using the b58 run.uid suffix. This would make it very fast to clean up old ones.
 // - References between these two sets of tables are by `uid`, as they may eventually reside in different databases.
 
-pub fn recreate_db(con: &Connection) -> Result<()> {
-	create_schema(con)?;
+/// The latest schema version (must match the last `MIGRATIONS` version)
+pub const SCHEMA_VERSION: i64 = 8;
+
+type MigrationFn = fn(&Connection) -> Result<()>;
+
+/// The ordered list of `(version, migration_fn)`.
+const MIGRATIONS: &[(i64, MigrationFn)] = &[
+	//
+	(1, create_schema),
+	(2, migrate_v2_run_replay),
+	(3, migrate_v3_run_resume),
+	(4, migrate_v4_task_ai_cached),
+	(5, migrate_v5_run_ai_cost),
+	(6, migrate_v6_run_params),
+	(7, migrate_v7_run_options),
+	(8, migrate_v8_run_after_all),
+];
+
+/// Create or upgrade the database schema to the `SCHEMA_VERSION`.
+/// Each migration is executed in its own transaction, along with its `user_version` update.
+pub fn migrate_db(con: &Connection) -> Result<()> {
+	let db_version = get_schema_version(con)?;
+
+	if db_version > SCHEMA_VERSION {
+		return Err(Error::custom(format!(
+			"Runtime db schema version {db_version} is newer than this aip version supports ({SCHEMA_VERSION}). Update aip or delete the runtime db file."
+		)));
+	}
+
+	for (version, migration_fn) in MIGRATIONS {
+		if *version <= db_version {
+			continue;
+		}
+		let tx = con.unchecked_transaction()?;
+		migration_fn(&tx)?;
+		tx.pragma_update(None, "user_version", version)?;
+		tx.commit()?;
+	}
+
 	Ok(())
 }
 
+pub fn get_schema_version(con: &Connection) -> Result<i64> {
+	let version = con.pragma_query_value(None, "user_version", |r| r.get::<_, i64>(0))?;
+	Ok(version)
+}
+
 // region:    --- Main Tables
 
 const LOOP_TABLE: (&str, &str) = (
@@ -390,4 +435,110 @@ CREATE INDEX IF NOT EXISTS idx_loop_last_run_id ON loop(last_run_id);
 	Ok(())
 }
 
+/// Add the run `before_all_ucontent_id` (before all value, for replay) and `replay_of_id` (the replayed run)
+fn migrate_v2_run_replay(con: &Connection) -> Result<()> {
+	con.execute("ALTER TABLE run ADD COLUMN before_all_ucontent_id INTEGER", ())?;
+	con.execute("ALTER TABLE run ADD COLUMN replay_of_id INTEGER", ())?;
+
+	Ok(())
+}
+
+/// Add the run `resume_of_id` (the resumed run)
+fn migrate_v3_run_resume(con: &Connection) -> Result<()> {
+	con.execute("ALTER TABLE run ADD COLUMN resume_of_id INTEGER", ())?;
+
+	Ok(())
+}
+
+/// Add the task `ai_cached` (the AI response was served from the responses cache)
+fn migrate_v4_task_ai_cached(con: &Connection) -> Result<()> {
+	con.execute("ALTER TABLE task ADD COLUMN ai_cached INTEGER", ())?;
+
+	Ok(())
+}
+
+/// Add the run `ai_cost` (the cost of the AI calls outside of the tasks, e.g., `aip.ai.chat` in the Before All)
+fn migrate_v5_run_ai_cost(con: &Connection) -> Result<()> {
+	con.execute("ALTER TABLE run ADD COLUMN ai_cost REAL", ())?;
+
+	Ok(())
+}
+
+/// Add the run `params_ucontent_id` (the run params, for replay)
+fn migrate_v6_run_params(con: &Connection) -> Result<()> {
+	con.execute("ALTER TABLE run ADD COLUMN params_ucontent_id INTEGER", ())?;
+
+	Ok(())
+}
+
+/// Add the run `options_ucontent_id` (the agent options overridden by the Before All, for resume)
+fn migrate_v7_run_options(con: &Connection) -> Result<()> {
+	con.execute("ALTER TABLE run ADD COLUMN options_ucontent_id INTEGER", ())?;
+
+	Ok(())
+}
+
+/// Add the run `after_all_ucontent_id` (the After All value, e.g., for the NDJSON run end)
+fn migrate_v8_run_after_all(con: &Connection) -> Result<()> {
+	con.execute("ALTER TABLE run ADD COLUMN after_all_ucontent_id INTEGER", ())?;
+
+	Ok(())
+}
+
 // endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_rt_db_setup_migrate_db_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let con = Connection::open_in_memory()?;
+
+		// -- Exec
+		migrate_db(&con)?;
+		// second time should be a no-op
+		migrate_db(&con)?;
+
+		// -- Check
+		assert_eq!(get_schema_version(&con)?, SCHEMA_VERSION);
+		let count: i64 = con.query_row("SELECT count(*) FROM run", [], |r| r.get(0))?;
+		assert_eq!(count, 0);
+		let count: i64 = con.query_row("SELECT count(replay_of_id) FROM run", [], |r| r.get(0))?;
+		assert_eq!(count, 0);
+		let count: i64 = con.query_row("SELECT count(ai_cached) FROM task", [], |r| r.get(0))?;
+		assert_eq!(count, 0);
+		let count: i64 = con.query_row("SELECT count(ai_cost) FROM run", [], |r| r.get(0))?;
+		assert_eq!(count, 0);
+		let count: i64 = con.query_row("SELECT count(params_ucontent_id) FROM run", [], |r| r.get(0))?;
+		assert_eq!(count, 0);
+		let count: i64 = con.query_row("SELECT count(options_ucontent_id) FROM run", [], |r| r.get(0))?;
+		assert_eq!(count, 0);
+		let count: i64 = con.query_row("SELECT count(after_all_ucontent_id) FROM run", [], |r| r.get(0))?;
+		assert_eq!(count, 0);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_rt_db_setup_migrate_db_newer_version_err() -> Result<()> {
+		// -- Setup & Fixtures
+		let con = Connection::open_in_memory()?;
+		con.pragma_update(None, "user_version", SCHEMA_VERSION + 1)?;
+
+		// -- Exec
+		let res = migrate_db(&con);
+
+		// -- Check
+		assert!(res.is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
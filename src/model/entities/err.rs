@@ -94,6 +94,20 @@ impl ErrBmc {
 		base::get::<Self, _>(mm, id)
 	}
 
+	/// List all errors of a run (including the task errors), in creation order
+	pub fn list_for_run(mm: &ModelManager, run_id: Id) -> Result<Vec<ErrRec>> {
+		let sql = format!(
+			"SELECT {} FROM {} WHERE run_id = ? ORDER BY id",
+			ErrRec::sql_columns(),
+			Self::table_ref(),
+		);
+
+		let db = mm.db();
+		let entities: Vec<ErrRec> = db.fetch_all(&sql, (run_id,))?;
+
+		Ok(entities)
+	}
+
 	/// Used by the TUI to get system err (not from run or task)
 	pub fn first_system_err(mm: &ModelManager) -> Result<Option<ErrRec>> {
 		let sql = format!(
@@ -175,6 +189,40 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn test_model_err_bmc_list_for_run() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = ModelManager::new().await?;
+		let run_1_id = create_run(&mm, "run-1").await?;
+		let run_2_id = create_run(&mm, "run-2").await?;
+		let task_id = create_task(&mm, run_1_id, 1).await?;
+		for (run_id, task_id, content) in [
+			(run_1_id, None, "run-1 err"),
+			(run_2_id, None, "run-2 err"),
+			(run_1_id, Some(task_id), "run-1 task err"),
+		] {
+			ErrBmc::create(
+				&mm,
+				ErrForCreate {
+					stage: None,
+					run_id: Some(run_id),
+					task_id,
+					typ: Some(ContentTyp::Text),
+					content: Some(content.to_string()),
+				},
+			)?;
+		}
+
+		// -- Exec
+		let errs = ErrBmc::list_for_run(&mm, run_1_id)?;
+
+		// -- Check
+		let contents: Vec<&str> = errs.iter().filter_map(|e| e.content.as_deref()).collect();
+		assert_eq!(contents, vec!["run-1 err", "run-1 task err"]);
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_model_err_bmc_update() -> Result<()> {
 		// -- Setup & Fixtures
@@ -1,8 +1,9 @@
 use crate::model::base::{self, DbBmc};
-use crate::model::{EndState, EntityType, EpochUs, Id, ModelManager, Result, RunningState, Stage};
+use crate::model::{EndState, EntityType, EpochUs, Error, Id, ModelManager, Result, RunningState, Stage, UcontentBmc};
 use modql::SqliteFromRow;
 use modql::field::{Fields, HasSqliteFields};
 use modql::filter::ListOptions;
+use serde_json::{Map, Value};
 use uuid::Uuid;
 
 // region:    --- Types
@@ -50,8 +51,19 @@ pub struct Run {
 	pub concurrency: Option<i32>,
 
 	pub total_cost: Option<f64>,
+	/// The cost of the AI calls outside of the tasks (included in the total_cost)
+	pub ai_cost: Option<f64>,
 	pub total_task_ms: Option<i64>,
 	pub flow_redo_count: Option<i32>,
+	/// The After All value (e.g., for the `end` run step of the NDJSON output)
+	pub after_all_ucontent_id: Option<Id>,
+
+	// -- Replay & Resume
+	pub before_all_ucontent_id: Option<Id>,
+	pub params_ucontent_id: Option<Id>,
+	pub options_ucontent_id: Option<Id>,
+	pub replay_of_id: Option<Id>,
+	pub resume_of_id: Option<Id>,
 }
 
 #[derive(Debug, Clone, Fields, SqliteFromRow)]
@@ -66,7 +78,6 @@ impl Run {
 		self.end.is_some()
 	}
 
-	#[allow(unused)]
 	pub fn duration_us(&self) -> Option<i64> {
 		if let (Some(start), Some(end)) = (self.start, self.end) {
 			Some(end.as_i64() - start.as_i64())
@@ -137,8 +148,19 @@ pub struct RunForUpdate {
 	pub concurrency: Option<i32>,
 
 	pub total_cost: Option<f64>,
+	/// The cost of the AI calls outside of the tasks (included in the total_cost)
+	pub ai_cost: Option<f64>,
 	pub total_task_ms: Option<i64>,
 	pub flow_redo_count: Option<i32>,
+	/// The After All value (e.g., for the `end` run step of the NDJSON output)
+	pub after_all_ucontent_id: Option<Id>,
+
+	// -- Replay & Resume
+	pub before_all_ucontent_id: Option<Id>,
+	pub params_ucontent_id: Option<Id>,
+	pub options_ucontent_id: Option<Id>,
+	pub replay_of_id: Option<Id>,
+	pub resume_of_id: Option<Id>,
 }
 
 // endregion: --- Types
@@ -181,11 +203,14 @@ impl RunBmc {
 		base::update::<Self>(mm, id, fields)
 	}
 
-	#[allow(unused)]
 	pub fn get(mm: &ModelManager, id: Id) -> Result<Run> {
 		base::get::<Self, _>(mm, id)
 	}
 
+	pub fn get_by_uid(mm: &ModelManager, uid: Uuid) -> Result<Run> {
+		base::get_by_uid::<Self, _>(mm, uid)
+	}
+
 	pub fn list(mm: &ModelManager, list_options: Option<ListOptions>) -> Result<Vec<Run>> {
 		base::list::<Self, _>(mm, list_options, None)
 	}
@@ -218,6 +243,126 @@ impl RunBmc {
 		Self::list(mm, Some(options))
 	}
 
+	/// Store the before all value of this run (e.g., to be reused by `aip replay`)
+	/// NOTE: A null before all is not stored.
+	pub fn set_before_all(mm: &ModelManager, run_id: Id, before_all: &Value) -> Result<()> {
+		if before_all.is_null() {
+			return Ok(());
+		}
+		let content = serde_json::to_string(before_all).map_err(|err| Error::cc("Cannot serialize before all", err))?;
+		let ucontent_id = UcontentBmc::get_or_create_for_text(mm, &content, true)?;
+
+		let run_u = RunForUpdate {
+			before_all_ucontent_id: Some(ucontent_id),
+			..Default::default()
+		};
+		Self::update(mm, run_id, run_u)?;
+
+		Ok(())
+	}
+
+	/// Get the stored before all value of this run (Value::Null if none)
+	pub fn get_before_all(mm: &ModelManager, run: &Run) -> Result<Value> {
+		let Some(ucontent_id) = run.before_all_ucontent_id else {
+			return Ok(Value::Null);
+		};
+		let Some(content) = UcontentBmc::get_content(mm, ucontent_id)? else {
+			return Ok(Value::Null);
+		};
+		let value = serde_json::from_str(&content).map_err(|err| Error::cc("Cannot parse stored before all", err))?;
+
+		Ok(value)
+	}
+
+	/// Store the After All value of this run (e.g., for the `end` run step of the NDJSON output)
+	/// NOTE: A null after all is not stored.
+	pub fn set_after_all(mm: &ModelManager, run_id: Id, after_all: &Value) -> Result<()> {
+		if after_all.is_null() {
+			return Ok(());
+		}
+		let content = serde_json::to_string(after_all).map_err(|err| Error::cc("Cannot serialize after all", err))?;
+		let ucontent_id = UcontentBmc::get_or_create_for_text(mm, &content, true)?;
+
+		let run_u = RunForUpdate {
+			after_all_ucontent_id: Some(ucontent_id),
+			..Default::default()
+		};
+		Self::update(mm, run_id, run_u)?;
+
+		Ok(())
+	}
+
+	/// Get the stored After All value of this run (Value::Null if none)
+	pub fn get_after_all(mm: &ModelManager, run: &Run) -> Result<Value> {
+		let Some(ucontent_id) = run.after_all_ucontent_id else {
+			return Ok(Value::Null);
+		};
+		let Some(content) = UcontentBmc::get_content(mm, ucontent_id)? else {
+			return Ok(Value::Null);
+		};
+		let value = serde_json::from_str(&content).map_err(|err| Error::cc("Cannot parse stored after all", err))?;
+
+		Ok(value)
+	}
+
+	/// Store the params of this run (e.g., to be reused by `aip replay`)
+	/// NOTE: Empty params are not stored.
+	pub fn set_params(mm: &ModelManager, run_id: Id, params: &Map<String, Value>) -> Result<()> {
+		if params.is_empty() {
+			return Ok(());
+		}
+		let content = serde_json::to_string(params).map_err(|err| Error::cc("Cannot serialize params", err))?;
+		let ucontent_id = UcontentBmc::get_or_create_for_text(mm, &content, true)?;
+
+		let run_u = RunForUpdate {
+			params_ucontent_id: Some(ucontent_id),
+			..Default::default()
+		};
+		Self::update(mm, run_id, run_u)?;
+
+		Ok(())
+	}
+
+	/// Get the stored params of this run (empty if none)
+	pub fn get_params(mm: &ModelManager, run: &Run) -> Result<Map<String, Value>> {
+		let Some(ucontent_id) = run.params_ucontent_id else {
+			return Ok(Map::new());
+		};
+		let Some(content) = UcontentBmc::get_content(mm, ucontent_id)? else {
+			return Ok(Map::new());
+		};
+		let params = serde_json::from_str(&content).map_err(|err| Error::cc("Cannot parse stored params", err))?;
+
+		Ok(params)
+	}
+
+	/// Store the agent options of this run, when overridden by the Before All (e.g., to be reused by `aip run --resume`)
+	pub fn set_options(mm: &ModelManager, run_id: Id, options: &Value) -> Result<()> {
+		let content = serde_json::to_string(options).map_err(|err| Error::cc("Cannot serialize options", err))?;
+		let ucontent_id = UcontentBmc::get_or_create_for_text(mm, &content, true)?;
+
+		let run_u = RunForUpdate {
+			options_ucontent_id: Some(ucontent_id),
+			..Default::default()
+		};
+		Self::update(mm, run_id, run_u)?;
+
+		Ok(())
+	}
+
+	/// Get the stored agent options of this run (None if not overridden)
+	pub fn get_options(mm: &ModelManager, run: &Run) -> Result<Option<Value>> {
+		let Some(ucontent_id) = run.options_ucontent_id else {
+			return Ok(None);
+		};
+		let Some(content) = UcontentBmc::get_content(mm, ucontent_id)? else {
+			return Ok(None);
+		};
+		let options = serde_json::from_str(&content).map_err(|err| Error::cc("Cannot parse stored options", err))?;
+
+		Ok(Some(options))
+	}
+
 	/// Create the ErrRec and assign it to this run, and set the end state
 	/// NOTE:
 	///   - This does not set the end time (just the end_state)
@@ -383,6 +528,85 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn test_model_run_bmc_set_get_before_all() -> Result<()> {
+		// -- Fixture
+		let mm = ModelManager::new().await?;
+		let run_c = RunForCreate {
+			parent_id: None,
+			agent_name: Some("Test Run".to_string()),
+			agent_path: Some("test/path".to_string()),
+			has_task_stages: None,
+			has_prompt_parts: None,
+		};
+		let id = RunBmc::create(&mm, run_c)?;
+		let before_all = serde_json::json!({"instruction": "Be concise", "files": ["a.md", "b.md"]});
+
+		// -- Exec
+		RunBmc::set_before_all(&mm, id, &before_all)?;
+
+		// -- Check
+		let run = RunBmc::get(&mm, id)?;
+		assert!(run.before_all_ucontent_id.is_some());
+		let stored = RunBmc::get_before_all(&mm, &run)?;
+		assert_eq!(stored, before_all);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_model_run_bmc_set_get_after_all() -> Result<()> {
+		// -- Fixture
+		let mm = ModelManager::new().await?;
+		let run_c = RunForCreate {
+			parent_id: None,
+			agent_name: Some("Test Run".to_string()),
+			agent_path: Some("test/path".to_string()),
+			has_task_stages: None,
+			has_prompt_parts: None,
+		};
+		let id = RunBmc::create(&mm, run_c)?;
+		let after_all = serde_json::json!({"summary": "All good", "count": 2});
+
+		// -- Exec
+		RunBmc::set_after_all(&mm, id, &after_all)?;
+
+		// -- Check
+		let run = RunBmc::get(&mm, id)?;
+		assert!(run.after_all_ucontent_id.is_some());
+		let stored = RunBmc::get_after_all(&mm, &run)?;
+		assert_eq!(stored, after_all);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_model_run_bmc_set_get_params() -> Result<()> {
+		// -- Fixture
+		let mm = ModelManager::new().await?;
+		let run_c = RunForCreate {
+			parent_id: None,
+			agent_name: Some("Test Run".to_string()),
+			agent_path: Some("test/path".to_string()),
+			has_task_stages: None,
+			has_prompt_parts: None,
+		};
+		let id = RunBmc::create(&mm, run_c)?;
+		let params = serde_json::json!({"lang": "fr", "max": 3});
+		let params = params.as_object().ok_or("Should be object")?;
+
+		// -- Exec
+		RunBmc::set_params(&mm, id, params)?;
+
+		// -- Check
+		let run = RunBmc::get(&mm, id)?;
+		assert!(run.params_ucontent_id.is_some());
+		let stored = RunBmc::get_params(&mm, &run)?;
+		assert_eq!(&stored, params);
+
+		Ok(())
+	}
 }
 
 // endregion: --- Tests
@@ -1,13 +1,14 @@
 use crate::hub::get_hub;
 use crate::model::base::{self, DbBmc};
 use crate::model::{
-	EndState, EntityAction, EntityType, EpochUs, Id, Inout, InoutBmc, InoutForCreate, InoutOnlyDisplay, ModelEvent,
-	ModelManager, RelIds, Result, RunningState, Stage, TypedContent,
+	ContentTyp, EndState, EntityAction, EntityType, EpochUs, Error, Id, Inout, InoutBmc, InoutForCreate,
+	InoutOnlyDisplay, ModelEvent, ModelManager, RelIds, Result, RunningState, Stage, TypedContent,
 };
 use crate::support::time::now_micro;
 use modql::SqliteFromRow;
 use modql::field::{Fields, HasSqliteFields, SqliteField};
 use modql::filter::ListOptions;
+use serde_json::Value;
 use uuid::Uuid;
 
 // region:    --- AiPrice
@@ -78,6 +79,9 @@ pub struct Task {
 	pub cost_cache_write: Option<f64>,
 	pub cost_cache_saving: Option<f64>,
 
+	/// The AI response was served from the responses cache (`cache_responses` option)
+	pub ai_cached: Option<bool>,
+
 	pub input_uid: Option<Uuid>,
 	pub input_short: Option<String>,
 	pub input_has_display: Option<bool>,
@@ -168,6 +172,9 @@ pub struct TaskForUpdate {
 	pub cost_cache_write: Option<f64>,
 	pub cost_cache_saving: Option<f64>,
 
+	/// The AI response was served from the responses cache (`cache_responses` option)
+	pub ai_cached: Option<bool>,
+
 	pub input_uid: Option<Uuid>,
 	pub input_short: Option<String>,
 	pub input_has_display: Option<bool>,
@@ -218,6 +225,23 @@ impl Task {
 		self.ai_running_state() == RunningState::Running
 	}
 
+	/// The stage in progress (the last started stage which is not ended), if any
+	pub fn current_stage(&self) -> Option<Stage> {
+		if self.output_start.is_some() {
+			return self.output_end.is_none().then_some(Stage::Output);
+		}
+		if self.ai_gen_start.is_some() && self.ai_gen_end.is_none() {
+			return Some(Stage::AiGen);
+		}
+		if self.ai_start.is_some() {
+			return self.ai_end.is_none().then_some(Stage::Ai);
+		}
+		if self.data_start.is_some() {
+			return self.data_end.is_none().then_some(Stage::Data);
+		}
+		None
+	}
+
 	pub fn is_skipped_before_ai(&self) -> bool {
 		if let Some(EndState::Skip) = self.end_state
 			&& self.ai_start.is_none()
@@ -564,11 +588,42 @@ impl TaskBmc {
 		}
 	}
 
+	/// Restore the original input value of this task (e.g., for `aip replay`)
+	pub fn get_input_value(mm: &ModelManager, task: &Task) -> Result<Value> {
+		Self::get_inout_value(mm, task.input_uid, task.input_short.as_deref())
+	}
+
+	/// Restore the original output value of this task (e.g., for `aip run --resume`)
+	pub fn get_output_value(mm: &ModelManager, task: &Task) -> Result<Value> {
+		Self::get_inout_value(mm, task.output_uid, task.output_short.as_deref())
+	}
+
+	/// NOTE: When no inout uid, the short is the full text content.
+	fn get_inout_value(mm: &ModelManager, inout_uid: Option<Uuid>, short: Option<&str>) -> Result<Value> {
+		let Some(inout_uid) = inout_uid else {
+			return Ok(short.map(|short| Value::String(short.to_string())).unwrap_or_default());
+		};
+
+		let inout = InoutBmc::get_by_uid::<Inout>(mm, inout_uid)?;
+		let Some(content) = inout.content else {
+			return Ok(Value::Null);
+		};
+
+		if inout.typ.as_deref() == Some(ContentTyp::Json.as_ref()) {
+			let value =
+				serde_json::from_str(&content).map_err(|err| Error::cc("Cannot parse stored task content", err))?;
+			Ok(value)
+		} else {
+			Ok(Value::String(content))
+		}
+	}
+
 	/// Update the input (called by create)
 	pub fn update_input(mm: &ModelManager, id: Id, input_content: TypedContent) -> Result<()> {
 		let task = TaskBmc::get(mm, id)?;
 
 		if let (Some(short), has_more) = input_content.extract_short() {
+			let has_more = store_full_content(&input_content, has_more);
 			// -- update the Task
 			// NOTE: Important, if no more than short content, do not set input_uid
 			let (input_uid, input_has_display) = if has_more {
@@ -618,6 +673,7 @@ impl TaskBmc {
 		let task = TaskBmc::get(mm, id)?;
 
 		if let (Some(short), has_more) = output_content.extract_short() {
+			let has_more = store_full_content(&output_content, has_more);
 			// -- update the Task
 			// NOTE: Important, if no more than short content, do not set input_uid
 			let (output_uid, output_has_display) = if has_more {
@@ -681,6 +737,7 @@ struct TaskInputPersistence {
 impl TaskInputPersistence {
 	fn from_typed_content(input_content: TypedContent) -> Self {
 		if let (Some(short), has_more) = input_content.extract_short() {
+			let has_more = store_full_content(&input_content, has_more);
 			let (input_uid, input_has_display) = if has_more {
 				(Some(input_content.uid), Some(input_content.display.is_some()))
 			} else {
@@ -710,6 +767,14 @@ impl TaskInputPersistence {
 	}
 }
 
+/// Returns true if the full content is stored in its inout record (not only the short)
+///
+/// NOTE: Besides a truncated short, a JSON content, or a content with a display, is stored in full,
+///       so that the original value can be restored (see `get_inout_value`, for `aip replay` and `--resume`).
+fn store_full_content(content: &TypedContent, has_more: bool) -> bool {
+	has_more || content.typ == ContentTyp::Json || (content.display.is_some() && content.content.is_some())
+}
+
 // endregion: --- Support Types
 
 // endregion: --- Bmc going to Content Model
@@ -1000,6 +1065,33 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn test_model_task_bmc_get_input_value() -> Result<()> {
+		// -- Fixture
+		let mm = ModelManager::new().await?;
+		let run_id = create_run(&mm, "run-input-value").await?;
+		let json_input = json!({"path": "src/main.rs", "_display": "src/main.rs"});
+		let items = vec![
+			TaskForCreate::new_with_input(run_id, 0, None, &json!("short input")),
+			TaskForCreate::new_with_input(run_id, 1, None, &json_input),
+			TaskForCreate::new_with_input(run_id, 2, None, &json!({"num": 1})),
+		];
+		let ids = TaskBmc::create_batch(&mm, items)?;
+
+		// -- Exec
+		let values = ids
+			.into_iter()
+			.map(|id| TaskBmc::get(&mm, id).and_then(|task| TaskBmc::get_input_value(&mm, &task)))
+			.collect::<core::result::Result<Vec<_>, _>>()?;
+
+		// -- Check
+		assert_eq!(values[0], json!("short input"));
+		assert_eq!(values[1], json_input);
+		assert_eq!(values[2], json!({"num": 1}));
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_model_task_bmc_create_batch_creates_large_inputs_in_batch() -> Result<()> {
 		// -- Fixture
@@ -56,6 +56,13 @@ impl UcontentBmc {
 
 		Self::create(mm, ucontent_c)
 	}
+
+	pub fn get_content(mm: &ModelManager, id: Id) -> Result<Option<String>> {
+		let sql = "SELECT content FROM ucontent WHERE id = ? LIMIT 1";
+		let content = mm.db().exec_returning_as_optional::<Option<String>>(sql, (id,))?;
+
+		Ok(content.flatten())
+	}
 }
 
 /// Private
@@ -1,5 +1,6 @@
 use crate::model::Result;
 use crate::model::db::Db;
+use simple_fs::SPath;
 
 #[derive(Debug, Clone)]
 pub struct ModelManager {
@@ -8,9 +9,18 @@ pub struct ModelManager {
 
 /// Constructors
 impl ModelManager {
+	/// In-memory runtime db (default)
 	pub async fn new() -> Result<Self> {
 		let db = Db::new()?;
-		db.recreate()?;
+		db.migrate()?;
+		Ok(Self { db })
+	}
+
+	/// On-disk runtime db, which survives across `aip` sessions.
+	/// The file will be created (and migrated to the latest schema) if needed.
+	pub async fn new_with_db_file(db_file: &SPath) -> Result<Self> {
+		let db = Db::new_with_file(db_file)?;
+		db.migrate()?;
 		Ok(Self { db })
 	}
 }
@@ -27,8 +37,13 @@ impl ModelManager {
 	/// NOTE: This is to make sure the db does not become too big in memory
 	///      For now, very agressive, just delete everything.
 	/// Should be called at the start of each run
+	///
+	/// NOTE: When the db is persistent, this is a no-op, as the records are meant to be kept.
 	pub fn trim(&self) -> Result<usize> {
 		let db = self.db();
+		if db.is_persistent() {
+			return Ok(0);
+		}
 		let run_count = db.exec("DELETE FROM run", [])?;
 		let task_count = db.exec("DELETE FROM task", [])?;
 		let log_count = db.exec("DELETE FROM log", [])?;
@@ -57,14 +72,31 @@ FROM pragma_page_count(), pragma_page_size();
 
 use tokio::sync::OnceCell;
 
-#[derive(Clone, Copy)]
-pub struct OnceModelManager;
+/// Lazy holder of the singleton `ModelManager`.
+///
+/// When `db_file` is set, the `ModelManager` will be persisted in this sqlite file,
+/// otherwise, it will be in-memory.
+#[derive(Debug, Clone, Default)]
+pub struct OnceModelManager {
+	db_file: Option<SPath>,
+}
 
 impl OnceModelManager {
+	pub fn new(db_file: Option<SPath>) -> Self {
+		Self { db_file }
+	}
+
 	/// Returns a reference to the singleton `ModelManager`, creating it on first call.
 	pub async fn get(&self) -> Result<ModelManager> {
 		static INSTANCE: OnceCell<ModelManager> = OnceCell::const_new();
-		let val = INSTANCE.get_or_try_init(|| async { ModelManager::new().await }).await?;
+		let val = INSTANCE
+			.get_or_try_init(|| async {
+				match self.db_file.as_ref() {
+					Some(db_file) => ModelManager::new_with_db_file(db_file).await,
+					None => ModelManager::new().await,
+				}
+			})
+			.await?;
 		Ok(val.clone())
 	}
 }
@@ -93,3 +125,45 @@ impl ModelManager {
 }
 
 // endregion: --- Mock Seed
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::gen_test_dir_path;
+	use crate::model::{RunBmc, RunForCreate};
+
+	#[tokio::test]
+	async fn test_model_manager_db_file_persist() -> Result<()> {
+		// -- Setup & Fixtures
+		let db_file = gen_test_dir_path().join(".session/rt-db.sqlite");
+		let mm = ModelManager::new_with_db_file(&db_file).await?;
+		let run_c = RunForCreate {
+			parent_id: None,
+			agent_name: Some("persisted-agent".to_string()),
+			agent_path: Some("persisted/agent.aip".to_string()),
+			has_task_stages: None,
+			has_prompt_parts: None,
+		};
+		let run_id = RunBmc::create(&mm, run_c)?;
+		drop(mm);
+
+		// -- Exec
+		let mm = ModelManager::new_with_db_file(&db_file).await?;
+
+		// -- Check
+		assert!(mm.db().is_persistent());
+		let run = RunBmc::get(&mm, run_id)?;
+		assert_eq!(run.agent_name.as_deref(), Some("persisted-agent"));
+		// trim is a no-op on persistent db
+		assert_eq!(mm.trim()?, 0);
+		assert!(RunBmc::get(&mm, run_id).is_ok());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,265 @@
+use crate::Result;
+use crate::agent::AgentOptions;
+use crate::model::Id;
+use crate::run::ai_fallback::exec_chat_with_fallback;
+use crate::run::ai_tools::{AiTools, exec_chat_with_tools};
+use crate::run::mock_chat::{agent_mock_dir, is_mock_model};
+use crate::run::response_schema::parse_response_json;
+use crate::run::{Cassette, CassetteMode, RunBaseOptions};
+use crate::runtime::Runtime;
+use genai::ModelName;
+use genai::chat::{ChatOptions, ChatRequest, ChatResponse, JsonSpec};
+use serde_json::Value;
+use simple_fs::SPath;
+use std::borrow::Cow;
+
+/// The result of an AI call
+pub struct AiExecRes {
+	pub chat_res: ChatResponse,
+	/// The model which answered (another model of the chain when the AI call fell back)
+	pub model_used: ModelName,
+	/// True when served from the responses cache (no cost nor usage for this call)
+	pub is_cached: bool,
+	/// The parsed response JSON, when a response schema
+	pub response_json: Option<Result<Value>>,
+}
+
+/// Exec an AI call of a run, the AI stage of a task or an `aip.ai.chat` call (with or without a task).
+///
+/// The response is replayed from the cassette, served from the responses cache, answered from the mock files
+/// (`mock::` models), or from the model (with the tools, retries, fallback models, and rate limits),
+/// and then recorded in the cassette and the responses cache.
+///
+/// NOTE: A cassette replay or a responses cache hit does not call the tool handlers (of the `ai_tools`).
+///
+/// NOTE: The cost & usage are recorded by the caller.
+#[allow(clippy::too_many_arguments)]
+pub async fn exec_ai_chat(
+	runtime: &Runtime,
+	client: &genai::Client,
+	options: &AgentOptions,
+	run_base_options: &RunBaseOptions,
+	agent_file: &SPath,
+	run_id: Id,
+	task_id: Option<Id>,
+	model_resolved: &ModelName,
+	ai_tools: Option<&AiTools>,
+	chat_req: ChatRequest,
+	chat_options: &ChatOptions,
+	response_schema: Option<&Value>,
+) -> Result<AiExecRes> {
+	let has_cache_control = chat_req
+		.messages
+		.iter()
+		.any(|message| message.options.as_ref().is_some_and(|options| options.cache_control.is_some()));
+
+	// the chat options with the eventual response schema (structured output)
+	let chat_options: Cow<ChatOptions> = match response_schema {
+		Some(schema) => {
+			let opts = chat_options.clone();
+			Cow::Owned(opts.with_response_format(JsonSpec::new("response", schema.clone())))
+		}
+		None => Cow::Borrowed(chat_options),
+	};
+
+	// compute the cache options with the eventual cache key
+	// Note: For now, we use the runtime session as the key. Later, we will allow payload to provide it
+	let c_chat_options: Cow<ChatOptions> = if has_cache_control {
+		let opts = chat_options.as_ref().clone();
+		Cow::Owned(opts.with_prompt_cache_key(runtime.session_str().to_string()))
+	} else {
+		Cow::Borrowed(chat_options.as_ref())
+	};
+
+	// -- Exec the chat (or replay it from the cassette, or serve it from the responses cache)
+	let cassette = run_base_options.cassette();
+	let response_cache = responses_cache(runtime, options, model_resolved)?;
+	let request_hash = match (cassette, &response_cache) {
+		(None, None) => None,
+		_ => Some(Cassette::request_hash(
+			model_resolved,
+			chat_options.as_ref(),
+			&chat_req,
+		)?),
+	};
+	let cached_res = match (&response_cache, request_hash.as_deref()) {
+		(Some(response_cache), Some(request_hash)) => response_cache.find(request_hash)?,
+		_ => None,
+	};
+	let is_cached = cached_res.is_some();
+	let (chat_res, model_used, response_json) = match (cached_res, cassette, request_hash.as_deref()) {
+		// NOTE: The recorded model is the one which answered (another model of the chain when it fell back)
+		(Some((model_used, cached_res)), cassette, request_hash) => {
+			// NOTE: A recording cassette still records the cached response (for its replay)
+			if let (Some(cassette), Some(request_hash)) = (cassette, request_hash)
+				&& !cassette.is_replay()
+			{
+				cassette.record(request_hash, &model_used, chat_req, &cached_res)?;
+			}
+			(cached_res, model_used, None)
+		}
+		(None, Some(cassette), Some(request_hash)) if cassette.is_replay() => {
+			let (model_used, chat_res) = cassette.replay(request_hash, model_resolved)?;
+			(chat_res, model_used, None)
+		}
+		(None, cassette, request_hash) => {
+			let record_req = (cassette.is_some() || response_cache.is_some()).then(|| chat_req.clone());
+			// NOTE: The `mock::` models (of the model chain) are answered offline from the agent mock files
+			let has_mock_model = is_mock_model(model_resolved)
+				|| options
+					.resolve_model_chain()
+					.into_iter()
+					.any(|model| is_mock_model(&model.into()));
+			let mock_dir = match (has_mock_model, run_base_options.mock_dir()) {
+				(false, _) => None,
+				(true, Some(mock_dir)) => Some(mock_dir.clone()),
+				(true, None) => Some(agent_mock_dir(agent_file)?),
+			};
+			let (chat_res, model_used) = if let (Some(ai_tools), Some(task_id)) = (ai_tools, task_id)
+				&& !is_mock_model(model_resolved)
+			{
+				// NOTE: Only the final response of the tool-call loop is recorded (and cached)
+				exec_chat_with_tools(
+					runtime,
+					client,
+					options,
+					ai_tools,
+					run_id,
+					task_id,
+					model_resolved,
+					mock_dir.as_ref(),
+					chat_req,
+					c_chat_options.as_ref(),
+				)
+				.await?
+			} else {
+				exec_chat_with_fallback(
+					runtime,
+					client,
+					options,
+					run_id,
+					task_id,
+					model_resolved,
+					mock_dir.as_ref(),
+					chat_req,
+					c_chat_options.as_ref(),
+				)
+				.await?
+			};
+			// NOTE: A response which does not match the response schema is not recorded (nor cached)
+			let response_json =
+				response_schema.map(|schema| parse_response_json(schema, chat_res.content.joined_texts().as_deref()));
+			if let (Some(request_hash), Some(record_req)) = (request_hash, record_req)
+				&& !matches!(response_json, Some(Err(_)))
+			{
+				for recorder in [cassette, response_cache.as_ref()].into_iter().flatten() {
+					recorder.record(request_hash, &model_used, record_req.clone(), &chat_res)?;
+				}
+			}
+			(chat_res, model_used, response_json)
+		}
+	};
+	let response_json = match (response_json, response_schema) {
+		(Some(response_json), _) => Some(response_json),
+		(None, Some(schema)) => Some(parse_response_json(schema, chat_res.content.joined_texts().as_deref())),
+		(None, None) => None,
+	};
+
+	Ok(AiExecRes {
+		chat_res,
+		model_used,
+		is_cached,
+		response_json,
+	})
+}
+
+// region:    --- Support
+
+/// The responses cache, when the `cache_responses` option is true
+/// (NOTE: the `mock::` models are never cached)
+fn responses_cache(runtime: &Runtime, options: &AgentOptions, model: &ModelName) -> Result<Option<Cassette>> {
+	if !options.cache_responses().unwrap_or(false) || is_mock_model(model) {
+		return Ok(None);
+	}
+	let Some(aipack_wks_dir) = runtime.dir_context().aipack_paths().aipack_wks_dir() else {
+		return Ok(None);
+	};
+	let cache_dir = aipack_wks_dir.get_ai_responses_cache_dir()?;
+	Ok(Some(Cassette::new(cache_dir, CassetteMode::Record)))
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use genai::ModelIden;
+	use genai::adapter::AdapterKind;
+	use genai::chat::{ChatMessage, MessageContent, Usage};
+	use serde_json::json;
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_run_ai_exec_responses_cache_hit_fallback_model() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let options = AgentOptions::from_options_value(json!({"cache_responses": true}))?;
+		let model_resolved = ModelName::from("gpt-5-mini");
+		// the cached response came from a fallback model of the chain
+		let model_fallback = ModelName::from("gpt-5-nano");
+		let chat_options = ChatOptions::default();
+		let chat_req = ChatRequest::from_messages(vec![ChatMessage::user("hello cache fallback")]);
+		let model_iden = ModelIden::new(AdapterKind::OpenAI, model_fallback.clone());
+		let chat_res = ChatResponse {
+			content: MessageContent::from_text("cached world"),
+			reasoning_content: None,
+			model_iden: model_iden.clone(),
+			provider_model_iden: model_iden,
+			stop_reason: None,
+			usage: Usage::default(),
+			captured_raw_body: None,
+			response_id: None,
+		};
+		let response_cache = responses_cache(&runtime, &options, &model_resolved)?.ok_or("Should have a cache")?;
+		let request_hash = Cassette::request_hash(&model_resolved, &chat_options, &chat_req)?;
+		response_cache.record(&request_hash, &model_fallback, chat_req.clone(), &chat_res)?;
+
+		// -- Exec
+		let res = exec_ai_chat(
+			&runtime,
+			runtime.genai_client(),
+			&options,
+			&RunBaseOptions::default(),
+			&SPath::new("agent.aip"),
+			0.into(),
+			None,
+			&model_resolved,
+			None,
+			chat_req,
+			&chat_options,
+			None,
+		)
+		.await?;
+
+		// -- Check
+		assert!(res.is_cached);
+		assert_eq!(res.model_used, model_fallback);
+		assert_eq!(res.chat_res.content.joined_texts().as_deref(), Some("cached world"));
+
+		// -- Clean
+		let cache_dir = runtime
+			.dir_context()
+			.aipack_paths()
+			.aipack_wks_dir()
+			.ok_or("Should have wks dir")?
+			.get_ai_responses_cache_dir()?;
+		std::fs::remove_file(cache_dir.join(format!("{request_hash}.json")))?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,245 @@
+use crate::agent::{AgentOptions, FallbackOn};
+use crate::model::{Id, LogKind};
+use crate::run::ai_retry::{self, exec_chat_with_retry};
+use crate::run::mock_chat::{is_mock_model, mock_exec_chat};
+use crate::runtime::Runtime;
+use crate::{Error, Result};
+use genai::ModelName;
+use genai::chat::{ChatOptions, ChatRequest, ChatResponse};
+use simple_fs::SPath;
+
+/// Exec the chat on the model, then on the next models of the resolved `model` list (the fallback chain)
+/// when the AI call fails (after its retries) with one of the `fallback_on` errors.
+/// Each fallback is logged (`log` table, AI stage of the task).
+///
+/// The `mock::` models of the chain are answered from the `mock_dir` (see `mock_chat`).
+///
+/// Returns the chat response with the model which answered it.
+#[allow(clippy::too_many_arguments)]
+pub async fn exec_chat_with_fallback(
+	runtime: &Runtime,
+	client: &genai::Client,
+	options: &AgentOptions,
+	run_id: Id,
+	task_id: Option<Id>,
+	model_resolved: &ModelName,
+	mock_dir: Option<&SPath>,
+	chat_req: ChatRequest,
+	chat_options: &ChatOptions,
+) -> Result<(ChatResponse, ModelName)> {
+	// The models after the model resolved in the chain
+	let mut fallbacks = options
+		.resolve_model_chain()
+		.into_iter()
+		.skip_while(|model| model.as_str() != model_resolved.as_str())
+		.skip(1)
+		.map(ModelName::from)
+		.peekable();
+
+	// NOTE: No clone of the request when no fallback
+	if fallbacks.peek().is_none() {
+		let chat_res = exec_chat_model(
+			runtime,
+			client,
+			options,
+			run_id,
+			task_id,
+			model_resolved,
+			mock_dir,
+			chat_req,
+			chat_options,
+		)
+		.await?;
+		return Ok((chat_res, model_resolved.clone()));
+	}
+
+	let fallback_on = options
+		.fallback_on()
+		.map(|on| on.to_vec())
+		.unwrap_or_else(|| FallbackOn::ALL.to_vec());
+
+	let mut model = model_resolved.clone();
+	loop {
+		let res = exec_chat_model(
+			runtime,
+			client,
+			options,
+			run_id,
+			task_id,
+			&model,
+			mock_dir,
+			chat_req.clone(),
+			chat_options,
+		)
+		.await;
+		let err = match res {
+			Ok(chat_res) => return Ok((chat_res, model)),
+			Err(err) => err,
+		};
+
+		let Some(fallback_class) = classify_error(&err).filter(|class| fallback_on.contains(class)) else {
+			return Err(err);
+		};
+		let Some(next_model) = fallbacks.next() else {
+			return Err(err);
+		};
+
+		let msg = format!("AI call to {model} failed ({fallback_class}). Falling back to {next_model}.");
+		// NOTE: The rt_log also publishes the message to the hub
+		runtime
+			.rt_log()
+			.rec_log_ai(run_id, task_id, msg, Some(LogKind::SysWarn))
+			.await?;
+
+		model = next_model;
+	}
+}
+
+// region:    --- Support
+
+/// Exec the chat on one model of the chain (with its retries, or from the mock dir for a `mock::` model)
+#[allow(clippy::too_many_arguments)]
+async fn exec_chat_model(
+	runtime: &Runtime,
+	client: &genai::Client,
+	options: &AgentOptions,
+	run_id: Id,
+	task_id: Option<Id>,
+	model: &ModelName,
+	mock_dir: Option<&SPath>,
+	chat_req: ChatRequest,
+	chat_options: &ChatOptions,
+) -> Result<ChatResponse> {
+	match mock_dir {
+		Some(mock_dir) if is_mock_model(model) => mock_exec_chat(runtime, mock_dir, task_id, model, &chat_req),
+		_ => exec_chat_with_retry(runtime, client, options, run_id, task_id, model, chat_req, chat_options).await,
+	}
+}
+
+/// Returns the fallback class of an AI call error (None if no fallback for this error)
+fn classify_error(err: &Error) -> Option<FallbackOn> {
+	match err {
+		Error::GenAIEnvKeyMissing { .. } => Some(FallbackOn::Auth),
+		Error::GenAI(genai_err) => classify_genai_error(genai_err),
+		_ => None,
+	}
+}
+
+fn classify_genai_error(err: &genai::Error) -> Option<FallbackOn> {
+	match ai_retry::status_of(err) {
+		Some(401 | 403) => return Some(FallbackOn::Auth),
+		Some(404) => return Some(FallbackOn::Model),
+		_ => (),
+	}
+	match err {
+		genai::Error::RequiresApiKey { .. }
+		| genai::Error::NoAuthResolver { .. }
+		| genai::Error::NoAuthData { .. }
+		| genai::Error::Resolver { .. } => Some(FallbackOn::Auth),
+		err => ai_retry::classify_genai_error(err).map(FallbackOn::from),
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::{gen_test_dir_path, remove_test_dir, save_file_content};
+	use crate::agent::Agent;
+	use crate::model::{RunBmc, TaskBmc};
+	use crate::run::{RunBaseOptions, run_agent};
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_run_ai_fallback_mock_model_chain() -> Result<()> {
+		// -- Setup & Fixtures
+		let dir = gen_test_dir_path();
+		let mock_dir = dir.join("mocks");
+		save_file_content(&mock_dir.join("default.md"), "Hello from the fallback")?;
+		let fx_content = r#"
+# Options
+
+```toml
+model = ["mock::error-503", "mock::default"]
+fallback_on = ["server"]
+```
+
+# Instruction
+
+Say hello
+
+# Output
+
+```lua
+return ai_response.content
+```
+"#;
+		let agent = Agent::mock_from_content(fx_content)?;
+		let run_base_options = RunBaseOptions::default().with_mock_dir(mock_dir);
+
+		// -- Exec
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let res = run_agent(&runtime, None, agent, Some(vec!["one".into()]), &run_base_options, true).await?;
+
+		// -- Check
+		let outputs = res.outputs.ok_or("Should have outputs")?;
+		assert_eq!(outputs, vec![serde_json::json!("Hello from the fallback")]);
+		let run = RunBmc::list(runtime.mm(), None)?.pop().ok_or("Should have one run")?;
+		let task = TaskBmc::list_for_run(runtime.mm(), run.id)?
+			.pop()
+			.ok_or("Should have one task")?;
+		assert_eq!(task.model_ov.as_deref(), Some("mock::default"));
+
+		// -- Clean
+		remove_test_dir(&dir)?;
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_run_ai_fallback_mock_model_chain_not_fallback_on() -> Result<()> {
+		// -- Setup & Fixtures
+		let dir = gen_test_dir_path();
+		let mock_dir = dir.join("mocks");
+		save_file_content(&mock_dir.join("default.md"), "Hello from the fallback")?;
+		// a 503 is a `server` error, which is not in the `fallback_on`
+		let fx_content = r#"
+# Options
+
+```toml
+model = ["mock::error-503", "mock::default"]
+fallback_on = ["auth", "model"]
+```
+
+# Instruction
+
+Say hello
+"#;
+		let agent = Agent::mock_from_content(fx_content)?;
+		let run_base_options = RunBaseOptions::default().with_mock_dir(mock_dir);
+
+		// -- Exec
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let res = run_agent(&runtime, None, agent, Some(vec!["one".into()]), &run_base_options, true).await;
+
+		// -- Check
+		let err = res.err().ok_or("Should fail")?;
+		assert!(err.to_string().contains("503"), "err: {err}");
+		let run = RunBmc::list(runtime.mm(), None)?.pop().ok_or("Should have one run")?;
+		let task = TaskBmc::list_for_run(runtime.mm(), run.id)?
+			.pop()
+			.ok_or("Should have one task")?;
+		assert!(task.model_ov.is_none(), "should not fall back");
+
+		// -- Clean
+		remove_test_dir(&dir)?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
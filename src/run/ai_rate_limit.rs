@@ -0,0 +1,70 @@
+use crate::Result;
+use crate::model::{Id, LogKind};
+use crate::run::pricing::estimate_tokens;
+use crate::runtime::Runtime;
+use crate::support::text::format_duration;
+use genai::ModelName;
+use genai::chat::{ChatRequest, ChatResponse};
+
+/// The rate limit slot of an AI call, for the `[rate_limits]` of its adapter kind
+/// (see the runtime `RateLimiter`, shared by all tasks, runs, and sub-agents).
+#[derive(Debug, Clone, Copy)]
+pub struct AiRateLimit {
+	/// The adapter kind lower name (e.g., `openai`)
+	adapter: &'static str,
+	/// The estimated prompt tokens of the request
+	tokens: u64,
+}
+
+/// Constructors
+impl AiRateLimit {
+	/// NOTE: None if the adapter kind cannot be resolved (then, not rate limited).
+	pub fn new(client: &genai::Client, model_resolved: &ModelName, chat_req: &ChatRequest) -> Option<Self> {
+		let adapter = client.default_model(model_resolved).ok()?.adapter_kind.as_lower_str();
+		let system_size = chat_req.system.as_ref().map(|s| s.len()).unwrap_or(0);
+		let prompt_size: usize = system_size + chat_req.messages.iter().map(|m| m.size()).sum::<usize>();
+		let tokens = estimate_tokens(prompt_size) as u64;
+		Some(Self { adapter, tokens })
+	}
+}
+
+impl AiRateLimit {
+	/// Wait until the request is within the rate limits of its adapter kind.
+	/// The first wait is logged (`log` table, AI stage of the task).
+	pub async fn acquire(&self, runtime: &Runtime, run_id: Id, task_id: Option<Id>) -> Result<()> {
+		let mut logged = false;
+		loop {
+			let wait = match runtime.rate_limiter().try_acquire(self.adapter, self.tokens) {
+				Ok(()) => return Ok(()),
+				Err(wait) => wait,
+			};
+
+			if !logged {
+				let msg = format!(
+					"AI call rate limited by the [rate_limits.{}] config. Waiting {}.",
+					self.adapter,
+					format_duration(wait)
+				);
+				// NOTE: The rt_log also publishes the message to the hub
+				runtime
+					.rt_log()
+					.rec_log_ai(run_id, task_id, msg, Some(LogKind::SysInfo))
+					.await?;
+				logged = true;
+			}
+
+			tokio::time::sleep(wait).await;
+		}
+	}
+
+	/// Record the tokens actually used by the response (when more than estimated)
+	pub fn record_usage(&self, runtime: &Runtime, chat_res: &ChatResponse) {
+		let usage = &chat_res.usage;
+		let used_tokens = usage
+			.total_tokens
+			.unwrap_or_else(|| usage.prompt_tokens.unwrap_or(0) + usage.completion_tokens.unwrap_or(0));
+		runtime
+			.rate_limiter()
+			.record_usage(self.adapter, self.tokens, used_tokens.max(0) as u64);
+	}
+}
@@ -1,8 +1,8 @@
 // region:    --- AiResponse
 
+use crate::script::serde_value_to_lua_value;
 use crate::support::W;
 use genai::ModelName;
-use genai::adapter::AdapterKind;
 use genai::chat::Usage;
 use mlua::IntoLua;
 use serde::Serialize;
@@ -10,9 +10,12 @@ use serde::Serialize;
 #[derive(Debug, Serialize)]
 pub struct AiResponse {
 	pub content: Option<String>,
+	/// The parsed JSON content (when the agent has a `response_schema`)
+	pub json: Option<serde_json::Value>,
 	pub reasoning_content: Option<String>,
 	pub model_name: ModelName,
-	pub adapter_kind: AdapterKind,
+	/// The adapter name (e.g., `OpenAI`, or `Mock` for the `mock::` models)
+	pub adapter_kind: &'static str,
 	pub usage: Usage,
 	pub price_usd: Option<f64>,
 	pub duration_sec: f64,
@@ -24,9 +27,12 @@ impl IntoLua for AiResponse {
 		let table = lua.create_table()?;
 
 		table.set("content", self.content.into_lua(lua)?)?;
+		if let Some(json) = self.json {
+			table.set("json", serde_value_to_lua_value(lua, json)?)?;
+		}
 		table.set("reasoning_content", self.reasoning_content.into_lua(lua)?)?;
 		table.set("model_name", self.model_name.into_lua(lua)?)?;
-		table.set("adapter_kind", self.adapter_kind.as_str().into_lua(lua)?)?;
+		table.set("adapter_kind", self.adapter_kind.into_lua(lua)?)?;
 		table.set("usage", W(&self.usage).into_lua(lua)?)?;
 		table.set("price_usd", self.price_usd.into_lua(lua)?)?;
 		table.set("duration_sec", self.duration_sec.into_lua(lua)?)?;
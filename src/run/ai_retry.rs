@@ -0,0 +1,211 @@
+use crate::Result;
+use crate::agent::{AgentOptions, RetryOn};
+use crate::model::{Id, LogKind};
+use crate::run::ai_rate_limit::AiRateLimit;
+use crate::run::ai_stream::exec_chat_live;
+use crate::runtime::Runtime;
+use crate::support::text::format_duration;
+use genai::ModelName;
+use genai::chat::{ChatOptions, ChatRequest, ChatResponse};
+use std::time::Duration;
+
+const DEFAULT_BACKOFF_MS: u64 = 1000;
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// The retry policy of the AI calls, from the `retry_max`, `retry_backoff_ms`, and `retry_on` agent options
+#[derive(Debug, Clone)]
+pub struct AiRetry {
+	max: u32,
+	backoff_ms: u64,
+	on: Vec<RetryOn>,
+}
+
+/// Constructors
+impl AiRetry {
+	pub fn from_options(options: &AgentOptions) -> Self {
+		Self {
+			max: options.retry_max().unwrap_or(0),
+			backoff_ms: options.retry_backoff_ms().unwrap_or(DEFAULT_BACKOFF_MS),
+			on: options
+				.retry_on()
+				.map(|on| on.to_vec())
+				.unwrap_or_else(|| RetryOn::ALL.to_vec()),
+		}
+	}
+}
+
+impl AiRetry {
+	/// Returns the error class if this error should be retried for this attempt (1-based)
+	pub fn retry_on_for(&self, attempt: u32, err: &genai::Error) -> Option<RetryOn> {
+		if attempt > self.max {
+			return None;
+		}
+		classify_genai_error(err).filter(|retry_on| self.on.contains(retry_on))
+	}
+
+	/// The delay before the retry of this attempt (1-based), exponential from the backoff,
+	/// or the provider `retry-after` (in seconds) when longer.
+	pub fn delay(&self, attempt: u32, err: &genai::Error) -> Duration {
+		let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+		let backoff = Duration::from_millis(self.backoff_ms.saturating_mul(factor)).min(MAX_BACKOFF);
+		match retry_after(err) {
+			Some(retry_after) => backoff.max(retry_after.min(MAX_BACKOFF)),
+			None => backoff,
+		}
+	}
+}
+
+/// Exec the chat (streamed to the hub when consumed) with the retries of the agent options.
+/// Each failed attempt which is retried is logged (`log` table, AI stage of the task).
+///
+/// Each attempt first waits for the `[rate_limits]` of its adapter kind (when configured).
+#[allow(clippy::too_many_arguments)]
+pub async fn exec_chat_with_retry(
+	runtime: &Runtime,
+	client: &genai::Client,
+	options: &AgentOptions,
+	run_id: Id,
+	task_id: Option<Id>,
+	model_resolved: &ModelName,
+	chat_req: ChatRequest,
+	chat_options: &ChatOptions,
+) -> Result<ChatResponse> {
+	let ai_retry = AiRetry::from_options(options);
+	let rate_limit = AiRateLimit::new(client, model_resolved, &chat_req);
+
+	// NOTE: No clone of the request when no retry
+	if ai_retry.max == 0 {
+		if let Some(rate_limit) = rate_limit.as_ref() {
+			rate_limit.acquire(runtime, run_id, task_id).await?;
+		}
+		let chat_res = exec_chat_live(client, model_resolved, chat_req, chat_options, task_id).await?;
+		if let Some(rate_limit) = rate_limit.as_ref() {
+			rate_limit.record_usage(runtime, &chat_res);
+		}
+		return Ok(chat_res);
+	}
+
+	let mut attempt = 1;
+	loop {
+		if let Some(rate_limit) = rate_limit.as_ref() {
+			rate_limit.acquire(runtime, run_id, task_id).await?;
+		}
+		let err = match exec_chat_live(client, model_resolved, chat_req.clone(), chat_options, task_id).await {
+			Ok(chat_res) => {
+				if let Some(rate_limit) = rate_limit.as_ref() {
+					rate_limit.record_usage(runtime, &chat_res);
+				}
+				return Ok(chat_res);
+			}
+			Err(err) => err,
+		};
+
+		let Some(retry_on) = ai_retry.retry_on_for(attempt, &err) else {
+			return Err(err.into());
+		};
+
+		let delay = ai_retry.delay(attempt, &err);
+		let cause = match status_of(&err) {
+			Some(status) => format!("{retry_on}, HTTP {status}"),
+			None => retry_on.to_string(),
+		};
+		let msg = format!(
+			"AI call attempt {attempt} failed ({cause}). Retrying in {} (retry {attempt} of {}).",
+			format_duration(delay),
+			ai_retry.max
+		);
+		// NOTE: The rt_log also publishes the message to the hub
+		runtime
+			.rt_log()
+			.rec_log_ai(run_id, task_id, msg, Some(LogKind::SysWarn))
+			.await?;
+
+		tokio::time::sleep(delay).await;
+		attempt += 1;
+	}
+}
+
+// region:    --- Support
+
+/// Returns the retry class of a genai error (None if not retryable)
+pub(super) fn classify_genai_error(err: &genai::Error) -> Option<RetryOn> {
+	if let Some(status) = status_of(err) {
+		return classify_status(status);
+	}
+	match err {
+		genai::Error::WebModelCall { webc_error, .. } | genai::Error::WebAdapterCall { webc_error, .. } => {
+			matches!(webc_error, genai::webc::Error::Reqwest(_)).then_some(RetryOn::Network)
+		}
+		genai::Error::WebStream { .. } => Some(RetryOn::Network),
+		_ => None,
+	}
+}
+
+/// The HTTP status of the failed AI call, if any
+pub(super) fn status_of(err: &genai::Error) -> Option<u16> {
+	match err {
+		genai::Error::WebModelCall { webc_error, .. } | genai::Error::WebAdapterCall { webc_error, .. } => {
+			webc_error.status().map(|status| status.as_u16())
+		}
+		genai::Error::HttpError { status, .. } => Some(status.as_u16()),
+		_ => None,
+	}
+}
+
+fn classify_status(status: u16) -> Option<RetryOn> {
+	match status {
+		429 => Some(RetryOn::RateLimit),
+		500..=599 => Some(RetryOn::Server),
+		_ => None,
+	}
+}
+
+/// The `retry-after` (in seconds) of the provider response, if any
+fn retry_after(err: &genai::Error) -> Option<Duration> {
+	let headers = match err {
+		genai::Error::WebModelCall { webc_error, .. } | genai::Error::WebAdapterCall { webc_error, .. } => {
+			webc_error.headers()?
+		}
+		genai::Error::HttpError { headers, .. } => headers,
+		_ => return None,
+	};
+	let secs = headers.get("retry-after")?.to_str().ok()?.trim().parse::<f64>().ok()?;
+	(secs >= 0.0).then(|| Duration::from_secs_f64(secs))
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_ai_retry_delay_and_classes() -> Result<()> {
+		// -- Setup & Fixtures
+		let options = AgentOptions::from_options_value(json!({
+			"retry_max": 2,
+			"retry_backoff_ms": 500,
+			"retry_on": ["server"]
+		}))?;
+		let ai_retry = AiRetry::from_options(&options);
+		let err = genai::Error::InvalidJsonResponseElement { info: "test" };
+
+		// -- Exec & Check
+		assert_eq!(ai_retry.delay(1, &err), Duration::from_millis(500));
+		assert_eq!(ai_retry.delay(3, &err), Duration::from_millis(2000));
+		assert_eq!(ai_retry.delay(20, &err), MAX_BACKOFF);
+		assert_eq!(classify_status(429), Some(RetryOn::RateLimit));
+		assert_eq!(classify_status(503), Some(RetryOn::Server));
+		assert_eq!(classify_status(400), None);
+		assert!(ai_retry.retry_on_for(1, &err).is_none(), "not a retryable error");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
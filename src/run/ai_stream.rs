@@ -0,0 +1,121 @@
+use crate::hub::{AiStreamEvent, AiStreamKind, get_hub};
+use crate::model::Id;
+use futures::StreamExt as _;
+use genai::ModelName;
+use genai::chat::{ChatOptions, ChatRequest, ChatResponse, ChatStreamEvent, ContentPart, MessageContent, ToolCall};
+
+/// Exec the chat, streamed to the hub when it is for a task and a UI consumes the AI stream events
+/// (the TUI live task view), otherwise with the non-streaming API (which has the provider model of the response).
+///
+/// NOTE: Returns the genai error, so that the retries can classify it.
+pub async fn exec_chat_live(
+	client: &genai::Client,
+	model_resolved: &ModelName,
+	chat_req: ChatRequest,
+	chat_options: &ChatOptions,
+	task_id: Option<Id>,
+) -> genai::Result<ChatResponse> {
+	if let Some(task_id) = task_id
+		&& get_hub().has_ai_stream_consumer()
+	{
+		exec_chat_stream(client, model_resolved, chat_req, chat_options, task_id).await
+	} else {
+		client.exec_chat(model_resolved, chat_req, Some(chat_options)).await
+	}
+}
+
+/// Exec the chat with the genai streaming API, publishing the content and reasoning chunks to the hub
+/// (for the live task view), and returns the assembled chat response.
+async fn exec_chat_stream(
+	client: &genai::Client,
+	model_resolved: &ModelName,
+	chat_req: ChatRequest,
+	chat_options: &ChatOptions,
+	task_id: Id,
+) -> genai::Result<ChatResponse> {
+	let hub = get_hub();
+	let publish = |kind: AiStreamKind| hub.publish(AiStreamEvent { task_id, kind });
+
+	// Capture everything needed to assemble the chat response at the stream end
+	let chat_options = chat_options
+		.clone()
+		.with_capture_usage(true)
+		.with_capture_content(true)
+		.with_capture_reasoning_content(true)
+		.with_capture_tool_calls(true);
+
+	publish(AiStreamKind::Start).await;
+	let res = consume_stream(client, model_resolved, chat_req, &chat_options, &publish).await;
+	publish(AiStreamKind::End).await;
+
+	res
+}
+
+async fn consume_stream<F, Fut>(
+	client: &genai::Client,
+	model_resolved: &ModelName,
+	chat_req: ChatRequest,
+	chat_options: &ChatOptions,
+	publish: &F,
+) -> genai::Result<ChatResponse>
+where
+	F: Fn(AiStreamKind) -> Fut,
+	Fut: Future<Output = ()>,
+{
+	let stream_res = client.exec_chat_stream(model_resolved, chat_req, Some(chat_options)).await?;
+	// NOTE: The stream metadata only has the model iden of the service target (which is the provider model),
+	//       the provider model name of the response body is not given by the stream.
+	let model_iden = stream_res.model_iden;
+	let mut stream = stream_res.stream;
+
+	// NOTE: The streamed content, in case the adapter does not capture it
+	let mut content = String::new();
+	let mut reasoning_content = String::new();
+	let mut tool_calls: Vec<ToolCall> = Vec::new();
+	let mut stream_end = None;
+
+	while let Some(event) = stream.next().await {
+		match event? {
+			ChatStreamEvent::Chunk(chunk) => {
+				content.push_str(&chunk.content);
+				publish(AiStreamKind::Content(chunk.content.into())).await;
+			}
+			ChatStreamEvent::ReasoningChunk(chunk) => {
+				reasoning_content.push_str(&chunk.content);
+				publish(AiStreamKind::Reasoning(chunk.content.into())).await;
+			}
+			ChatStreamEvent::ToolCallChunk(chunk) => tool_calls.push(chunk.tool_call),
+			ChatStreamEvent::End(end) => stream_end = Some(end),
+			_ => (),
+		}
+	}
+
+	// NOTE: A stream without its end is truncated (and would have no usage, so no cost)
+	let stream_end = stream_end.ok_or_else(|| genai::Error::WebStream {
+		model_iden: model_iden.clone(),
+		cause: "AI response stream ended without its end event".to_string(),
+		error: "stream truncated".into(),
+	})?;
+	let content = stream_end.captured_content.unwrap_or_else(|| {
+		let mut parts: Vec<ContentPart> = Vec::new();
+		if !content.is_empty() {
+			parts.push(ContentPart::from_text(content));
+		}
+		parts.extend(tool_calls.into_iter().map(ContentPart::ToolCall));
+		MessageContent::from_parts(parts)
+	});
+	let reasoning_content = stream_end
+		.captured_reasoning_content
+		.or_else(|| (!reasoning_content.is_empty()).then_some(reasoning_content));
+
+	Ok(ChatResponse {
+		content,
+		reasoning_content,
+		model_iden: model_iden.clone(),
+		provider_model_iden: model_iden,
+		stop_reason: stream_end.captured_stop_reason,
+		usage: stream_end.captured_usage.unwrap_or_default(),
+		captured_raw_body: None,
+		response_id: stream_end.captured_response_id,
+	})
+}
@@ -0,0 +1,377 @@
+use crate::agent::{Agent, AgentOptions};
+use crate::model::{Id, LogKind, RuntimeCtx, Stage};
+use crate::run::Literals;
+use crate::run::ai_fallback::exec_chat_with_fallback;
+use crate::runtime::Runtime;
+use crate::script::{LuaEngine, ScriptLocation, lua_value_to_serde_value};
+use crate::support::text::truncate_with_ellipsis;
+use crate::{Error, Result};
+use genai::ModelName;
+use genai::chat::{ChatOptions, ChatRequest, ChatResponse, Tool, ToolCall, ToolResponse, Usage};
+use mlua::{Function, Table};
+use serde_json::Value;
+use simple_fs::SPath;
+
+/// The maximum number of AI calls of the tool-call loop of a task
+const MAX_TOOL_TURNS: usize = 20;
+
+/// The maximum number of characters of the tool arguments and results in the task logs
+const LOG_MAX_CHARS: usize = 300;
+
+/// The tools of the agent `# Tools` section, with their Lua handlers.
+///
+/// NOTE: The handlers are only called by an actual AI call. A response replayed from a cassette (`--replay`)
+///       or served from the responses cache (`cache_responses`) is the recorded final response,
+///       so the handlers (and their side effects) are skipped.
+pub struct AiTools {
+	// NOTE: The handlers are functions of this engine, so it needs to be kept alive
+	lua_engine: LuaEngine,
+	tools: Vec<AiTool>,
+	script: String,
+	location: Option<ScriptLocation>,
+}
+
+struct AiTool {
+	name: String,
+	description: Option<String>,
+	schema: Option<Value>,
+	handler: Function,
+}
+
+/// Constructor
+impl AiTools {
+	/// Eval the agent `# Tools` script, with the same scope as the `# Output` stage (without the `ai_response`).
+	///
+	/// Returns None if the agent has no tools.
+	#[allow(clippy::too_many_arguments)]
+	pub async fn load(
+		runtime: &Runtime,
+		base_rt_ctx: &RuntimeCtx,
+		agent: &Agent,
+		literals: &Literals,
+		before_all: &Value,
+		input: &Value,
+		data: &Value,
+	) -> Result<Option<Self>> {
+		let Some(tools_script) = agent.tools_script() else {
+			return Ok(None);
+		};
+
+		// -- Create the Tools Lua Engine (the handlers are executed in the AI stage)
+		let lua_engine = runtime.new_lua_engine_with_ctx(literals, base_rt_ctx.with_stage(Stage::Ai))?;
+
+		// -- Create the scope
+		let lua_scope = lua_engine.create_table()?;
+		lua_scope.set("input", lua_engine.serde_to_lua_value(input.clone())?)?;
+		lua_scope.set("data", lua_engine.serde_to_lua_value(data.clone())?)?;
+		lua_scope.set("before_all", lua_engine.serde_to_lua_value(before_all.clone())?)?;
+		lua_scope.set("options", agent.options_as_ref())?;
+
+		let location = agent.script_location(Stage::Ai);
+		let lua_value = lua_engine
+			.eval_with_paths(tools_script, Some(lua_scope), agent.context_dirs(), location.as_ref())
+			.await?;
+
+		let tools = parse_tools(lua_value).map_err(|cause| Error::ToolsFailWrongReturn { cause })?;
+		if tools.is_empty() {
+			return Ok(None);
+		}
+
+		Ok(Some(Self {
+			lua_engine,
+			tools,
+			script: tools_script.to_string(),
+			location,
+		}))
+	}
+}
+
+/// Getters
+impl AiTools {
+	/// The genai tools of the chat request
+	pub fn genai_tools(&self) -> Vec<Tool> {
+		self.tools
+			.iter()
+			.map(|tool| {
+				let mut genai_tool = Tool::new(tool.name.clone());
+				if let Some(description) = tool.description.as_ref() {
+					genai_tool = genai_tool.with_description(description.clone());
+				}
+				if let Some(schema) = tool.schema.as_ref() {
+					genai_tool = genai_tool.with_schema(schema.clone());
+				}
+				genai_tool
+			})
+			.collect()
+	}
+}
+
+/// Call
+impl AiTools {
+	/// Call the tool handler with the tool call arguments, and returns the tool response content
+	/// (the handler string result as is, other values as JSON).
+	///
+	/// NOTE: The error is returned to the AI as the tool response, so that it can recover.
+	async fn call(&self, tool_call: &ToolCall) -> core::result::Result<String, String> {
+		let tool = self
+			.tools
+			.iter()
+			.find(|tool| tool.name == tool_call.fn_name)
+			.ok_or_else(|| format!("Unknown tool '{}'", tool_call.fn_name))?;
+
+		let args = self
+			.lua_engine
+			.serde_to_lua_value(tool_call.fn_arguments.clone())
+			.map_err(|err| err.to_string())?;
+
+		let res = tool
+			.handler
+			.call_async::<mlua::Value>(args)
+			.await
+			.map_err(|err| Error::from_error_with_script(&err, &self.script, self.location.as_ref()).to_string())?;
+
+		match res {
+			mlua::Value::String(content) => Ok(content.to_string_lossy()),
+			res => {
+				let value = lua_value_to_serde_value(res).map_err(|err| err.to_string())?;
+				Ok(value.to_string())
+			}
+		}
+	}
+}
+
+/// Exec the chat with the agent tools, calling the tool handlers of the AI tool calls
+/// and sending their results back, until the AI returns a final answer.
+/// Each tool call and result is logged (`log` table, AI stage of the task).
+///
+/// Returns the final chat response (with the usage of all the AI calls) with the model which answered it.
+#[allow(clippy::too_many_arguments)]
+pub async fn exec_chat_with_tools(
+	runtime: &Runtime,
+	client: &genai::Client,
+	options: &AgentOptions,
+	ai_tools: &AiTools,
+	run_id: Id,
+	task_id: Id,
+	model_resolved: &ModelName,
+	mock_dir: Option<&SPath>,
+	chat_req: ChatRequest,
+	chat_options: &ChatOptions,
+) -> Result<(ChatResponse, ModelName)> {
+	let rt_log = runtime.rt_log();
+
+	let mut chat_req = chat_req;
+	let mut model = model_resolved.clone();
+	let mut usage = Usage::default();
+
+	for _ in 0..MAX_TOOL_TURNS {
+		let (mut chat_res, model_used) = exec_chat_with_fallback(
+			runtime,
+			client,
+			options,
+			run_id,
+			Some(task_id),
+			&model,
+			mock_dir,
+			chat_req.clone(),
+			chat_options,
+		)
+		.await?;
+		// NOTE: The next AI calls stay on the model which answered (when it fell back)
+		model = model_used;
+		add_usage(&mut usage, &chat_res.usage);
+
+		let tool_calls: Vec<ToolCall> = chat_res.tool_calls().into_iter().cloned().collect();
+		if tool_calls.is_empty() {
+			chat_res.usage = usage;
+			return Ok((chat_res, model));
+		}
+
+		if let Some(assistant_msg) = chat_res.into_assistant_message_for_tool_use() {
+			chat_req = chat_req.append_message(assistant_msg);
+		}
+
+		for tool_call in tool_calls {
+			let msg = format!(
+				"Tool call: {name}({args})",
+				name = tool_call.fn_name,
+				args = truncate_with_ellipsis(&tool_call.fn_arguments.to_string(), LOG_MAX_CHARS, "...")
+			);
+			rt_log.rec_log_ai(run_id, Some(task_id), msg, Some(LogKind::SysInfo)).await?;
+
+			let (content, msg, kind) = match ai_tools.call(&tool_call).await {
+				Ok(content) => {
+					let msg = format!(
+						"Tool result: {name} -> {content}",
+						name = tool_call.fn_name,
+						content = truncate_with_ellipsis(&content, LOG_MAX_CHARS, "...")
+					);
+					(content, msg, LogKind::SysInfo)
+				}
+				Err(err) => {
+					let msg = format!("Tool error: {name} -> {err}", name = tool_call.fn_name);
+					(format!("Error: {err}"), msg, LogKind::SysWarn)
+				}
+			};
+			rt_log.rec_log_ai(run_id, Some(task_id), msg, Some(kind)).await?;
+
+			chat_req = chat_req.append_message(ToolResponse::new(tool_call.call_id, content));
+		}
+	}
+
+	Err(Error::custom(format!(
+		"AI did not return a final answer after {MAX_TOOL_TURNS} tool-call turns"
+	)))
+}
+
+// region:    --- Support
+
+/// Parse the list of tools returned by the `# Tools` script
+fn parse_tools(value: mlua::Value) -> core::result::Result<Vec<AiTool>, String> {
+	let mlua::Value::Table(table) = value else {
+		return Err(format!(
+			"Must return a list of tools, but returned a {}",
+			value.type_name()
+		));
+	};
+
+	let mut tools: Vec<AiTool> = Vec::new();
+	for (idx, item) in table.sequence_values::<Table>().enumerate() {
+		let item = item.map_err(|err| format!("Tool #{} must be a table. {err}", idx + 1))?;
+		let name = item
+			.get::<Option<String>>("name")
+			.map_err(|err| err.to_string())?
+			.ok_or_else(|| format!("Tool #{} must have a 'name'", idx + 1))?;
+		let handler = item
+			.get::<Option<Function>>("handler")
+			.map_err(|err| format!("Tool '{name}' 'handler' must be a function. {err}"))?
+			.ok_or_else(|| format!("Tool '{name}' must have a 'handler' function"))?;
+		let description = item.get::<Option<String>>("description").map_err(|err| err.to_string())?;
+		let schema = match item.get::<mlua::Value>("schema").map_err(|err| err.to_string())? {
+			mlua::Value::Nil => None,
+			schema => Some(lua_value_to_serde_value(schema).map_err(|err| err.to_string())?),
+		};
+
+		if tools.iter().any(|tool| tool.name == name) {
+			return Err(format!("Tool '{name}' is defined more than once"));
+		}
+
+		tools.push(AiTool {
+			name,
+			description,
+			schema,
+			handler,
+		});
+	}
+
+	Ok(tools)
+}
+
+/// Add the usage of an AI call to the total usage (of the tool-call loop)
+fn add_usage(total: &mut Usage, usage: &Usage) {
+	fn add(total: &mut Option<i32>, value: Option<i32>) {
+		if let Some(value) = value {
+			*total = Some(total.unwrap_or_default() + value);
+		}
+	}
+
+	add(&mut total.prompt_tokens, usage.prompt_tokens);
+	add(&mut total.completion_tokens, usage.completion_tokens);
+	add(&mut total.total_tokens, usage.total_tokens);
+
+	if let Some(details) = usage.prompt_tokens_details.as_ref() {
+		let total_details = total.prompt_tokens_details.get_or_insert_with(Default::default);
+		add(&mut total_details.cached_tokens, details.cached_tokens);
+		add(&mut total_details.cache_creation_tokens, details.cache_creation_tokens);
+	}
+	if let Some(details) = usage.completion_tokens_details.as_ref() {
+		let total_details = total.completion_tokens_details.get_or_insert_with(Default::default);
+		add(&mut total_details.reasoning_tokens, details.reasoning_tokens);
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[tokio::test]
+	async fn test_ai_tools_load_and_call() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let agent = Agent::mock_from_content(
+			r#"
+# Tools
+
+```lua
+return {
+	{
+		name        = "add",
+		description = "Add two numbers",
+		schema      = { type = "object", properties = { a = { type = "number" }, b = { type = "number" } } },
+		handler     = function(args) return { sum = args.a + args.b, unit = data.unit } end
+	},
+	{
+		name    = "fail",
+		handler = function(args) error("not available") end
+	}
+}
+```
+
+# User
+
+Add 1 and 2
+"#,
+		)?;
+		let data = json!({"unit": "apples"});
+
+		// -- Exec
+		let ai_tools = AiTools::load(
+			&runtime,
+			&RuntimeCtx::default(),
+			&agent,
+			&Literals::default(),
+			&Value::Null,
+			&Value::Null,
+			&data,
+		)
+		.await?
+		.ok_or("Should have tools")?;
+		let add_call = ToolCall {
+			call_id: "call_1".to_string(),
+			fn_name: "add".to_string(),
+			fn_arguments: json!({"a": 1, "b": 2}),
+			thought_signatures: None,
+		};
+		let add_res = ai_tools.call(&add_call).await?;
+		let fail_err = ai_tools
+			.call(&ToolCall {
+				fn_name: "fail".to_string(),
+				..add_call.clone()
+			})
+			.await
+			.err()
+			.ok_or("Should fail")?;
+
+		// -- Check
+		let genai_tools = ai_tools.genai_tools();
+		assert_eq!(genai_tools.len(), 2);
+		assert_eq!(genai_tools[0].description.as_deref(), Some("Add two numbers"));
+		assert!(genai_tools[0].schema.is_some());
+		assert_eq!(
+			serde_json::from_str::<Value>(&add_res)?,
+			json!({"sum": 3, "unit": "apples"})
+		);
+		assert!(fail_err.contains("not available"), "err: {fail_err}");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,200 @@
+//! The record/replay cassette of the AI calls (`aip run --record DIR` / `aip run --replay DIR`).
+//!
+//! Each AI call is stored in the cassette dir as `<request_hash>.json`, with the model,
+//! the `ChatRequest`, and the `ChatResponse`. The request hash is computed from the model,
+//! the chat options, and the request, so a replay serves back the same response for the same request
+//! (and fails when a request was not recorded).
+
+use crate::support::text::blake3_b64u;
+use crate::{Error, Result};
+use genai::adapter::AdapterKind;
+use genai::chat::{ChatOptions, ChatRequest, ChatResponse, MessageContent, StopReason, Usage};
+use genai::{ModelIden, ModelName};
+use serde::{Deserialize, Serialize};
+use simple_fs::{SPath, ensure_dir};
+
+#[derive(Debug, Clone)]
+pub struct Cassette {
+	dir: SPath,
+	mode: CassetteMode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+	Record,
+	Replay,
+}
+
+/// The content of a cassette entry file
+#[derive(Debug, Serialize, Deserialize)]
+struct CassetteEntry {
+	model: String,
+	#[serde(skip_deserializing)]
+	request: ChatRequest,
+	response: CassetteResponse,
+}
+
+/// The recorded `ChatResponse`
+///
+/// NOTE: The genai `ModelName` does not deserialize from its serialized form,
+///       so the model idens are stored as `(adapter_kind, model_name)`.
+#[derive(Debug, Serialize, Deserialize)]
+struct CassetteResponse {
+	content: MessageContent,
+	reasoning_content: Option<String>,
+	model_iden: (AdapterKind, String),
+	provider_model_iden: (AdapterKind, String),
+	stop_reason: Option<StopReason>,
+	usage: Usage,
+}
+
+impl From<&ChatResponse> for CassetteResponse {
+	fn from(res: &ChatResponse) -> Self {
+		Self {
+			content: res.content.clone(),
+			reasoning_content: res.reasoning_content.clone(),
+			model_iden: (res.model_iden.adapter_kind, res.model_iden.model_name.to_string()),
+			provider_model_iden: (
+				res.provider_model_iden.adapter_kind,
+				res.provider_model_iden.model_name.to_string(),
+			),
+			stop_reason: res.stop_reason.clone(),
+			usage: res.usage.clone(),
+		}
+	}
+}
+
+impl From<CassetteResponse> for ChatResponse {
+	fn from(res: CassetteResponse) -> Self {
+		ChatResponse {
+			content: res.content,
+			reasoning_content: res.reasoning_content,
+			model_iden: ModelIden::new(res.model_iden.0, res.model_iden.1),
+			provider_model_iden: ModelIden::new(res.provider_model_iden.0, res.provider_model_iden.1),
+			stop_reason: res.stop_reason,
+			usage: res.usage,
+			captured_raw_body: None,
+			response_id: None,
+		}
+	}
+}
+
+/// Constructors
+impl Cassette {
+	pub fn new(dir: impl Into<SPath>, mode: CassetteMode) -> Self {
+		Self { dir: dir.into(), mode }
+	}
+}
+
+/// Getters
+impl Cassette {
+	pub fn is_replay(&self) -> bool {
+		matches!(self.mode, CassetteMode::Replay)
+	}
+}
+
+/// Record & Replay
+impl Cassette {
+	/// The hash of the request, which is the key of the cassette entry
+	pub fn request_hash(model: &ModelName, chat_options: &ChatOptions, chat_req: &ChatRequest) -> Result<String> {
+		let options_json = serde_json::to_string(chat_options)?;
+		let req_json = serde_json::to_string(chat_req)?;
+		Ok(blake3_b64u(&[model.as_str(), &options_json, &req_json]))
+	}
+
+	pub fn record(
+		&self,
+		request_hash: &str,
+		model: &ModelName,
+		chat_req: ChatRequest,
+		chat_res: &ChatResponse,
+	) -> Result<()> {
+		ensure_dir(&self.dir)?;
+		let entry = CassetteEntry {
+			model: model.to_string(),
+			request: chat_req,
+			response: chat_res.into(),
+		};
+		simple_fs::save_json_pretty(self.entry_path(request_hash), &entry)?;
+		Ok(())
+	}
+
+	/// Returns the recorded model (which answered) and response of this request
+	pub fn replay(&self, request_hash: &str, model: &ModelName) -> Result<(ModelName, ChatResponse)> {
+		self.find(request_hash)?.ok_or_else(|| {
+			Error::custom(format!(
+				"No cassette entry for the request to '{model}' (request hash: {request_hash}).\nCassette file not found: {}",
+				self.entry_path(request_hash)
+			))
+		})
+	}
+
+	/// Returns the recorded model (which answered, e.g., a fallback model) and response of this request, if any
+	/// (also used for the `cache_responses` responses cache)
+	pub fn find(&self, request_hash: &str) -> Result<Option<(ModelName, ChatResponse)>> {
+		let path = self.entry_path(request_hash);
+		if !path.is_file() {
+			return Ok(None);
+		}
+		let entry: CassetteEntry = simple_fs::load_json(&path)?;
+		Ok(Some((entry.model.into(), entry.response.into())))
+	}
+
+	fn entry_path(&self, request_hash: &str) -> SPath {
+		self.dir.join(format!("{request_hash}.json"))
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::{assert_contains, gen_test_dir_path, remove_test_dir};
+	use genai::chat::ChatMessage;
+
+	#[test]
+	fn test_run_cassette_record_replay_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let dir = gen_test_dir_path().join("cassette");
+		let model = ModelName::from("gpt-5-mini");
+		let options = ChatOptions::default();
+		let chat_req = ChatRequest::from_messages(vec![ChatMessage::user("hello")]);
+		let model_iden = ModelIden::new(AdapterKind::OpenAI, model.clone());
+		let chat_res = ChatResponse {
+			content: MessageContent::from_text("world"),
+			reasoning_content: None,
+			model_iden: model_iden.clone(),
+			provider_model_iden: model_iden,
+			stop_reason: None,
+			usage: Usage::default(),
+			captured_raw_body: None,
+			response_id: None,
+		};
+		let other_req = ChatRequest::from_messages(vec![ChatMessage::user("hello!")]);
+
+		// -- Exec
+		let hash = Cassette::request_hash(&model, &options, &chat_req)?;
+		Cassette::new(dir.clone(), CassetteMode::Record).record(&hash, &model, chat_req, &chat_res)?;
+		let replay = Cassette::new(dir.clone(), CassetteMode::Replay);
+		let (res_model, res) = replay.replay(&hash, &model)?;
+
+		// -- Check
+		assert_eq!(res_model, model);
+		assert_eq!(res.content.joined_texts().as_deref(), Some("world"));
+		let other_hash = Cassette::request_hash(&model, &options, &other_req)?;
+		assert_ne!(hash, other_hash);
+		let err = replay.replay(&other_hash, &model).err().ok_or("Should fail")?;
+		assert_contains(&err.to_string(), "No cassette entry");
+		assert!(replay.find(&other_hash)?.is_none());
+
+		// -- Clean
+		remove_test_dir(&dir.parent().ok_or("Should have parent")?)?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
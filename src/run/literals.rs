@@ -3,6 +3,7 @@ use crate::agent::{Agent, AgentRef};
 use crate::dir_context::join_support_pack_ref;
 use crate::runtime::Runtime;
 use crate::script::LuaEngine;
+use serde_json::{Map, Value};
 use std::sync::Arc;
 
 /// TODO: Will need to put the Vec in Arc, since this clone what a bit
@@ -11,6 +12,9 @@ pub struct Literals {
 	/// The store of all literals, pattern and value
 	/// e.g. `vec![("&AIPACK_AGENT_DIR","./.aipack/custom/command-agent/some.aipack")]`
 	store: Arc<Vec<(&'static str, String)>>,
+
+	/// The run params (`--param key=value`), exposed as `CTX.PARAMS`
+	params: Arc<Map<String, Value>>,
 }
 
 /// Constructors
@@ -87,7 +91,14 @@ impl Literals {
 		store.push(("AGENT_FILE_DIR", agent_dir.to_string()));
 		store.push(("AGENT_FILE_STEM", agent_path.stem().to_string()));
 
-		Ok(Self { store: Arc::new(store) })
+		Ok(Self {
+			store: Arc::new(store),
+			params: Arc::default(),
+		})
+	}
+
+	pub fn with_params(self, params: Arc<Map<String, Value>>) -> Self {
+		Self { params, ..self }
 	}
 }
 
@@ -106,7 +117,14 @@ impl Literals {
 	pub fn append(&self, pattern: &'static str, value: impl Into<String>) -> Self {
 		let mut store = self.store.as_ref().clone();
 		store.push((pattern, value.into()));
-		Self { store: Arc::new(store) }
+		Self {
+			store: Arc::new(store),
+			params: self.params.clone(),
+		}
+	}
+
+	pub fn params(&self) -> &Map<String, Value> {
+		&self.params
 	}
 }
 
@@ -119,6 +137,8 @@ impl Literals {
 		for (name, value) in self.as_strs() {
 			table.set(name, value)?;
 		}
+		let params = lua_engine.serde_to_lua_value(Value::Object(self.params.as_ref().clone()))?;
+		table.set("PARAMS", params)?;
 		Ok(mlua::Value::Table(table))
 	}
 }
@@ -0,0 +1,210 @@
+//! The offline `mock::` model namespace (e.g., `model = "mock::default"`).
+//!
+//! Instead of calling a provider, the response content is read from the mock dir of the agent
+//! (`<agent_dir>/mocks/<agent_stem>/`, or `$AIPACK_MOCK_DIR/<agent_stem>/` when set,
+//! or the mock dir of the run options, e.g., the one of the `aip test` case),
+//! also for the `aip.ai.chat` calls of the agent,
+//! from the first file found of:
+//!
+//! - `<prompt_hash>.md` - The hash of the rendered prompt messages (given in the error when not found)
+//! - `task-<task_idx>.md` - The task index (0 based)
+//! - `default.md`
+//!
+//! The special `mock::echo` model returns the last user message when no file is found,
+//! and the `mock::error-<status>` models (e.g., `mock::error-503`) fail with this HTTP status
+//! (e.g., to test the `fallback_on` of a model chain).
+//!
+//! The `Usage` is synthetic (~4 chars per token), and the cost is zero, so that the run and task records are complete.
+//!
+//! NOTE: genai does not have a mock adapter kind, so the `ModelIden` of a mock response has a placeholder adapter kind.
+//!       It is never reported or priced: use `adapter_name` for the reported adapter (`Mock`),
+//!       and the pricing skips the `mock::` models.
+
+use crate::model::{Id, TaskBmc};
+use crate::runtime::Runtime;
+use crate::support::text::blake3_b64u;
+use crate::{Error, Result};
+use genai::adapter::AdapterKind;
+use genai::chat::{ChatRequest, ChatResponse, ChatRole, MessageContent, StopReason, Usage};
+use genai::{ModelIden, ModelName};
+use simple_fs::SPath;
+
+const MOCK_NAMESPACE: &str = "mock";
+const MOCK_ECHO_NAME: &str = "echo";
+const MOCK_ERROR_PREFIX: &str = "error-";
+const MOCK_DIR_ENV: &str = "AIPACK_MOCK_DIR";
+const MOCK_ADAPTER_NAME: &str = "Mock";
+/// The placeholder adapter kind of the mock responses model iden (see module doc)
+const MOCK_PLACEHOLDER_ADAPTER_KIND: AdapterKind = AdapterKind::Ollama;
+
+/// Returns true if the model is in the `mock::` namespace
+pub fn is_mock_model(model: &ModelName) -> bool {
+	model.namespace_is(MOCK_NAMESPACE)
+}
+
+/// The reported adapter name of the model iden (e.g., `OpenAI`), `Mock` for the `mock::` models
+pub fn adapter_name(model_iden: &ModelIden) -> &'static str {
+	if is_mock_model(&model_iden.model_name) {
+		MOCK_ADAPTER_NAME
+	} else {
+		model_iden.adapter_kind.as_str()
+	}
+}
+
+/// Returns the canned response for this chat request from the mock dir (see module doc)
+///
+/// NOTE: The `task-<task_idx>.md` file is only for the AI calls of a task.
+pub fn mock_exec_chat(
+	runtime: &Runtime,
+	mock_dir: &SPath,
+	task_id: Option<Id>,
+	model: &ModelName,
+	chat_req: &ChatRequest,
+) -> Result<ChatResponse> {
+	let (_, name) = model.namespace_and_name();
+	if let Some(status) = name.strip_prefix(MOCK_ERROR_PREFIX) {
+		return Err(mock_http_error(model, status)?.into());
+	}
+
+	let prompt = prompt_text(chat_req);
+	let prompt_hash = prompt_hash(&prompt);
+
+	let mut candidates = vec![mock_dir.join(format!("{prompt_hash}.md"))];
+	if let Some(task_id) = task_id {
+		let task_idx = TaskBmc::get(runtime.mm(), task_id)?.idx.unwrap_or_default();
+		candidates.push(mock_dir.join(format!("task-{task_idx}.md")));
+	}
+	candidates.push(mock_dir.join("default.md"));
+
+	let content = match candidates.iter().find(|path| path.is_file()) {
+		Some(path) => simple_fs::read_to_string(path)?,
+		None if name == MOCK_ECHO_NAME => last_user_text(chat_req).unwrap_or_default(),
+		None => {
+			let tried = candidates.iter().map(|p| format!("  - {p}")).collect::<Vec<_>>().join("\n");
+			return Err(Error::custom(format!(
+				"No mock response found for model '{model}' (prompt hash: {prompt_hash}). Tried:\n{tried}"
+			)));
+		}
+	};
+
+	let model_iden = ModelIden::new(MOCK_PLACEHOLDER_ADAPTER_KIND, model.clone());
+	let prompt_tokens = approx_tokens(&prompt);
+	let completion_tokens = approx_tokens(&content);
+
+	Ok(ChatResponse {
+		content: MessageContent::from_text(content),
+		reasoning_content: None,
+		model_iden: model_iden.clone(),
+		provider_model_iden: model_iden,
+		stop_reason: Some(StopReason::Completed("stop".to_string())),
+		usage: Usage {
+			prompt_tokens: Some(prompt_tokens),
+			completion_tokens: Some(completion_tokens),
+			total_tokens: Some(prompt_tokens + completion_tokens),
+			..Default::default()
+		},
+		captured_raw_body: None,
+		response_id: None,
+	})
+}
+
+// region:    --- Support
+
+/// The mock dir of the agent file (see module doc)
+pub fn agent_mock_dir(agent_file: &SPath) -> Result<SPath> {
+	let agent_stem = agent_file.stem().to_string();
+	let base_dir = match std::env::var(MOCK_DIR_ENV) {
+		Ok(dir) => SPath::new(dir),
+		Err(_) => agent_file.parent().ok_or("Agent does not have a parent dir")?.join("mocks"),
+	};
+	Ok(base_dir.join(agent_stem))
+}
+
+/// The HTTP error of a `mock::error-<status>` model
+fn mock_http_error(model: &ModelName, status: &str) -> Result<genai::Error> {
+	let status = status
+		.parse::<u16>()
+		.ok()
+		.and_then(|status| reqwest::StatusCode::from_u16(status).ok())
+		.ok_or_else(|| Error::custom(format!("Mock model '{model}' does not have a valid HTTP status")))?;
+	Ok(genai::Error::HttpError {
+		status,
+		canonical_reason: status.canonical_reason().unwrap_or_default().to_string(),
+		body: format!("Mock error of model '{model}'"),
+		headers: Default::default(),
+	})
+}
+
+/// The prompt as `role:\ncontent` blocks (system first)
+fn prompt_text(chat_req: &ChatRequest) -> String {
+	let mut prompt = String::new();
+	if let Some(system) = chat_req.system.as_deref() {
+		prompt.push_str(&format!("system:\n{system}\n"));
+	}
+	for msg in chat_req.messages.iter() {
+		let content = msg.content.joined_texts().unwrap_or_default();
+		prompt.push_str(&format!("{}:\n{content}\n", msg.role));
+	}
+	prompt
+}
+
+fn prompt_hash(prompt: &str) -> String {
+	blake3_b64u(&[prompt]).chars().take(16).collect()
+}
+
+fn last_user_text(chat_req: &ChatRequest) -> Option<String> {
+	chat_req
+		.messages
+		.iter()
+		.rev()
+		.find(|msg| matches!(msg.role, ChatRole::User))
+		.and_then(|msg| msg.content.joined_texts())
+}
+
+fn approx_tokens(text: &str) -> i32 {
+	(text.chars().count() as i32 + 3) / 4
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use genai::chat::ChatMessage;
+
+	#[test]
+	fn test_run_mock_chat_is_mock_model() -> Result<()> {
+		assert!(is_mock_model(&ModelName::from("mock::default")));
+		assert!(!is_mock_model(&ModelName::from("mock-model")));
+		assert!(!is_mock_model(&ModelName::from("gpt-5-mini")));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_run_mock_chat_prompt_hash_stable() -> Result<()> {
+		// -- Setup & Fixtures
+		let req_a = ChatRequest::from_messages(vec![ChatMessage::system("sys"), ChatMessage::user("hello")]);
+		let req_b = ChatRequest::from_messages(vec![ChatMessage::system("sys"), ChatMessage::user("hello")]);
+		let req_c = ChatRequest::from_messages(vec![ChatMessage::system("sys"), ChatMessage::user("hello!")]);
+
+		// -- Exec
+		let hash_a = prompt_hash(&prompt_text(&req_a));
+		let hash_b = prompt_hash(&prompt_text(&req_b));
+		let hash_c = prompt_hash(&prompt_text(&req_c));
+
+		// -- Check
+		assert_eq!(hash_a.len(), 16);
+		assert_eq!(hash_a, hash_b);
+		assert_ne!(hash_a, hash_c);
+		assert_eq!(last_user_text(&req_c).as_deref(), Some("hello!"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,25 +1,40 @@
 // region:    --- Modules
+mod ai_exec;
+mod ai_fallback;
+mod ai_rate_limit;
+mod ai_retry;
+mod ai_stream;
+mod ai_tools;
 mod literals;
+mod mock_chat;
 mod pricing;
 mod proc_after_all;
 mod proc_ai;
 mod proc_before_all;
 mod proc_data;
 mod proc_output;
+mod response_schema;
 mod run_agent_task;
+mod run_deadline;
 
 mod ai_response;
+mod cassette;
 mod genai_client;
 mod run_agent;
 mod run_executor;
 mod run_types;
 
+pub(crate) use ai_exec::{AiExecRes, exec_ai_chat};
 pub use ai_response::*;
+pub use cassette::*;
 pub use genai_client::*;
 pub use literals::Literals;
+pub(crate) use mock_chat::adapter_name;
 pub use pricing::ModelPricing;
+pub(crate) use pricing::price_it;
 pub use run_agent::*;
-pub(crate) use run_agent::{RunAgentExecution, run_agent_with_identity};
+pub(crate) use run_agent::{RunAgentExecution, run_agent_task_replay, run_agent_with_identity};
+pub use run_deadline::RunDeadline;
 pub use run_executor::*;
 pub use run_types::*;
 
@@ -0,0 +1,136 @@
+use super::ModelPricing;
+use crate::model::Id;
+use crate::support::text::{format_f64_nonzero, format_num};
+use genai::chat::ChatMessage;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Approximate number of bytes per token (for the usual English text & code tokenizers)
+const BYTES_PER_TOKEN: usize = 4;
+
+/// The `--dry req` estimate of a task prompt (nothing is sent to the AI provider)
+#[derive(Debug, Clone, Copy)]
+pub struct DryReqEstimate {
+	pub prompt_tokens: i64,
+	/// The projected input cost in USD (None if no pricing for the model)
+	pub cost: Option<f64>,
+}
+
+impl DryReqEstimate {
+	pub fn new(chat_messages: &[ChatMessage], pricing: Option<&ModelPricing>) -> Self {
+		let prompt_size: usize = chat_messages.iter().map(|m| m.size()).sum();
+		let prompt_tokens = estimate_tokens(prompt_size);
+		let cost = pricing.map(|pricing| prompt_tokens as f64 * pricing.input_normal / 1_000_000.0);
+		Self { prompt_tokens, cost }
+	}
+}
+
+impl std::fmt::Display for DryReqEstimate {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "~{} prompt tokens", format_num(self.prompt_tokens))?;
+		match self.cost {
+			Some(cost) => write!(f, " | ~${} input cost", format_f64_nonzero(cost)),
+			None => write!(f, " | no pricing for model"),
+		}
+	}
+}
+
+/// The `--dry req` estimates of a run (sum of its tasks)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DryReqTotal {
+	pub task_count: usize,
+	pub prompt_tokens: i64,
+	pub cost: f64,
+	/// Number of tasks without pricing (not in the cost)
+	pub unpriced_count: usize,
+}
+
+impl std::fmt::Display for DryReqTotal {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{} task(s) | ~{} prompt tokens | ~${} input cost",
+			self.task_count,
+			format_num(self.prompt_tokens),
+			format_f64_nonzero(self.cost)
+		)?;
+		if self.unpriced_count > 0 {
+			write!(f, " ({} task(s) without model pricing)", self.unpriced_count)?;
+		}
+		Ok(())
+	}
+}
+
+/// Accumulates the `--dry req` task estimates per run
+#[derive(Debug, Default)]
+pub struct DryReqEstimates {
+	runs: Mutex<HashMap<Id, DryReqTotal>>,
+}
+
+impl DryReqEstimates {
+	pub fn add(&self, run_id: Id, estimate: DryReqEstimate) {
+		let mut runs = self.runs.lock().unwrap_or_else(|e| e.into_inner());
+		let total = runs.entry(run_id).or_default();
+		total.task_count += 1;
+		total.prompt_tokens += estimate.prompt_tokens;
+		match estimate.cost {
+			Some(cost) => total.cost += cost,
+			None => total.unpriced_count += 1,
+		}
+	}
+
+	/// Returns (and remove) the total of the run (None if no task estimate)
+	pub fn take(&self, run_id: Id) -> Option<DryReqTotal> {
+		let mut runs = self.runs.lock().unwrap_or_else(|e| e.into_inner());
+		runs.remove(&run_id)
+	}
+}
+
+/// The approximate number of tokens of a prompt size (in bytes)
+pub fn estimate_tokens(size: usize) -> i64 {
+	size.div_ceil(BYTES_PER_TOKEN) as i64
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_pricing_dry_estimate_total() -> Result<()> {
+		// -- Setup & Fixtures
+		let pricing = ModelPricing {
+			name: "test-model",
+			input_cached: None,
+			input_normal: 2.0,
+			output_normal: 8.0,
+			output_reasoning: None,
+		};
+		let messages = vec![ChatMessage::system("a".repeat(400)), ChatMessage::user("b".repeat(3600))];
+		let estimates = DryReqEstimates::default();
+		let run_id: Id = 1.into();
+
+		// -- Exec
+		let estimate = DryReqEstimate::new(&messages, Some(&pricing));
+		estimates.add(run_id, estimate);
+		estimates.add(run_id, estimate);
+		estimates.add(run_id, DryReqEstimate::new(&messages, None));
+
+		// -- Check
+		assert_eq!(estimate.prompt_tokens, 1000);
+		assert_eq!(estimate.cost, Some(0.002));
+		let total = estimates.take(run_id).ok_or("Should have total")?;
+		assert_eq!(total.task_count, 3);
+		assert_eq!(total.prompt_tokens, 3000);
+		assert!((total.cost - 0.004).abs() < 1e-9);
+		assert_eq!(total.unpriced_count, 1);
+		assert!(estimates.take(run_id).is_none());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,11 +1,13 @@
 // region:    --- Modules
 
+mod dry_estimate;
 mod pricer;
 mod pricing_types;
 
 // endregion: --- Modules
 
 // region:    --- Public API
+pub use dry_estimate::{DryReqEstimate, DryReqEstimates, estimate_tokens};
 pub use pricer::{model_pricing, price_it};
 pub use pricing_types::ModelPricing;
 
@@ -1,7 +1,8 @@
 use super::ModelPricing;
 use crate::model::AiPrice;
-use genai::ModelIden;
+use crate::run::mock_chat::is_mock_model;
 use genai::chat::Usage;
+use genai::{ModelIden, ModelName};
 
 /// Calculates the price for a given provider type, model name, and usage.
 ///
@@ -12,7 +13,16 @@ use genai::chat::Usage;
 ///
 /// # Returns
 /// * `Option<PriceResult>` - The calculated price information, or None if the provider or model was not found
+///   (the `mock::` models are free)
 pub fn price_it(provider_type: &str, model_name: &str, usage: &Usage) -> Option<AiPrice> {
+	if is_mock_model(&ModelName::from(model_name)) {
+		return Some(AiPrice {
+			cost: 0.0,
+			cost_cache_write: None,
+			cost_cache_saving: None,
+		});
+	}
+
 	let ai_cost = aicost::compute(provider_type, model_name, usage).ok()?;
 	Some(AiPrice {
 		cost: ai_cost.total,
@@ -21,7 +31,12 @@ pub fn price_it(provider_type: &str, model_name: &str, usage: &Usage) -> Option<
 	})
 }
 
+/// NOTE: None for the `mock::` models (free, see `price_it`)
 pub fn model_pricing(model_iden: &ModelIden) -> Option<ModelPricing> {
+	if is_mock_model(&model_iden.model_name) {
+		return None;
+	}
+
 	let pricing = aicost::model_pricing(model_iden)?;
 
 	Some(ModelPricing {
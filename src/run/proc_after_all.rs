@@ -49,7 +49,12 @@ pub async fn process_after_all(
 		rt_step.step_aa_start(run_id).await?;
 
 		let lua_value = lua_engine
-			.eval_with_paths(after_all_script, Some(lua_scope), agent.context_dirs())
+			.eval_with_paths(
+				after_all_script,
+				Some(lua_scope),
+				agent.context_dirs(),
+				agent.script_location(Stage::AfterAll).as_ref(),
+			)
 			.await?;
 
 		// -- Rt Step - After All End
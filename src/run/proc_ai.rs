@@ -2,12 +2,15 @@ use crate::Result;
 use crate::agent::{Agent, AgentOptions, PromptPart, parse_prompt_part_options};
 use crate::hub::get_hub;
 use crate::model::{AiPrice, Id};
-use crate::run::pricing::{model_pricing, price_it};
+use crate::run::ai_exec::{AiExecRes, exec_ai_chat};
+use crate::run::ai_tools::AiTools;
+use crate::run::mock_chat::adapter_name;
+use crate::run::pricing::{DryReqEstimate, model_pricing, price_it};
 use crate::run::{AiResponse, Attachments, DryMode, RunBaseOptions};
 use crate::runtime::Runtime;
 use crate::support::hbs::hbs_render;
 use crate::support::text::{self, format_duration, format_usage};
-use genai::chat::{CacheControl, ChatMessage, ChatOptions, ChatRequest, ChatResponse, ContentPart};
+use genai::chat::{CacheControl, ChatMessage, ChatRequest, ChatResponse, ContentPart, Usage};
 use genai::{ModelIden, ModelName};
 use serde_json::Value;
 use simple_fs::SPath;
@@ -25,6 +28,7 @@ pub fn build_chat_messages(
 	before_all: &Value,
 	input: &Value,
 	data: &Value,
+	params: &Value,
 	attachments: &Attachments,
 ) -> Result<Vec<ChatMessage>> {
 	let data_scope = HashMap::from([
@@ -33,6 +37,7 @@ pub fn build_chat_messages(
 		("data", data),
 		("input", input),
 		("before_all", before_all),
+		("params", params),
 	]);
 
 	let mut chat_messages: Vec<ChatMessage> = Vec::new();
@@ -91,6 +96,7 @@ File Name: '{file_name}'
 			kind,
 			content,
 			options_str,
+			..
 		} = prompt_part;
 
 		// Note: If we have an options_str, then add it as the first line
@@ -144,6 +150,8 @@ pub async fn process_ai(
 	run_id: Id,
 	task_id: Id,
 	agent: Agent,
+	response_schema: Option<&Value>,
+	ai_tools: Option<&AiTools>,
 	chat_messages: Vec<ChatMessage>,
 ) -> Result<ProcAiResponse> {
 	let hub = get_hub();
@@ -166,9 +174,22 @@ pub async fn process_ai(
 		}
 	}
 
-	// if dry_mode req, we stop
+	// if dry_mode req, we stop (with the prompt tokens & cost estimate)
 	// NOTE: dry_mode will be checked also upstream
 	if matches!(run_base_options.dry_mode(), DryMode::Req) {
+		if !is_inst_empty {
+			let model_resolved = agent.model_resolved();
+			// NOTE: Fallback on the default model iden, since the service target needs the auth (not required in dry mode)
+			let model_iden = match client.resolve_service_target(model_resolved).await {
+				Ok(service_target) => Some(service_target.model),
+				Err(_) => client.default_model(model_resolved).ok(),
+			};
+			let pricing = model_iden.as_ref().and_then(model_pricing);
+			let estimate = DryReqEstimate::new(&chat_messages, pricing.as_ref());
+			hub.publish(format!("-> Dry req estimate for {model_resolved}: {estimate}"))
+				.await;
+			run_base_options.dry_req_estimates().add(run_id, estimate);
+		}
 		return Ok(ProcAiResponse { ai_response: None });
 	}
 
@@ -193,6 +214,8 @@ pub async fn process_ai(
 			run_id,
 			task_id,
 			model_resolved,
+			response_schema,
+			ai_tools,
 			chat_messages,
 		)
 		.await;
@@ -221,17 +244,19 @@ async fn process_send_to_genai(
 	run_id: Id,
 	task_id: Id,
 	model_resolved: &ModelName,
+	response_schema: Option<&Value>,
+	ai_tools: Option<&AiTools>,
 	chat_messages: Vec<ChatMessage>,
 ) -> Result<AiResponse> {
 	let hub = get_hub();
 
 	let rt_model = runtime.rt_model();
 
-	let has_cache_control = chat_messages
-		.iter()
-		.any(|message| message.options.as_ref().is_some_and(|options| options.cache_control.is_some()));
-
 	let chat_req = ChatRequest::from_messages(chat_messages);
+	let chat_req = match ai_tools {
+		Some(ai_tools) => chat_req.with_tools(ai_tools.genai_tools()),
+		None => chat_req,
+	};
 
 	hub.publish(format!("-> Sending rendered instruction to {model_resolved} ..."))
 		.await;
@@ -245,18 +270,37 @@ async fn process_send_to_genai(
 
 	let start = Instant::now();
 
-	// compute the cache options with the eventual cache key
-	// Note: For now, we use the runtime session as the key. Later, we will allow payload to provide it
-	let c_chat_options: Cow<ChatOptions> = if has_cache_control {
-		let opts = agent.genai_chat_options().clone();
-		Cow::Owned(opts.with_prompt_cache_key(runtime.session_str().to_string()))
-	} else {
-		Cow::Borrowed(agent.genai_chat_options())
-	};
-
-	let chat_res = client
-		.exec_chat(model_resolved, chat_req, Some(c_chat_options.as_ref()))
-		.await?;
+	// -- Exec the chat (or replay it from the cassette, or serve it from the responses cache)
+	let AiExecRes {
+		chat_res,
+		model_used,
+		is_cached,
+		response_json,
+	} = exec_ai_chat(
+		runtime,
+		client,
+		&agent.options(),
+		run_base_options,
+		&SPath::new(agent.file_path()),
+		run_id,
+		Some(task_id),
+		model_resolved,
+		ai_tools,
+		chat_req,
+		agent.genai_chat_options(),
+		response_schema,
+	)
+	.await?;
+
+	// -- Rt Update Task - Model (when the AI call fell back on another model of the chain)
+	if model_used != *model_resolved {
+		rt_model.update_task_model_ov(run_id, task_id, &model_used).await?;
+		if let Ok(service_target) = client.resolve_service_target(&model_used).await
+			&& let Some(pricing) = model_pricing(&service_target.model)
+		{
+			let _ = rt_model.update_task_model_pricing(run_id, task_id, &pricing).await;
+		}
+	}
 	let duration = start.elapsed();
 
 	// region:    --- First Info Part
@@ -268,14 +312,17 @@ async fn process_send_to_genai(
 
 	let mut info = duration_msg;
 
-	// Compute the price
-	let ai_price = get_price(&chat_res);
+	// Compute the price (a cached response has no cost)
+	let ai_price = if is_cached { None } else { get_price(&chat_res) };
 	let price_usd = ai_price.as_ref().map(|ap| ap.cost);
 
 	// -- Rt Rec - Update Cost
-	if let Some(ref ai_price) = ai_price {
+	if is_cached {
+		let _ = rt_model.update_task_ai_cached(run_id, task_id).await;
+		info = format!("{info} | cached");
+	} else if let Some(ref ai_price) = ai_price {
 		let _ = rt_model
-			.update_task_cost(
+			.add_task_cost(
 				run_id,
 				task_id,
 				ai_price.cost,
@@ -311,10 +358,24 @@ async fn process_send_to_genai(
 	} = chat_res;
 
 	// -- Rt Rec - Update Task Usage
+	// NOTE: A cached response has no usage (its tokens were recorded by the call which was cached)
+	let task_usage = if is_cached {
+		Usage {
+			prompt_tokens: Some(0),
+			completion_tokens: Some(0),
+			total_tokens: Some(0),
+			..Default::default()
+		}
+	} else {
+		usage.clone()
+	};
 	rt_model
-		.update_task_usage(run_id, task_id, &usage, &provider_model_iden)
+		.update_task_usage(run_id, task_id, &task_usage, &provider_model_iden)
 		.await?;
 
+	// NOTE: The response schema error is returned after the cost & usage are recorded
+	let ai_response_json = response_json.transpose()?;
+
 	let ai_response_content = content.into_joined_texts().filter(|s| !s.is_empty());
 	let ai_response_reasoning_content = reasoning_content;
 
@@ -328,12 +389,14 @@ async fn process_send_to_genai(
 	}
 
 	let info = format!("{info} | {model_info}",);
+	let adapter_kind = adapter_name(&res_model_iden);
 
 	Ok(AiResponse {
 		content: ai_response_content,
+		json: ai_response_json,
 		reasoning_content: ai_response_reasoning_content,
 		model_name: res_model_iden.model_name,
-		adapter_kind: res_model_iden.adapter_kind,
+		adapter_kind,
 		duration_sec,
 		price_usd,
 		usage,
@@ -382,7 +445,7 @@ fn format_model(
 
 	format!(
 		"{model_section}| Adapter: {adapter_kind}{temp_section}{top_p_section}",
-		adapter_kind = res_model_iden.adapter_kind,
+		adapter_kind = adapter_name(res_model_iden),
 	)
 }
 
@@ -104,7 +104,12 @@ async fn process_before_all_script(
 
 	// -- Exec the script
 	let lua_value = lua_engine
-		.eval_with_paths(before_all_script, Some(lua_scope), agent.context_dirs())
+		.eval_with_paths(
+			before_all_script,
+			Some(lua_scope),
+			agent.context_dirs(),
+			agent.script_location(Stage::BeforeAll).as_ref(),
+		)
 		.await?;
 	let before_all_res = serde_json::to_value(lua_value)?;
 
@@ -75,7 +75,12 @@ pub async fn process_data(
 
 		// -- Exec
 		let lua_value = lua_engine
-			.eval_with_paths(data_script, Some(lua_scope), agent.context_dirs())
+			.eval_with_paths(
+				data_script,
+				Some(lua_scope),
+				agent.context_dirs(),
+				agent.script_location(Stage::Data).as_ref(),
+			)
 			.await?;
 		let data_res = serde_json::to_value(lua_value)?;
 
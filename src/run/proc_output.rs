@@ -31,7 +31,12 @@ pub async fn process_output(
 		lua_scope.set("options", agent.options_as_ref())?;
 
 		let lua_value = lua_engine
-			.eval_with_paths(output_script, Some(lua_scope), agent.context_dirs())
+			.eval_with_paths(
+				output_script,
+				Some(lua_scope),
+				agent.context_dirs(),
+				agent.script_location(Stage::Output).as_ref(),
+			)
 			.await?;
 		let output_response = serde_json::to_value(lua_value)?;
 
@@ -0,0 +1,122 @@
+use crate::agent::{Agent, ResponseSchema};
+use crate::dir_context::PathResolver;
+use crate::runtime::Runtime;
+use crate::support::json_schema::validate_json_schema;
+use crate::support::jsons::load_json_to_serde_value;
+use crate::support::tomls::parse_toml_into_json;
+use crate::{Error, Result};
+use serde_json::Value;
+use simple_fs::SPath;
+
+/// Returns the JSON schema of the agent `response_schema` option (None if not set).
+///
+/// A schema path is relative to the agent file directory (can also be `~/...` or a pack ref),
+/// and can be a `.json` (or jsonc) or a `.toml` file.
+pub fn load_response_schema(runtime: &Runtime, agent: &Agent) -> Result<Option<Value>> {
+	let options = agent.options();
+	let schema = match options.response_schema() {
+		None => return Ok(None),
+		Some(ResponseSchema::Inline(schema)) => schema.clone(),
+		Some(ResponseSchema::Path(inline)) if inline.trim_start().starts_with('{') => serde_json::from_str(inline)
+			.map_err(|err| Error::custom_and_cause("Invalid inline response_schema JSON", err))?,
+		Some(ResponseSchema::Path(path)) => {
+			let agent_dir = agent.file_dir()?;
+			let file = runtime.dir_context().resolve_path(
+				runtime.session(),
+				SPath::new(path),
+				PathResolver::WksDir,
+				Some(&agent_dir),
+			)?;
+			if !file.exists() {
+				return Err(Error::custom(format!("response_schema file '{file}' not found")));
+			}
+			if file.ext() == "toml" {
+				parse_toml_into_json(&simple_fs::read_to_string(&file)?)?
+			} else {
+				load_json_to_serde_value(&file)?
+					.ok_or_else(|| Error::custom(format!("response_schema file '{file}' is empty")))?
+			}
+		}
+	};
+
+	if !schema.is_object() {
+		return Err(Error::custom("response_schema must be a JSON schema object"));
+	}
+
+	Ok(Some(schema))
+}
+
+/// Parse the AI response content as JSON, and validate it against the response schema.
+///
+/// NOTE: A response within a markdown code block (e.g., ` ```json `) is accepted.
+pub fn parse_response_json(schema: &Value, content: Option<&str>) -> Result<Value> {
+	let content = strip_code_fence(content.unwrap_or_default().trim());
+
+	let value: Value = serde_json::from_str(content).map_err(|err| Error::AiResponseSchema {
+		cause: format!("The response is not valid JSON. {err}"),
+	})?;
+
+	validate_json_schema(schema, &value).map_err(|err| Error::AiResponseSchema { cause: err.to_string() })?;
+
+	Ok(value)
+}
+
+// region:    --- Support
+
+/// Returns the content of the markdown code block if the content is one code block
+fn strip_code_fence(content: &str) -> &str {
+	let Some(rest) = content.strip_prefix("```") else {
+		return content;
+	};
+	let Some(rest) = rest.strip_suffix("```") else {
+		return content;
+	};
+	// skip the eventual language of the code fence (e.g., `json`)
+	match rest.split_once('\n') {
+		Some((_lang, body)) => body.trim(),
+		None => content,
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_response_schema_parse_response_json() -> Result<()> {
+		// -- Setup & Fixtures
+		let schema = json!({
+			"type": "object",
+			"properties": { "label": { "type": "string" } },
+			"required": ["label"]
+		});
+
+		// -- Exec
+		let value = parse_response_json(&schema, Some("```json\n{\"label\": \"bug\"}\n```"))?;
+		let err = parse_response_json(&schema, Some(r#"{"other": 1}"#))
+			.err()
+			.ok_or("Should fail")?;
+		let not_json_err = parse_response_json(&schema, Some("Sure, here it is"))
+			.err()
+			.ok_or("Should fail")?;
+
+		// -- Check
+		assert_eq!(value, json!({"label": "bug"}));
+		assert!(matches!(err, Error::AiResponseSchema { .. }), "err: {err}");
+		assert!(
+			not_json_err.to_string().contains("not valid JSON"),
+			"err: {not_json_err}"
+		);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
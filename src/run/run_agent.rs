@@ -1,11 +1,13 @@
 use crate::agent::{Agent, AgentRef};
 use crate::hub::get_hub;
 use crate::model::{Id, LogKind, RuntimeCtx, Stage, TaskForCreate};
-use crate::run::RunBaseOptions;
 use crate::run::literals::Literals;
 use crate::run::proc_after_all::{ProcAfterAllResponse, process_after_all};
 use crate::run::proc_before_all::{ProcBeforeAllResponse, process_before_all};
+use crate::run::response_schema::load_response_schema;
 use crate::run::run_agent_task::run_agent_task_outer;
+use crate::run::run_deadline::with_deadline;
+use crate::run::{DryMode, RunBaseOptions, RunDeadline};
 use crate::runtime::Runtime;
 use crate::script::{AipackCustom, FromValue};
 use crate::types::RunAgentResponse;
@@ -70,13 +72,19 @@ pub(crate) async fn run_agent_with_identity(
 	} else {
 		rt_model.create_run(parent_uid, &agent).await?
 	};
-
 	// -- Rt Step - Start Run
 	let run_id = rt_step.step_run_start(run_id).await?;
 
 	let cancel_rx_opt = runtime.cancel_rx().cloned();
 
-	let run_future = run_agent_inner(runtime, run_id, agent, inputs, run_base_options, return_output_values);
+	let run_future = run_base_options.scope(run_agent_inner(
+		runtime,
+		run_id,
+		agent,
+		inputs,
+		run_base_options,
+		return_output_values,
+	));
 	tokio::pin!(run_future);
 
 	let (run_agent_res, canceled) = if let Some(cancel_rx) = cancel_rx_opt {
@@ -92,8 +100,12 @@ pub(crate) async fn run_agent_with_identity(
 	};
 
 	match run_agent_res.as_ref() {
-		// NOTE: Eventually we want to store the after all response as well
 		Ok(_ok_res) => {
+			// -- Rt Update - after all (before the run end step, so that it is part of it)
+			if let Some(after_all) = _ok_res.after_all.as_ref() {
+				rt_model.update_run_after_all(run_id, after_all).await?;
+			}
+
 			// -- Rt Step - End
 			if canceled {
 				rt_step.step_run_end_canceled(run_id).await?;
@@ -140,55 +152,108 @@ async fn run_agent_inner(
 	let rt_step = runtime.rt_step();
 	let rt_model = runtime.rt_model();
 
+	// -- The run deadline (from the `run_timeout` option)
+	let run_deadline = RunDeadline::for_run(&agent.options());
+
 	let base_rt_ctx =
 		RuntimeCtx::from_run_id(runtime, run_id)?.with_flow_redo_run_count(run_base_options.flow_redo_count());
 	rt_model
 		.update_run_flow_redo_count(run_id, run_base_options.flow_redo_count())
 		.await?;
 
+	// -- Validate the inputs and params against the agent `# Inputs` section (with their defaults)
+	// NOTE: When resuming, the inputs are the recorded task inputs, so they are not validated again
+	let (inputs, run_base_options) = match agent.inputs_schema() {
+		Some(schema) => {
+			let map_err = |err: Error| Error::AgentInputInvalid {
+				agent_name: agent.name().to_string(),
+				cause: err.to_string(),
+			};
+			let inputs = match inputs {
+				Some(inputs) if run_base_options.resume().is_none() => {
+					Some(schema.validate_inputs(inputs).map_err(map_err)?)
+				}
+				inputs => inputs,
+			};
+			let params = schema
+				.validate_params(run_base_options.params().as_ref().clone())
+				.map_err(map_err)?;
+			(inputs, &run_base_options.clone().with_params(params))
+		}
+		None => (inputs, run_base_options),
+	};
+	// -- Rt Update - params, once validated (so that a task can be replayed with the same params)
+	rt_model.update_run_params(run_id, run_base_options.params()).await?;
+
 	let literals = Literals::from_runtime_and_agent_path(runtime, &agent)?
+		.with_params(run_base_options.params().clone())
 		.append("RUN_FLOW_REDO_COUNT", run_base_options.flow_redo_count().to_string());
 
 	// -- Process Before All
-	// Rt Step - Start Before All
-	rt_step.step_ba_start(run_id).await?;
-	// process
-	let res = process_before_all(
-		runtime,
-		base_rt_ctx.clone(),
-		run_id,
-		agent.clone(),
-		literals.clone(),
-		inputs.clone(),
-	)
-	.await;
-	// Capture error if anyw
-	if let Err(err) = res.as_ref() {
-		rt_model.set_run_end_error(run_id, Some(Stage::BeforeAll), err)?;
-	}
-	// -- Rt Step - End Before All
-	rt_step.step_ba_end(run_id).await?;
+	// NOTE: When resuming, the Before All is not executed again, and the before all value and options overrides
+	//       of the resumed run are reused (the inputs are the recorded task inputs, which are the Before All ones)
+	let (before_all, agent, inputs, redo_ba) = if let Some(resume) = run_base_options.resume() {
+		rt_model.update_run_resume_of(run_id, resume.resume_of_id()).await?;
+		let agent = match resume.options() {
+			Some(options) => agent.new_merge(options.clone())?,
+			None => agent,
+		};
+		(resume.before_all().clone(), agent, inputs, false)
+	} else {
+		// Rt Step - Start Before All
+		rt_step.step_ba_start(run_id).await?;
+		// process
+		let res = with_deadline(
+			run_deadline,
+			process_before_all(
+				runtime,
+				base_rt_ctx.clone(),
+				run_id,
+				agent.clone(),
+				literals.clone(),
+				inputs.clone(),
+			),
+		)
+		.await;
+		// Capture error if anyw
+		if let Err(err) = res.as_ref() {
+			rt_model.set_run_end_error(run_id, Some(Stage::BeforeAll), err)?;
+		}
+		// -- Rt Step - End Before All
+		rt_step.step_ba_end(run_id).await?;
+
+		let ProcBeforeAllResponse {
+			before_all,
+			agent,
+			inputs,
+			skip,
+			redo: redo_ba,
+		} = res?;
+		// skip
+		if skip {
+			rt_model.set_run_end_state_to_skip(run_id)?;
+			return Ok(RunAgentResponse::default());
+		}
+		// redo
+		if redo_ba {
+			return Ok(RunAgentResponse {
+				redo_requested: true,
+				..Default::default()
+			});
+		}
+		(before_all, agent, inputs, redo_ba)
+	};
 
-	let ProcBeforeAllResponse {
-		before_all,
-		agent,
-		inputs,
-		skip,
-		redo: redo_ba,
-	} = res?;
-	// skip
-	if skip {
-		rt_model.set_run_end_state_to_skip(run_id)?;
-		return Ok(RunAgentResponse::default());
-	}
-	// redo
-	if redo_ba {
-		return Ok(RunAgentResponse {
-			redo_requested: true,
-			..Default::default()
-		});
+	// -- Rt Update - before all (so that a task can be replayed with the same value)
+	rt_model.update_run_before_all(run_id, &before_all).await?;
+	// -- Rt Update - options overrides (so that the run can be resumed with the same options)
+	if let Some(options_ov) = agent.options_ov() {
+		rt_model.update_run_options(run_id, options_ov).await?;
 	}
 
+	// -- Load the response schema (once for the run, a task only reloads it when its Data stage overrides it)
+	let response_schema = load_response_schema(runtime, &agent)?;
+
 	// -- Print the run info
 	print_run_info(runtime, run_id, &agent).await?;
 
@@ -223,11 +288,13 @@ async fn run_agent_inner(
 			runtime,
 			run_id,
 			&agent,
+			response_schema.as_ref(),
 			&literals,
 			run_base_options,
 			&before_all,
 			&inputs,
 			return_output_values,
+			run_deadline,
 		)
 		.await?;
 
@@ -260,15 +327,18 @@ async fn run_agent_inner(
 	// -- Process After All
 	// Rt Step - Start After All
 	rt_step.step_aa_start(run_id).await?;
-	let res = process_after_all(
-		runtime,
-		base_rt_ctx,
-		run_id,
-		&agent,
-		literals,
-		before_all,
-		inputs,
-		outputs,
+	let res = with_deadline(
+		run_deadline,
+		process_after_all(
+			runtime,
+			base_rt_ctx,
+			run_id,
+			&agent,
+			literals,
+			before_all,
+			inputs,
+			outputs,
+		),
 	)
 	.await;
 	// Capture error if any
@@ -297,6 +367,132 @@ async fn run_agent_inner(
 	})
 }
 
+/// Replay one task of a past run with its recorded input and before all value
+/// (the agent has the options overrides of the replayed run, if any).
+///
+/// The replay is recorded as a new run, linked to the replayed one with `run.replay_of_id`.
+/// NOTE: The Before All and After All stages are not executed.
+pub(crate) async fn run_agent_task_replay(
+	runtime: &Runtime,
+	agent: Agent,
+	replay_of_id: Id,
+	task_idx: usize,
+	before_all: Value,
+	input: Value,
+	run_base_options: &RunBaseOptions,
+) -> Result<RunAgentExecution> {
+	let rt_step = runtime.rt_step();
+	let rt_model = runtime.rt_model();
+
+	// -- Validate the params against the agent `# Inputs` section (with their defaults)
+	// NOTE: The recorded params might be from before the validation, or the agent file might have changed since
+	let run_base_options = &match agent.inputs_schema() {
+		Some(schema) => {
+			let params = schema
+				.validate_params(run_base_options.params().as_ref().clone())
+				.map_err(|err| Error::AgentInputInvalid {
+					agent_name: agent.name().to_string(),
+					cause: err.to_string(),
+				})?;
+			run_base_options.clone().with_params(params)
+		}
+		None => run_base_options.clone(),
+	};
+
+	// -- Rt Create - New run, linked to the replayed one
+	let run_id = rt_model.create_run(None, &agent).await?;
+	rt_model.update_run_replay_of(run_id, replay_of_id).await?;
+	rt_model.update_run_before_all(run_id, &before_all).await?;
+	if let Some(options_ov) = agent.options_ov() {
+		rt_model.update_run_options(run_id, options_ov).await?;
+	}
+	rt_model.update_run_params(run_id, run_base_options.params()).await?;
+
+	// -- Rt Step - Start Run
+	let run_id = rt_step.step_run_start(run_id).await?;
+
+	let res = replay_task_inner(runtime, run_id, &agent, task_idx, before_all, input, run_base_options).await;
+
+	match res.as_ref() {
+		Ok(_) => rt_step.step_run_end_ok(run_id).await?,
+		Err(err) => rt_step.step_run_end_err(run_id, err).await?,
+	}
+	runtime.file_write_manager().swap_if_used();
+
+	res.map(|response| RunAgentExecution {
+		run_id,
+		loop_id: None,
+		response,
+	})
+}
+
+async fn replay_task_inner(
+	runtime: &Runtime,
+	run_id: Id,
+	agent: &Agent,
+	task_idx: usize,
+	before_all: Value,
+	input: Value,
+	run_base_options: &RunBaseOptions,
+) -> Result<RunAgentResponse> {
+	let rt_step = runtime.rt_step();
+	let rt_model = runtime.rt_model();
+
+	let literals =
+		Literals::from_runtime_and_agent_path(runtime, agent)?.with_params(run_base_options.params().clone());
+
+	print_run_info(runtime, run_id, agent).await?;
+
+	let response_schema = load_response_schema(runtime, agent)?;
+
+	let _ = rt_model
+		.update_run_model_and_concurrency(run_id, agent.model_resolved(), 1)
+		.await;
+
+	// -- Rt Step - Tasks Start
+	rt_step.step_tasks_start(run_id).await?;
+
+	// -- Rt Create - The task, with the same idx as the replayed one
+	let task_id = rt_model.create_task(run_id, task_idx, &input).await?;
+
+	// -- Rt Step - Task Start
+	rt_step.step_task_start(run_id, task_id).await?;
+
+	let res = run_agent_task_outer(
+		run_id,
+		task_id,
+		task_idx,
+		runtime,
+		agent,
+		response_schema.as_ref(),
+		before_all,
+		input,
+		&literals,
+		run_base_options,
+	)
+	.await;
+
+	// -- Rt Step - Task End
+	let output = match res {
+		Ok((_, output)) => {
+			rt_step.step_task_end_ok(run_id, task_id).await?;
+			output
+		}
+		Err(err) => {
+			rt_step.step_task_end_err(run_id, task_id, &err).await?;
+			return Err(err);
+		}
+	};
+
+	// -- Rt Step - Tasks End
+	rt_step.step_tasks_end(run_id).await?;
+
+	Ok(RunAgentResponse {
+		outputs: Some(vec![output]),
+		..Default::default()
+	})
+}
+
 async fn print_run_info(runtime: &Runtime, run_id: Id, agent: &Agent) -> Result<()> {
 	let rt_log = runtime.rt_log();
 
@@ -341,11 +537,13 @@ async fn run_tasks(
 	runtime: &Runtime,
 	run_id: Id,
 	agent: &Agent,
+	response_schema: Option<&Value>,
 	literals: &Literals,
 	run_base_options: &RunBaseOptions,
 	before_all: &Value,
 	inputs: &[Value],
 	return_output_values: bool,
+	run_deadline: Option<RunDeadline>,
 ) -> Result<(Option<Vec<(usize, Value)>>, bool)> {
 	let rt_model = runtime.rt_model();
 
@@ -366,6 +564,12 @@ async fn run_tasks(
 		.update_run_model_and_concurrency(run_id, agent.model_resolved(), concurrency)
 		.await;
 
+	// -- Cost Budget - run max cost
+	let cost_budget = runtime.cost_budget();
+	cost_budget.set_run_max_cost(run_id, agent.options().max_cost_usd());
+	// The error which stopped the scheduling of the new tasks (budget or run timeout)
+	let mut stop_err: Option<Error> = None;
+
 	// -- Run the Tasks
 	let mut join_set = JoinSet::new();
 	let mut in_progress = 0;
@@ -391,14 +595,50 @@ async fn run_tasks(
 		.map(|(idx, (input, task_id))| (input, idx, task_id))
 		.collect();
 
+	// -- Log the resume info
+	let resume = run_base_options.resume();
+	if let Some(resume) = resume {
+		let msg = format!(
+			"Resuming run {}: {} of {} tasks reused (ended Ok), {} to run",
+			resume.resume_of_id(),
+			resume.ok_count(),
+			inputs.len(),
+			inputs.len().saturating_sub(resume.ok_count())
+		);
+		runtime.rt_log().rec_log_run(run_id, msg, Some(LogKind::SysInfo)).await?;
+	}
+
 	// -- Iterate and run each task (concurrency as setup)
 	for (input, task_idx, task_id) in input_idx_task_id_list {
 		if redo_requested {
 			break;
 		}
 
+		// -- Stop scheduling new tasks once the budget is exceeded or the run timed out
+		if let Some(reason) = cost_budget.exceeded_reason(run_id) {
+			stop_err = Some(Error::BudgetExceeded { reason });
+			break;
+		}
+		if let Some(run_deadline) = run_deadline.filter(|d| d.is_passed()) {
+			stop_err = Some(run_deadline.to_error());
+			break;
+		}
+
+		// -- When resuming, reuse the output of the task which ended Ok
+		if let Some(output) = resume.and_then(|resume| resume.ok_output(task_idx)) {
+			let rt_step = runtime.rt_step();
+			rt_step.step_task_start(run_id, task_id).await?;
+			rt_model.update_task_output(task_id, output).await?;
+			rt_step.step_task_end_ok(run_id, task_id).await?;
+			if let Some(captured_outputs) = captured_outputs.as_mut() {
+				captured_outputs.push((task_idx, output.clone()));
+			}
+			continue;
+		}
+
 		let runtime_clone = runtime.clone();
 		let agent_clone = agent.clone();
+		let response_schema_clone = response_schema.cloned();
 		let before_all_clone = before_all.clone();
 		let literals = literals.clone();
 
@@ -413,16 +653,22 @@ async fn run_tasks(
 			let _ = rt_step.step_task_start(run_id, task_id).await;
 
 			// Execute the command agent (this will perform do Data, Instruction, and Output stages)
-			let res = run_agent_task_outer(
-				run_id,
-				task_id,
-				task_idx,
-				&runtime_clone,
-				&agent_clone,
-				before_all_clone,
-				input,
-				&literals,
-				&base_run_config_clone,
+			// NOTE: The task is cancelled when its deadline (`task_timeout` or `run_timeout`) is passed
+			let task_deadline = RunDeadline::for_task(&agent_clone.options(), run_deadline);
+			let res = with_deadline(
+				task_deadline,
+				run_agent_task_outer(
+					run_id,
+					task_id,
+					task_idx,
+					&runtime_clone,
+					&agent_clone,
+					response_schema_clone.as_ref(),
+					before_all_clone,
+					input,
+					&literals,
+					&base_run_config_clone,
+				),
 			)
 			.await;
 
@@ -433,7 +679,9 @@ async fn run_tasks(
 					Ok((task_idx, output))
 				}
 				Err(err) => {
-					//
+					if matches!(err, Error::Timeout { .. }) {
+						let _ = rt.rt_model().set_task_end_error_at_current_stage(task_id, &err);
+					}
 					rt_step.step_task_end_err(run_id, task_id, &err).await?;
 					if allow_run_on_task_fail {
 						let err_val = serde_json::json!({ "error": err.to_string() });
@@ -465,6 +713,17 @@ async fn run_tasks(
 		}
 	}
 
+	if let Some(stop_err) = stop_err {
+		return Err(stop_err);
+	}
+
+	// -- Dry req - the run prompt tokens & cost estimate
+	if matches!(run_base_options.dry_mode(), DryMode::Req)
+		&& let Some(total) = run_base_options.dry_req_estimates().take(run_id)
+	{
+		get_hub().publish(format!("\n==== Dry req estimate: {total}")).await;
+	}
+
 	Ok((captured_outputs, redo_requested))
 }
 
@@ -506,7 +765,9 @@ pub async fn run_command_agent_input_for_test(
 ) -> Result<Option<Value>> {
 	use crate::run::run_agent_task::run_agent_task_outer;
 
-	let literals = Literals::from_runtime_and_agent_path(runtime, agent)?;
+	let literals =
+		Literals::from_runtime_and_agent_path(runtime, agent)?.with_params(run_base_options.params().clone());
+	let response_schema = load_response_schema(runtime, agent)?;
 
 	//NOTE: Need to reactive.
 	let (idx, output) = run_agent_task_outer(
@@ -515,6 +776,7 @@ pub async fn run_command_agent_input_for_test(
 		input_idx,
 		runtime,
 		agent,
+		response_schema.as_ref(),
 		before_all,
 		input,
 		&literals,
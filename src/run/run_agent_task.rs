@@ -1,16 +1,19 @@
 use crate::agent::Agent;
 use crate::hub::{HubEvent, get_hub};
 use crate::model::{Id, RuntimeCtx, Stage};
+use crate::run::ai_tools::AiTools;
 use crate::run::literals::Literals;
 use crate::run::proc_ai::{ProcAiResponse, build_chat_messages, process_ai};
 use crate::run::proc_data::{ProcDataResponse, process_data};
 use crate::run::proc_output::process_output;
+use crate::run::response_schema::load_response_schema;
 use crate::run::{AiResponse, DryMode, RunBaseOptions};
 use crate::runtime::Runtime;
 use crate::script::{AipackCustom, FromValue};
 use crate::{Error, Result};
 use serde::Serialize;
 use serde_json::Value;
+use std::borrow::Cow;
 use value_ext::JsonValueExt as _;
 
 // region:    --- Run Task Outer
@@ -24,6 +27,7 @@ pub async fn run_agent_task_outer(
 	input_idx: usize,
 	runtime: &Runtime,
 	agent: &Agent,
+	response_schema: Option<&Value>,
 	before_all: Value,
 	input: impl Serialize,
 	literals: &Literals,
@@ -39,18 +43,21 @@ pub async fn run_agent_task_outer(
 	let label = get_input_label(&input).unwrap_or_else(|| format!("{input_idx}"));
 	hub.publish(format!("\n==== Running input: {label}")).await;
 
-	let run_response = run_agent_task(
-		runtime,
-		run_id,
-		task_id,
-		agent,
-		before_all,
-		&label,
-		input,
-		literals,
-		run_base_options,
-	)
-	.await?;
+	// NOTE: The tasks are spawned, so the run base options are scoped again for their sub-agents
+	let run_response = run_base_options
+		.scope(run_agent_task(
+			runtime,
+			run_id,
+			task_id,
+			agent,
+			response_schema,
+			before_all,
+			&label,
+			input,
+			literals,
+			run_base_options,
+		))
+		.await?;
 
 	// if the response value is a String, then, print it
 	if let Some(response_txt) = run_response.as_ref().and_then(|r| r.as_str()) {
@@ -162,6 +169,7 @@ pub async fn run_agent_task(
 	run_id: Id,
 	task_id: Id,
 	agent: &Agent,
+	response_schema: Option<&Value>,
 	before_all_result: Value,
 	_label: &str,
 	input: Value,
@@ -175,6 +183,9 @@ pub async fn run_agent_task(
 
 	rt_step.step_task_schedule(run_id, task_id).await?;
 
+	// The `response_schema` option of the run (to know if the Data stage overrides it)
+	let run_response_schema_opt = agent.options().response_schema().cloned();
+
 	// -- Build Base Rt Context
 	let base_rt_ctx = RuntimeCtx::from_run_task_ids(runtime, Some(run_id), Some(task_id))?;
 
@@ -218,17 +229,54 @@ pub async fn run_agent_task(
 	// Rt Step - Start AI stage
 	rt_step.step_task_ai_start(run_id, task_id).await?;
 
-	let chat_messages = build_chat_messages(runtime, &agent, &before_all_result, &input, &data, &attachments)?;
-	let res = process_ai(
+	let params = Value::Object(literals.params().clone());
+	let chat_messages = build_chat_messages(
 		runtime,
-		client,
-		run_base_options,
-		&run_model_resolved,
-		run_id,
-		task_id,
-		agent.clone(),
-		chat_messages,
-	)
+		&agent,
+		&before_all_result,
+		&input,
+		&data,
+		&params,
+		&attachments,
+	)?;
+	let res = async {
+		// NOTE: The response schema of the run is only reloaded when the Data stage overrides it
+		let response_schema: Option<Cow<Value>> =
+			if agent.options().response_schema() != run_response_schema_opt.as_ref() {
+				load_response_schema(runtime, &agent)?.map(Cow::Owned)
+			} else {
+				response_schema.map(Cow::Borrowed)
+			};
+		// NOTE: The `# Tools` are only loaded for an actual AI call (their handlers are called by the AI),
+		//       so not in `--dry req` mode, nor without instruction.
+		let ai_tools = if chat_messages.is_empty() || matches!(run_base_options.dry_mode(), DryMode::Req) {
+			None
+		} else {
+			AiTools::load(
+				runtime,
+				&base_rt_ctx,
+				&agent,
+				literals,
+				&before_all_result,
+				&input,
+				&data,
+			)
+			.await?
+		};
+		process_ai(
+			runtime,
+			client,
+			run_base_options,
+			&run_model_resolved,
+			run_id,
+			task_id,
+			agent.clone(),
+			response_schema.as_deref(),
+			ai_tools.as_ref(),
+			chat_messages,
+		)
+		.await
+	}
 	.await;
 
 	// Capture error if any
@@ -0,0 +1,151 @@
+use crate::agent::AgentOptions;
+use crate::support::text::format_duration;
+use crate::{Error, Result};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+
+tokio::task_local! {
+	/// The deadline of the current task (or run stage), also checked by the Lua engine
+	static CURRENT_DEADLINE: RunDeadline;
+}
+
+/// The deadline of a task or a run, from the `task_timeout` and `run_timeout` agent options
+#[derive(Debug, Clone, Copy)]
+pub struct RunDeadline {
+	instant: Instant,
+	timeout: Duration,
+	kind: DeadlineKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum DeadlineKind {
+	Task,
+	Run,
+}
+
+/// Constructors
+impl RunDeadline {
+	/// The run deadline, from now
+	pub fn for_run(options: &AgentOptions) -> Option<Self> {
+		Self::from_secs(options.run_timeout()?, DeadlineKind::Run)
+	}
+
+	/// The task deadline, from now (the run deadline if sooner)
+	pub fn for_task(options: &AgentOptions, run_deadline: Option<RunDeadline>) -> Option<Self> {
+		let task_deadline = options
+			.task_timeout()
+			.and_then(|secs| Self::from_secs(secs, DeadlineKind::Task));
+		match (task_deadline, run_deadline) {
+			(Some(task), Some(run)) if run.instant < task.instant => Some(run),
+			(Some(task), _) => Some(task),
+			(None, run) => run,
+		}
+	}
+
+	/// The deadline of the current task (or run stage), if any
+	pub fn current() -> Option<Self> {
+		CURRENT_DEADLINE.try_with(|deadline| *deadline).ok()
+	}
+
+	fn from_secs(secs: f64, kind: DeadlineKind) -> Option<Self> {
+		let timeout = Duration::try_from_secs_f64(secs).ok()?;
+		Some(Self {
+			instant: Instant::now() + timeout,
+			timeout,
+			kind,
+		})
+	}
+}
+
+impl RunDeadline {
+	pub fn is_passed(&self) -> bool {
+		Instant::now() >= self.instant
+	}
+
+	pub fn to_error(self) -> Error {
+		let (what, option) = match self.kind {
+			DeadlineKind::Task => ("Task", "task_timeout"),
+			DeadlineKind::Run => ("Run", "run_timeout"),
+		};
+		Error::Timeout {
+			what,
+			timeout: format_duration(self.timeout),
+			option,
+		}
+	}
+}
+
+/// Execute the future with the deadline (when some).
+/// When the deadline is passed, the future is cancelled (dropped) and a timeout error is returned.
+pub async fn with_deadline<T, F>(deadline: Option<RunDeadline>, fut: F) -> Result<T>
+where
+	F: Future<Output = Result<T>>,
+{
+	let Some(deadline) = deadline else {
+		return fut.await;
+	};
+
+	match tokio::time::timeout_at(deadline.instant, CURRENT_DEADLINE.scope(deadline, fut)).await {
+		Ok(res) => res,
+		Err(_) => Err(deadline.to_error()),
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::runtime::Runtime;
+	use crate::script::LuaEngine;
+	use serde_json::json;
+
+	#[tokio::test]
+	async fn test_run_deadline_with_deadline_timeout() -> Result<()> {
+		// -- Setup & Fixtures
+		let options = AgentOptions::from_options_value(json!({ "task_timeout": 0.05, "run_timeout": 10 }))?;
+		let run_deadline = RunDeadline::for_run(&options);
+		let deadline = RunDeadline::for_task(&options, run_deadline);
+
+		// -- Exec
+		let res = with_deadline(deadline, async {
+			assert!(RunDeadline::current().is_some(), "should have current deadline");
+			tokio::time::sleep(Duration::from_secs(5)).await;
+			Ok(())
+		})
+		.await;
+
+		// -- Check
+		let err = res.err().ok_or("Should be a timeout error")?;
+		assert!(matches!(err, Error::Timeout { what: "Task", .. }), "err: {err}");
+		assert!(RunDeadline::current().is_none());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_run_deadline_lua_engine_reused_after_deadline() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let engine = LuaEngine::new(runtime, "test_run_deadline_lua_engine_reused_after_deadline")?;
+		let options = AgentOptions::from_options_value(json!({ "task_timeout": 0.05 }))?;
+		let deadline = RunDeadline::for_task(&options, None);
+		let fx_script = "local n = 0\nfor i = 1, 100000 do n = n + i end\nreturn n";
+
+		// -- Exec
+		with_deadline(deadline, async { engine.eval("return 1", None).await }).await?;
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		// same engine, outside of the deadline scope (e.g., tool handlers called later)
+		let res = engine.eval(fx_script, None).await?;
+
+		// -- Check
+		assert_eq!(serde_json::to_value(res)?, json!(5000050000_i64));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
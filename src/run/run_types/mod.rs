@@ -2,11 +2,13 @@
 
 mod attachments;
 mod run_redo_ctx;
+mod run_resume;
 mod run_sub_agent_params;
 mod run_top_agent_params;
 
 pub use attachments::*;
 pub use run_redo_ctx::*;
+pub use run_resume::*;
 pub use run_sub_agent_params::*;
 pub use run_top_agent_params::*;
 
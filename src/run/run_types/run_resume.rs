@@ -0,0 +1,142 @@
+use crate::agent::AgentOptions;
+use crate::model::{EndState, Id, ModelManager, Run, RunBmc, TaskBmc};
+use crate::{Error, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The recorded state of a past run to resume (`aip run --resume <run>`).
+///
+/// The tasks which ended `Ok` are not executed again, and their stored outputs are reused for the After All.
+/// The Before All is not executed again, its value and agent options overrides are reused.
+#[derive(Debug)]
+pub struct RunResume {
+	resume_of_id: Id,
+	before_all: Value,
+	/// The agent options of the resumed run, when overridden by its Before All
+	options: Option<AgentOptions>,
+	/// The inputs of all of the tasks (in the task idx order)
+	inputs: Vec<Value>,
+	/// The outputs of the tasks which ended `Ok` (by task idx)
+	ok_outputs: HashMap<usize, Value>,
+}
+
+/// Constructor
+impl RunResume {
+	pub fn load(mm: &ModelManager, run: &Run) -> Result<Self> {
+		let mut tasks = TaskBmc::list_for_run(mm, run.id)?;
+		if tasks.is_empty() {
+			return Err(Error::RunNothingToResume {
+				run_id: run.id.as_i64(),
+			});
+		}
+		tasks.sort_by_key(|task| task.idx);
+
+		let mut inputs = Vec::with_capacity(tasks.len());
+		let mut ok_outputs = HashMap::new();
+		for (idx, task) in tasks.iter().enumerate() {
+			inputs.push(TaskBmc::get_input_value(mm, task)?);
+			if task.end_state == Some(EndState::Ok) {
+				ok_outputs.insert(idx, TaskBmc::get_output_value(mm, task)?);
+			}
+		}
+
+		let before_all = RunBmc::get_before_all(mm, run)?;
+		let options = RunBmc::get_options(mm, run)?
+			.map(serde_json::from_value::<AgentOptions>)
+			.transpose()?;
+
+		Ok(Self {
+			resume_of_id: run.id,
+			before_all,
+			options,
+			inputs,
+			ok_outputs,
+		})
+	}
+}
+
+/// Getters
+impl RunResume {
+	pub fn resume_of_id(&self) -> Id {
+		self.resume_of_id
+	}
+
+	pub fn before_all(&self) -> &Value {
+		&self.before_all
+	}
+
+	pub fn options(&self) -> Option<&AgentOptions> {
+		self.options.as_ref()
+	}
+
+	pub fn inputs(&self) -> &[Value] {
+		&self.inputs
+	}
+
+	pub fn ok_output(&self, task_idx: usize) -> Option<&Value> {
+		self.ok_outputs.get(&task_idx)
+	}
+
+	pub fn ok_count(&self) -> usize {
+		self.ok_outputs.len()
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::model::{RunForCreate, TaskForCreate, TaskForUpdate, TypedContent};
+	use serde_json::json;
+
+	#[tokio::test]
+	async fn test_run_resume_load_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = ModelManager::new().await?;
+		let run_id = RunBmc::create(
+			&mm,
+			RunForCreate {
+				parent_id: None,
+				agent_name: Some("agent-01".to_string()),
+				agent_path: Some("agent-01.aip".to_string()),
+				has_task_stages: None,
+				has_prompt_parts: None,
+			},
+		)?;
+		RunBmc::set_before_all(&mm, run_id, &json!({"prefix": "BA"}))?;
+		let items = vec![
+			TaskForCreate::new_with_input(run_id, 0, None, &json!("one")),
+			TaskForCreate::new_with_input(run_id, 1, None, &json!({"name": "two"})),
+		];
+		let task_ids = TaskBmc::create_batch(&mm, items)?;
+		let ok_task_id = task_ids[1];
+		TaskBmc::update(
+			&mm,
+			ok_task_id,
+			TaskForUpdate {
+				end_state: Some(EndState::Ok),
+				..Default::default()
+			},
+		)?;
+		TaskBmc::update_output(&mm, ok_task_id, TypedContent::from_value(&json!({"res": 2})))?;
+		let run = RunBmc::get(&mm, run_id)?;
+
+		// -- Exec
+		let resume = RunResume::load(&mm, &run)?;
+
+		// -- Check
+		assert_eq!(resume.resume_of_id(), run_id);
+		assert_eq!(resume.before_all(), &json!({"prefix": "BA"}));
+		assert_eq!(resume.inputs(), &[json!("one"), json!({"name": "two"})]);
+		assert_eq!(resume.ok_count(), 1);
+		assert!(resume.ok_output(0).is_none());
+		assert_eq!(resume.ok_output(1), Some(&json!({"res": 2})));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
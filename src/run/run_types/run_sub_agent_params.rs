@@ -1,6 +1,7 @@
 use crate::Result;
 use crate::agent::AgentOptions;
 use crate::event::OneShotTx;
+use crate::run::RunBaseOptions;
 use crate::runtime::Runtime;
 use crate::types::{RunAgentOptions, RunAgentResponse};
 use simple_fs::SPath;
@@ -23,6 +24,12 @@ pub struct RunSubAgentParams {
 	/// The eventual agent option overlay
 	pub agent_options: Option<AgentOptions>,
 
+	/// The eventual run params (`CTX.PARAMS` of the called agent)
+	pub params: Option<serde_json::Map<String, serde_json::Value>>,
+
+	/// The base options of the parent run (for the cassette, mock dir, dry mode)
+	pub run_base_options: RunBaseOptions,
+
 	/// The response oneshot with the RunAgentResponse
 	pub response_shot: Option<OneShotTx<Result<RunAgentResponse>>>,
 }
@@ -40,6 +47,7 @@ impl RunSubAgentParams {
 			inputs,
 			options: agent_options,
 			agent_base_dir,
+			params,
 		} = run_options;
 
 		let agent_dir = agent_base_dir.or(parent_agent_dir);
@@ -51,6 +59,9 @@ impl RunSubAgentParams {
 			agent_name: agent_name.into(),
 			inputs,
 			agent_options,
+			params,
+			// NOTE: Called from the parent run scripts, so the parent run base options are the current ones
+			run_base_options: RunBaseOptions::current().unwrap_or_default(),
 			response_shot,
 		})
 	}
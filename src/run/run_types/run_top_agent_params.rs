@@ -1,5 +1,9 @@
 use crate::Result;
-use crate::exec::cli::RunArgs;
+use crate::exec::cli::{ReplayArgs, RunArgs};
+use crate::run::pricing::DryReqEstimates;
+use crate::run::{Cassette, CassetteMode, RunResume};
+use serde_json::{Map, Value};
+use simple_fs::SPath;
 use std::sync::Arc;
 
 // region:    --- RunCommandOptions
@@ -14,6 +18,10 @@ pub struct RunTopAgentParams {
 struct ParamsInner {
 	on_file_globs: Option<Vec<String>>,
 	on_inputs: Option<Vec<String>>,
+	/// The NDJSON, JSON array, or CSV file with one input per record
+	inputs_file: Option<String>,
+	/// The run id or uid to resume (only for the first run, not for redo)
+	resume_run_ref: Option<String>,
 	flow_redo_count: i32,
 
 	base_run_options: RunBaseOptions,
@@ -38,6 +46,14 @@ impl RunTopAgentParams {
 		self.inner.on_inputs.as_ref().map(|v| v.iter().map(|s| s.as_str()).collect())
 	}
 
+	pub fn inputs_file(&self) -> Option<&str> {
+		self.inner.inputs_file.as_deref()
+	}
+
+	pub fn resume_run_ref(&self) -> Option<&str> {
+		self.inner.resume_run_ref.as_deref()
+	}
+
 	pub fn base_run_options(&self) -> &RunBaseOptions {
 		&self.inner.base_run_options
 	}
@@ -54,6 +70,16 @@ impl RunTopAgentParams {
 		if let (Some(_), Some(_)) = (args.on_inputs.as_ref(), args.on_files.as_ref()) {
 			return Err("Cannot use both --on-inputs and --on-files".into());
 		}
+		if args.inputs_file.is_some() && (args.on_inputs.is_some() || args.on_files.is_some()) {
+			return Err("Cannot use --inputs-file with --on-inputs or --on-files".into());
+		}
+		if args.resume.is_some() && (args.on_inputs.is_some() || args.on_files.is_some() || args.inputs_file.is_some())
+		{
+			return Err(
+				"Cannot use --resume with --on-inputs, --on-files, or --inputs-file (the inputs of the resumed run are used)"
+					.into(),
+			);
+		}
 
 		// -- Refine the globs
 		let on_file_globs = if let Some(on_files) = args.on_files {
@@ -77,6 +103,17 @@ impl RunTopAgentParams {
 		// -- Parse dry_mode
 		let dry_mode = parse_dry_mode(args.dry_mode.as_deref());
 
+		// -- Parse the params
+		let params = parse_params(args.params.as_deref().unwrap_or_default())?;
+
+		// -- Build the cassette
+		let cassette = match (args.record, args.replay) {
+			(Some(dir), None) => Some(Cassette::new(dir, CassetteMode::Record)),
+			(None, Some(dir)) => Some(Cassette::new(dir, CassetteMode::Replay)),
+			(None, None) => None,
+			(Some(_), Some(_)) => return Err("Cannot use both --record and --replay".into()),
+		};
+
 		// -- Build the base Options
 		let base_run_options = RunBaseOptions {
 			watch: args.watch,
@@ -84,11 +121,18 @@ impl RunTopAgentParams {
 			dry_mode,
 			open: args.open,
 			flow_redo_count: 0,
+			resume: None,
+			params: Arc::new(params),
+			cassette: cassette.map(Arc::new),
+			mock_dir: None,
+			dry_req_estimates: Arc::default(),
 		};
 
 		Ok(ParamsInner {
 			on_file_globs,
 			on_inputs: args.on_inputs,
+			inputs_file: args.inputs_file,
+			resume_run_ref: args.resume,
 			flow_redo_count: 0,
 			base_run_options,
 		}
@@ -99,9 +143,13 @@ impl RunTopAgentParams {
 		ParamsInner {
 			on_file_globs: self.inner.on_file_globs.clone(),
 			on_inputs: self.inner.on_inputs.clone(),
+			inputs_file: self.inner.inputs_file.clone(),
+			// NOTE: A redo runs all of the inputs again
+			resume_run_ref: None,
 			flow_redo_count,
 			base_run_options: RunBaseOptions {
 				flow_redo_count,
+				resume: None,
 				..self.inner.base_run_options.clone()
 			},
 		}
@@ -131,9 +179,69 @@ pub struct RunBaseOptions {
 	dry_mode: DryMode,
 	open: bool,
 	flow_redo_count: i32,
+	resume: Option<Arc<RunResume>>,
+	/// The run params (`--param key=value`, or `params` of `aip.agent.run`)
+	params: Arc<Map<String, Value>>,
+	/// The AI calls record/replay cassette (`--record` / `--replay`)
+	cassette: Option<Arc<Cassette>>,
+	/// The dir of the `mock::` model responses, instead of the agent one (e.g., for `aip test`)
+	mock_dir: Option<SPath>,
+	/// The `--dry req` prompt tokens & cost estimates, per run
+	dry_req_estimates: Arc<DryReqEstimates>,
+}
+
+tokio::task_local! {
+	/// The base options of the current run, for the sub-agents (`aip.agent.run`) of its scripts
+	static CURRENT_RUN_BASE_OPTIONS: RunBaseOptions;
 }
 
 impl RunBaseOptions {
+	/// The base options of the current run (or task), if any
+	pub fn current() -> Option<Self> {
+		CURRENT_RUN_BASE_OPTIONS.try_with(|options| options.clone()).ok()
+	}
+
+	/// Execute the future with these options as the current run base options
+	pub async fn scope<F: Future>(&self, fut: F) -> F::Output {
+		CURRENT_RUN_BASE_OPTIONS.scope(self.clone(), fut).await
+	}
+
+	/// The base options of a sub-agent run (`aip.agent.run`), with its own params.
+	///
+	/// NOTE: The cassette, mock dir, and dry mode of the parent run are kept,
+	///       but not the watch, open, redo, and resume which are for the top run only.
+	pub fn for_sub_agent(&self, params: Map<String, Value>) -> Self {
+		Self {
+			watch: false,
+			open: false,
+			flow_redo_count: 0,
+			resume: None,
+			params: Arc::new(params),
+			..self.clone()
+		}
+	}
+
+	pub fn with_params(self, params: Map<String, Value>) -> Self {
+		Self {
+			params: Arc::new(params),
+			..self
+		}
+	}
+
+	pub fn with_mock_dir(self, mock_dir: SPath) -> Self {
+		Self {
+			mock_dir: Some(mock_dir),
+			..self
+		}
+	}
+
+	pub fn with_resume(&self, resume: RunResume) -> Self {
+		Self {
+			resume: Some(Arc::new(resume)),
+			..self.clone()
+		}
+	}
+
 	pub fn watch(&self) -> bool {
 		self.watch
 	}
@@ -153,6 +261,56 @@ impl RunBaseOptions {
 	pub fn flow_redo_count(&self) -> i32 {
 		self.flow_redo_count
 	}
+
+	pub fn resume(&self) -> Option<&RunResume> {
+		self.resume.as_deref()
+	}
+
+	pub fn params(&self) -> &Arc<Map<String, Value>> {
+		&self.params
+	}
+
+	pub fn cassette(&self) -> Option<&Cassette> {
+		self.cassette.as_deref()
+	}
+
+	pub fn mock_dir(&self) -> Option<&SPath> {
+		self.mock_dir.as_ref()
+	}
+
+	pub fn dry_req_estimates(&self) -> &DryReqEstimates {
+		&self.dry_req_estimates
+	}
+}
+
+/// Implementations for various test.
+#[cfg(test)]
+impl RunBaseOptions {
+	pub fn with_cassette(self, cassette: Cassette) -> Self {
+		Self {
+			cassette: Some(Arc::new(cassette)),
+			..self
+		}
+	}
+}
+
+/// For `aip replay`, which does not watch nor redo
+impl From<&ReplayArgs> for RunBaseOptions {
+	fn from(args: &ReplayArgs) -> Self {
+		Self {
+			watch: false,
+			verbose: args.verbose,
+			dry_mode: parse_dry_mode(args.dry_mode.as_deref()),
+			open: false,
+			flow_redo_count: 0,
+			resume: None,
+			// NOTE: The params of the replayed run are set by the replay (see `exec_replay`)
+			params: Arc::default(),
+			cassette: None,
+			mock_dir: None,
+			dry_req_estimates: Arc::default(),
+		}
+	}
 }
 
 // endregion: --- Common
@@ -167,4 +325,58 @@ fn parse_dry_mode(dry_mode: Option<&str>) -> DryMode {
 	}
 }
 
+/// Parse the `--param key=value` into a params map (the values are strings).
+/// Note: Only the first `=` is the separator, so `--param filter=a=b` gives `filter: "a=b"`
+fn parse_params(params: &[String]) -> Result<Map<String, Value>> {
+	let mut map = Map::new();
+	for param in params {
+		let Some((key, value)) = param.split_once('=') else {
+			return Err(format!("Invalid --param '{param}', must be in the format 'key=value'").into());
+		};
+		let key = key.trim();
+		if key.is_empty() {
+			return Err(format!("Invalid --param '{param}', the key cannot be empty").into());
+		}
+		map.insert(key.to_string(), Value::String(value.to_string()));
+	}
+	Ok(map)
+}
+
 // endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_run_top_agent_params_parse_params_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let params = vec!["lang=fr".to_string(), "filter=a=b".to_string(), "empty=".to_string()];
+
+		// -- Exec
+		let map = parse_params(&params)?;
+
+		// -- Check
+		assert_eq!(map.len(), 3);
+		assert_eq!(map.get("lang").and_then(|v| v.as_str()), Some("fr"));
+		assert_eq!(map.get("filter").and_then(|v| v.as_str()), Some("a=b"));
+		assert_eq!(map.get("empty").and_then(|v| v.as_str()), Some(""));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_run_top_agent_params_parse_params_invalid() -> Result<()> {
+		// -- Exec & Check
+		assert!(parse_params(&["no-equal".to_string()]).is_err());
+		assert!(parse_params(&["=value".to_string()]).is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -99,14 +99,15 @@ impl<'a> RtLog<'a> {
 		self.rec_log(run_id, Some(task_id), None, Some(Stage::Data), msg, level).await
 	}
 
+	/// NOTE: No task for the AI calls outside of a task (e.g., `aip.ai.chat` in the `# Before All`)
 	pub async fn rec_log_ai(
 		&self,
 		run_id: Id,
-		task_id: Id,
+		task_id: Option<Id>,
 		msg: impl Into<String>,
 		level: Option<LogKind>,
 	) -> Result<()> {
-		self.rec_log(run_id, Some(task_id), None, Some(Stage::Ai), msg, level).await
+		self.rec_log(run_id, task_id, None, Some(Stage::Ai), msg, level).await
 	}
 
 	pub async fn rec_log_output(
@@ -1,5 +1,5 @@
 use crate::Result;
-use crate::agent::Agent;
+use crate::agent::{Agent, AgentOptions};
 use crate::hub::get_hub;
 use crate::model::base::DbBmc;
 use crate::model::{
@@ -10,7 +10,7 @@ use crate::run::ModelPricing;
 use crate::runtime::Runtime;
 use derive_more::From;
 use genai::ModelIden;
-use serde_json::Value;
+use serde_json::{Map, Value};
 use uuid::Uuid;
 
 #[derive(Debug, From)]
@@ -137,6 +137,47 @@ impl<'a> RtModel<'a> {
 		Ok(())
 	}
 
+	pub async fn update_run_before_all(&self, run_id: Id, before_all: &Value) -> Result<()> {
+		RunBmc::set_before_all(self.mm(), run_id, before_all)?;
+		Ok(())
+	}
+
+	pub async fn update_run_after_all(&self, run_id: Id, after_all: &Value) -> Result<()> {
+		RunBmc::set_after_all(self.mm(), run_id, after_all)?;
+		Ok(())
+	}
+
+	pub async fn update_run_params(&self, run_id: Id, params: &Map<String, Value>) -> Result<()> {
+		RunBmc::set_params(self.mm(), run_id, params)?;
+		Ok(())
+	}
+
+	pub async fn update_run_options(&self, run_id: Id, options: &AgentOptions) -> Result<()> {
+		let options = serde_json::to_value(options)?;
+		RunBmc::set_options(self.mm(), run_id, &options)?;
+		Ok(())
+	}
+
+	pub async fn update_run_replay_of(&self, run_id: Id, replay_of_id: Id) -> Result<()> {
+		let run_u = RunForUpdate {
+			replay_of_id: Some(replay_of_id),
+			..Default::default()
+		};
+		RunBmc::update(self.mm(), run_id, run_u)?;
+
+		Ok(())
+	}
+
+	pub async fn update_run_resume_of(&self, run_id: Id, resume_of_id: Id) -> Result<()> {
+		let run_u = RunForUpdate {
+			resume_of_id: Some(resume_of_id),
+			..Default::default()
+		};
+		RunBmc::update(self.mm(), run_id, run_u)?;
+
+		Ok(())
+	}
+
 	pub fn set_run_end_error(&self, run_id: Id, stage: Option<Stage>, err: &crate::Error) -> Result<()> {
 		RunBmc::set_end_error(self.mm(), run_id, stage, err)?;
 		Ok(())
@@ -254,6 +295,42 @@ impl<'a> RtModel<'a> {
 		Ok(())
 	}
 
+	/// Mark the task AI response as served from the responses cache (with a zero cost)
+	pub async fn update_task_ai_cached(&self, run_id: Id, task_id: Id) -> Result<()> {
+		let task_u = TaskForUpdate {
+			ai_cached: Some(true),
+			..Default::default()
+		};
+		TaskBmc::update(self.mm(), task_id, task_u)?;
+
+		self.add_task_cost(run_id, task_id, 0.0, None, None).await
+	}
+
+	/// Add a cost to the task cost (e.g., the AI stage and the `aip.ai.chat` calls of the task)
+	pub async fn add_task_cost(
+		&self,
+		run_id: Id,
+		task_id: Id,
+		cost: f64,
+		cost_cache_write: Option<f64>,
+		cost_cache_saving: Option<f64>,
+	) -> Result<()> {
+		let task = TaskBmc::get(self.mm(), task_id)?;
+		let add = |prev: Option<f64>, value: Option<f64>| match (prev, value) {
+			(Some(prev), Some(value)) => Some(prev + value),
+			(prev, value) => prev.or(value),
+		};
+
+		self.update_task_cost(
+			run_id,
+			task_id,
+			task.cost.unwrap_or(0.0) + cost,
+			add(task.cost_cache_write, cost_cache_write),
+			add(task.cost_cache_saving, cost_cache_saving),
+		)
+		.await
+	}
+
 	pub async fn update_task_cost(
 		&self,
 		run_id: Id,
@@ -263,6 +340,7 @@ impl<'a> RtModel<'a> {
 		cost_cache_saving: Option<f64>,
 	) -> Result<()> {
 		// -- Update Task
+		let prev_cost = TaskBmc::get(self.mm(), task_id)?.cost.unwrap_or(0.0);
 		let task_u = TaskForUpdate {
 			cost: Some(cost),
 			cost_cache_write,
@@ -271,18 +349,47 @@ impl<'a> RtModel<'a> {
 		};
 		TaskBmc::update(self.mm(), task_id, task_u)?;
 
-		// -- Update the run total cost
+		self.update_run_total_cost(run_id, cost - prev_cost)
+	}
+
+	/// Add a cost to the run, for the AI calls outside of the tasks (e.g., `aip.ai.chat` in the Before All)
+	pub async fn add_run_cost(&self, run_id: Id, cost: f64) -> Result<()> {
+		let ai_cost = RunBmc::get(self.mm(), run_id)?.ai_cost.unwrap_or(0.0) + cost;
+		let run_u = RunForUpdate {
+			ai_cost: Some(ai_cost),
+			..Default::default()
+		};
+		RunBmc::update(self.mm(), run_id, run_u)?;
+
+		self.update_run_total_cost(run_id, cost)
+	}
+
+	/// Update the run total cost (the tasks costs and the run AI cost), and the cost budget
+	fn update_run_total_cost(&self, run_id: Id, cost_delta: f64) -> Result<()> {
 		// NOTE: Here we recompute the total cost rather than doing a simple add to avoid
 		//       any race condition
+		let run = RunBmc::get(self.mm(), run_id)?;
 		let tasks = TaskBmc::list_for_run(self.mm(), run_id)?;
-		let total_cost: f64 = tasks.iter().filter_map(|t| t.cost).sum();
+		let total_cost: f64 = tasks.iter().filter_map(|t| t.cost).sum::<f64>() + run.ai_cost.unwrap_or(0.0);
 		let run_u = RunForUpdate {
 			total_cost: Some(total_cost),
 			..Default::default()
 		};
 		RunBmc::update(self.mm(), run_id, run_u)?;
 
-		if let Some(loop_id) = RunBmc::get(self.mm(), run_id)?.loop_id {
+		// -- Update the cost budget (run max cost & workspace budget)
+		// NOTE: The parent runs (of a sub-agent run) also get the cost, for their max cost
+		let mut parent_ids: Vec<Id> = Vec::new();
+		let mut parent_id = run.parent_id;
+		while let Some(id) = parent_id {
+			parent_ids.push(id);
+			parent_id = RunBmc::get(self.mm(), id)?.parent_id;
+		}
+		self.runtime
+			.cost_budget()
+			.on_cost_update(run_id, &parent_ids, total_cost, cost_delta)?;
+
+		if let Some(loop_id) = run.loop_id {
 			LoopBmc::recompute_cost(self.mm(), loop_id)?;
 		}
 
@@ -305,6 +412,16 @@ impl<'a> RtModel<'a> {
 		Ok(())
 	}
 
+	/// Set the task end error at the stage in progress (e.g., for a timeout, which cancels the stage)
+	///
+	/// NOTE: No stage in progress when the stage returned the error (already recorded at its stage)
+	pub fn set_task_end_error_at_current_stage(&self, task_id: Id, err: &crate::Error) -> Result<()> {
+		if let Some(stage) = TaskBmc::get(self.mm(), task_id)?.current_stage() {
+			TaskBmc::set_end_error_no_end(self.mm(), task_id, Some(stage), err)?;
+		}
+		Ok(())
+	}
+
 	/// Note: the rec log already happened (in the current design)
 	/// This does not set the end time
 	pub fn set_task_end_state_to_skip(&self, _run_id: Id, task_id: Id) -> Result<()> {
@@ -1,5 +1,5 @@
 use crate::Result;
-use crate::dir_context::DirContext;
+use crate::dir_context::{BudgetConfig, DirContext, RateLimitsConfig};
 use crate::event::{CancelRx, CancelTrx, CancelTx};
 use crate::exec::ExecutorTx;
 use crate::hub::get_hub;
@@ -7,7 +7,7 @@ use crate::model::{ModelManager, RuntimeCtx};
 use crate::run::{Literals, new_genai_client};
 use crate::runtime::queue::{RunEvent, RunQueue};
 use crate::runtime::runtime_inner::RuntimeInner;
-use crate::runtime::support::FileWriteManager;
+use crate::runtime::support::{CostBudget, FileWriteManager, RateLimiter};
 use crate::runtime::{RtLog, RtModel, RtStep};
 use crate::script::LuaEngine;
 use genai::Client;
@@ -40,6 +40,17 @@ impl Runtime {
 		let mut run_queue = RunQueue::new();
 		let run_tx = run_queue.start()?;
 
+		// -- Create the Cost Budget (from the `[budget]` config)
+		let budget_config = BudgetConfig::load(dir_context.aipack_paths())?;
+		let ledger_file = match dir_context.aipack_paths().aipack_wks_dir() {
+			Some(wks_dir) => Some(wks_dir.get_cost_ledger_path()?),
+			None => None,
+		};
+		let cost_budget = CostBudget::new(budget_config, ledger_file);
+
+		// -- Create the Rate Limiter (from the `[rate_limits]` config)
+		let rate_limiter = RateLimiter::new(RateLimitsConfig::load(dir_context.aipack_paths())?);
+
 		let inner = RuntimeInner {
 			dir_context,
 			genai_client,
@@ -48,6 +59,8 @@ impl Runtime {
 			session: Session::new(),
 			mm,
 			file_write_manager: FileWriteManager::new().into(),
+			cost_budget: cost_budget.into(),
+			rate_limiter: rate_limiter.into(),
 			cancel_trx,
 		};
 
@@ -142,6 +155,14 @@ impl Runtime {
 	pub fn file_write_manager(&self) -> &FileWriteManager {
 		self.inner.file_write_manager()
 	}
+
+	pub fn cost_budget(&self) -> &CostBudget {
+		self.inner.cost_budget()
+	}
+
+	pub fn rate_limiter(&self) -> &RateLimiter {
+		self.inner.rate_limiter()
+	}
 }
 
 // region:    --- Session
@@ -225,7 +246,7 @@ mod tests_support {
 		}
 
 		async fn new_test_runtime(dir_context: DirContext) -> Result<Self> {
-			let executor = Executor::new(OnceModelManager);
+			let executor = Executor::new(OnceModelManager::default());
 			let exec_sender = executor.sender();
 			tokio::spawn(async move {
 				if let Err(err) = executor.start().await {
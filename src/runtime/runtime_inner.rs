@@ -4,7 +4,7 @@ use crate::exec::ExecutorTx;
 use crate::model::ModelManager;
 use crate::runtime::Session;
 use crate::runtime::queue::RunTx;
-use crate::runtime::support::FileWriteManager;
+use crate::runtime::support::{CostBudget, FileWriteManager, RateLimiter};
 use genai::Client;
 use std::sync::Arc;
 
@@ -17,6 +17,8 @@ pub struct RuntimeInner {
 	pub(super) run_tx: RunTx,
 	pub(super) mm: ModelManager,
 	pub(super) file_write_manager: Arc<FileWriteManager>,
+	pub(super) cost_budget: Arc<CostBudget>,
+	pub(super) rate_limiter: Arc<RateLimiter>,
 
 	pub(super) cancel_trx: Option<CancelTrx>,
 }
@@ -46,4 +48,12 @@ impl RuntimeInner {
 	pub fn file_write_manager(&self) -> &FileWriteManager {
 		&self.file_write_manager
 	}
+
+	pub fn cost_budget(&self) -> &CostBudget {
+		&self.cost_budget
+	}
+
+	pub fn rate_limiter(&self) -> &RateLimiter {
+		&self.rate_limiter
+	}
 }
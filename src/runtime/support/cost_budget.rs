@@ -0,0 +1,334 @@
+use crate::Result;
+use crate::dir_context::BudgetConfig;
+use crate::model::Id;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use simple_fs::{SPath, ensure_file_dir};
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::sync::Mutex;
+use time::OffsetDateTime;
+use time_tz::OffsetDateTimeExt as _;
+
+/// Tracks the AI costs against the per-run `max_cost_usd` agent option
+/// and the workspace `[budget]` config (daily & monthly USD).
+///
+/// The workspace costs are persisted, per day, in the `.aipack/.session/cost-ledger.json` file,
+/// so that they are shared across the `aip` sessions of the workspace
+/// (read and written under a file lock, and reloaded before each check).
+///
+/// The cost of a sub-agent run (`aip.agent.run`) also counts against the `max_cost_usd` of its parent runs.
+#[derive(Debug)]
+pub struct CostBudget {
+	config: BudgetConfig,
+	ledger_file: Option<SPath>,
+	ledger: Mutex<CostLedger>,
+	run_max_costs: DashMap<Id, f64>,
+	run_costs: DashMap<Id, f64>,
+	/// The costs of the sub-agent runs, per parent run
+	sub_run_costs: DashMap<Id, f64>,
+}
+
+/// Constructors
+impl CostBudget {
+	/// NOTE: The `ledger_file` is only read/written when the config has a budget.
+	pub fn new(config: BudgetConfig, ledger_file: Option<SPath>) -> Self {
+		let ledger = match ledger_file.as_ref() {
+			Some(file) if !config.is_empty() => CostLedger::load(file),
+			_ => CostLedger::default(),
+		};
+
+		Self {
+			config,
+			ledger_file,
+			ledger: Mutex::new(ledger),
+			run_max_costs: DashMap::new(),
+			run_costs: DashMap::new(),
+			sub_run_costs: DashMap::new(),
+		}
+	}
+}
+
+impl CostBudget {
+	/// Set the `max_cost_usd` of a run (from the agent options)
+	pub fn set_run_max_cost(&self, run_id: Id, max_cost_usd: Option<f64>) {
+		match max_cost_usd {
+			Some(max_cost_usd) => self.run_max_costs.insert(run_id, max_cost_usd),
+			None => self.run_max_costs.remove(&run_id).map(|(_, v)| v),
+		};
+	}
+
+	/// Called when a run cost is updated.
+	/// - `parent_ids` the parent runs of this run, when a sub-agent run (they also get the `cost_delta`)
+	/// - `run_total_cost` the new total cost of the run
+	/// - `cost_delta` the cost added by this update (to be added to the workspace ledger)
+	pub fn on_cost_update(&self, run_id: Id, parent_ids: &[Id], run_total_cost: f64, cost_delta: f64) -> Result<()> {
+		self.run_costs.insert(run_id, run_total_cost);
+		for parent_id in parent_ids {
+			*self.sub_run_costs.entry(*parent_id).or_insert(0.0) += cost_delta;
+		}
+
+		if self.config.is_empty() || cost_delta == 0.0 {
+			return Ok(());
+		}
+
+		let mut ledger = self.ledger.lock().unwrap_or_else(|e| e.into_inner());
+		let today = today_local();
+		match self.ledger_file.as_ref() {
+			// NOTE: Reload under the lock to include the costs of the other sessions of this workspace
+			Some(file) => {
+				let _lock = CostLedger::lock(file, true)?;
+				*ledger = CostLedger::load(file);
+				ledger.add(&today, cost_delta);
+				ledger.save(file)?;
+			}
+			None => ledger.add(&today, cost_delta),
+		}
+
+		Ok(())
+	}
+
+	/// Returns the reason if the run max cost or the workspace budget is exceeded (None if still within budget)
+	pub fn exceeded_reason(&self, run_id: Id) -> Option<String> {
+		let run_cost = self.run_costs.get(&run_id).map(|v| *v).unwrap_or(0.0)
+			+ self.sub_run_costs.get(&run_id).map(|v| *v).unwrap_or(0.0);
+		if let Some(max_cost) = self.run_max_costs.get(&run_id).map(|v| *v)
+			&& run_cost >= max_cost
+		{
+			return Some(format!(
+				"Run cost ${run_cost:.4} reached the agent max_cost_usd of ${max_cost:.4}"
+			));
+		}
+
+		if self.config.is_empty() {
+			return None;
+		}
+
+		let mut ledger = self.ledger.lock().unwrap_or_else(|e| e.into_inner());
+		// Reload to include the costs of the other sessions of this workspace
+		// NOTE: Best effort, a ledger which cannot be locked is still read.
+		if let Some(file) = self.ledger_file.as_ref() {
+			let _lock = CostLedger::lock(file, false).ok();
+			*ledger = CostLedger::load(file);
+		}
+		let today = today_local();
+
+		if let Some(daily_usd) = self.config.daily_usd() {
+			let day_cost = ledger.day_total(&today);
+			if day_cost >= daily_usd {
+				return Some(format!(
+					"Workspace daily budget of ${daily_usd:.4} reached (${day_cost:.4} spent today)\n(see [budget] daily_usd in .aipack/config.toml)"
+				));
+			}
+		}
+
+		if let Some(monthly_usd) = self.config.monthly_usd() {
+			let month_cost = ledger.month_total(&today);
+			if month_cost >= monthly_usd {
+				return Some(format!(
+					"Workspace monthly budget of ${monthly_usd:.4} reached (${month_cost:.4} spent this month)\n(see [budget] monthly_usd in .aipack/config.toml)"
+				));
+			}
+		}
+
+		None
+	}
+}
+
+// region:    --- CostLedger
+
+/// The workspace costs (in USD) per local day (`YYYY-MM-DD`)
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CostLedger {
+	days: BTreeMap<String, f64>,
+}
+
+impl CostLedger {
+	/// NOTE: A missing or invalid ledger file starts a new ledger.
+	fn load(file: &SPath) -> Self {
+		if !file.exists() {
+			return Self::default();
+		}
+		simple_fs::load_json(file).unwrap_or_default()
+	}
+
+	/// Lock the ledger (exclusive for a read & write, shared for a read), until the returned file is dropped.
+	///
+	/// NOTE: The lock is on a sibling `.lock` file, since the ledger file is replaced on save.
+	fn lock(file: &SPath, exclusive: bool) -> Result<File> {
+		ensure_file_dir(file)?;
+		let lock_file = OpenOptions::new()
+			.create(true)
+			.truncate(false)
+			.write(true)
+			.open(format!("{file}.lock"))?;
+		if exclusive {
+			lock_file.lock()?;
+		} else {
+			lock_file.lock_shared()?;
+		}
+		Ok(lock_file)
+	}
+
+	fn save(&self, file: &SPath) -> Result<()> {
+		ensure_file_dir(file)?;
+		simple_fs::save_json_pretty(file, self)?;
+		Ok(())
+	}
+
+	/// Add the cost to the day, and only keep the days of that month (enough for the budgets)
+	fn add(&mut self, day: &str, cost: f64) {
+		let month = month_of(day);
+		self.days.retain(|d, _| month_of(d) == month);
+		*self.days.entry(day.to_string()).or_insert(0.0) += cost;
+	}
+
+	fn day_total(&self, day: &str) -> f64 {
+		self.days.get(day).copied().unwrap_or(0.0)
+	}
+
+	fn month_total(&self, day: &str) -> f64 {
+		let month = month_of(day);
+		self.days
+			.iter()
+			.filter(|(d, _)| month_of(d) == month)
+			.map(|(_, cost)| cost)
+			.sum()
+	}
+}
+
+/// `YYYY-MM` of a `YYYY-MM-DD` day
+fn month_of(day: &str) -> &str {
+	day.get(..7).unwrap_or(day)
+}
+
+/// The local day (`YYYY-MM-DD`), or UTC if the system timezone is not available
+fn today_local() -> String {
+	let now = OffsetDateTime::now_utc();
+	let now = if let Ok(local) = time_tz::system::get_timezone() {
+		now.to_timezone(local)
+	} else {
+		now
+	};
+	let date = now.date();
+	format!("{:04}-{:02}-{:02}", date.year(), date.month() as u8, date.day())
+}
+
+// endregion: --- CostLedger
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::gen_test_dir_path;
+
+	#[test]
+	fn test_cost_budget_run_max_cost() -> Result<()> {
+		// -- Setup & Fixtures
+		let budget = CostBudget::new(BudgetConfig::default(), None);
+		let run_id: Id = 1.into();
+		budget.set_run_max_cost(run_id, Some(0.5));
+
+		// -- Exec & Check
+		budget.on_cost_update(run_id, &[], 0.3, 0.3)?;
+		assert!(budget.exceeded_reason(run_id).is_none());
+
+		budget.on_cost_update(run_id, &[], 0.6, 0.3)?;
+		let reason = budget.exceeded_reason(run_id).ok_or("Should be exceeded")?;
+		assert!(reason.contains("max_cost_usd"), "reason: {reason}");
+
+		// other runs are not impacted
+		assert!(budget.exceeded_reason(2.into()).is_none());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_cost_budget_run_max_cost_with_sub_runs() -> Result<()> {
+		// -- Setup & Fixtures
+		let budget = CostBudget::new(BudgetConfig::default(), None);
+		let (run_id, sub_run_id, sub_sub_run_id): (Id, Id, Id) = (1.into(), 2.into(), 3.into());
+		budget.set_run_max_cost(run_id, Some(0.5));
+
+		// -- Exec
+		budget.on_cost_update(run_id, &[], 0.2, 0.2)?;
+		budget.on_cost_update(sub_run_id, &[run_id], 0.2, 0.2)?;
+		assert!(budget.exceeded_reason(run_id).is_none());
+		budget.on_cost_update(sub_sub_run_id, &[sub_run_id, run_id], 0.2, 0.2)?;
+
+		// -- Check
+		let reason = budget.exceeded_reason(run_id).ok_or("Should be exceeded")?;
+		assert!(reason.contains("max_cost_usd"), "reason: {reason}");
+		assert!(budget.exceeded_reason(sub_run_id).is_none());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_cost_budget_daily_ledger() -> Result<()> {
+		// -- Setup & Fixtures
+		let ledger_file = gen_test_dir_path().join(".session/cost-ledger.json");
+		let config = BudgetConfig::new(Some(1.0), Some(10.0));
+		let budget = CostBudget::new(config.clone(), Some(ledger_file.clone()));
+		let run_id: Id = 1.into();
+
+		// -- Exec
+		budget.on_cost_update(run_id, &[], 0.7, 0.7)?;
+		budget.on_cost_update(run_id, &[], 1.2, 0.5)?;
+
+		// -- Check
+		let reason = budget.exceeded_reason(run_id).ok_or("Should be exceeded")?;
+		assert!(reason.contains("daily budget"), "reason: {reason}");
+
+		// the ledger is shared by a new session (new run ids)
+		let budget = CostBudget::new(config, Some(ledger_file));
+		let reason = budget.exceeded_reason(3.into()).ok_or("Should be exceeded")?;
+		assert!(reason.contains("daily budget"), "reason: {reason}");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_cost_budget_ledger_other_session() -> Result<()> {
+		// -- Setup & Fixtures
+		let ledger_file = gen_test_dir_path().join(".session/cost-ledger.json");
+		let config = BudgetConfig::new(Some(1.0), Some(10.0));
+		let budget_a = CostBudget::new(config.clone(), Some(ledger_file.clone()));
+		let budget_b = CostBudget::new(config, Some(ledger_file));
+
+		// -- Exec
+		budget_a.on_cost_update(1.into(), &[], 0.6, 0.6)?;
+		assert!(budget_b.exceeded_reason(2.into()).is_none());
+		budget_b.on_cost_update(2.into(), &[], 0.6, 0.6)?;
+
+		// -- Check
+		// the ledger is reloaded before each check (costs of both sessions)
+		let reason = budget_a.exceeded_reason(1.into()).ok_or("Should be exceeded")?;
+		assert!(reason.contains("daily budget"), "reason: {reason}");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_cost_budget_ledger_month() -> Result<()> {
+		// -- Setup & Fixtures
+		let mut ledger = CostLedger::default();
+
+		// -- Exec
+		ledger.add("2026-01-30", 2.0);
+		ledger.add("2026-02-01", 1.0);
+		ledger.add("2026-02-02", 0.5);
+
+		// -- Check
+		assert_eq!(ledger.days.len(), 2, "previous month should be pruned");
+		assert_eq!(ledger.day_total("2026-02-02"), 0.5);
+		assert_eq!(ledger.month_total("2026-02-02"), 1.5);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,3 +1,7 @@
+mod cost_budget;
 mod file_write_manager;
+mod rate_limiter;
 
+pub use cost_budget::*;
 pub use file_write_manager::*;
+pub use rate_limiter::*;
@@ -0,0 +1,195 @@
+use crate::dir_context::{RateLimitConfig, RateLimitsConfig};
+use dashmap::DashMap;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// The provider rate limiter, per adapter kind, from the `[rate_limits]` config
+/// (requests and tokens per minute, over a sliding window of one minute).
+///
+/// It is shared by all the AI calls of the runtime (all tasks, runs, and sub-agents).
+#[derive(Debug)]
+pub struct RateLimiter {
+	config: RateLimitsConfig,
+	windows: DashMap<&'static str, Mutex<RateWindow>>,
+}
+
+/// Constructors
+impl RateLimiter {
+	pub fn new(config: RateLimitsConfig) -> Self {
+		Self {
+			config,
+			windows: DashMap::new(),
+		}
+	}
+}
+
+impl RateLimiter {
+	/// Reserve a request with its estimated tokens for the adapter kind (by its lower name).
+	/// Returns the time to wait before trying again if over the rate limits.
+	///
+	/// NOTE: A request is always allowed when the window is empty, even if its tokens are over the tokens per minute.
+	pub fn try_acquire(&self, adapter: &'static str, tokens: u64) -> Result<(), Duration> {
+		self.try_acquire_at(adapter, tokens, Instant::now())
+	}
+
+	/// Add the tokens actually used over the estimated tokens of the request (if more).
+	pub fn record_usage(&self, adapter: &'static str, estimated_tokens: u64, used_tokens: u64) {
+		if used_tokens <= estimated_tokens || self.config.get(adapter).is_none() {
+			return;
+		}
+		let window = self.windows.entry(adapter).or_default();
+		let mut window = window.lock().unwrap_or_else(|e| e.into_inner());
+		window.tokens.push_back((Instant::now(), used_tokens - estimated_tokens));
+	}
+
+	fn try_acquire_at(&self, adapter: &'static str, tokens: u64, now: Instant) -> Result<(), Duration> {
+		let Some(limit) = self.config.get(adapter) else {
+			return Ok(());
+		};
+
+		let window = self.windows.entry(adapter).or_default();
+		let mut window = window.lock().unwrap_or_else(|e| e.into_inner());
+		window.prune(now);
+
+		if let Some(wait) = window.wait_for(&limit, tokens, now) {
+			return Err(wait);
+		}
+
+		window.requests.push_back(now);
+		window.tokens.push_back((now, tokens));
+		Ok(())
+	}
+}
+
+// region:    --- RateWindow
+
+/// The requests and tokens of the last minute (oldest first)
+#[derive(Debug, Default)]
+struct RateWindow {
+	requests: VecDeque<Instant>,
+	tokens: VecDeque<(Instant, u64)>,
+}
+
+impl RateWindow {
+	fn prune(&mut self, now: Instant) {
+		while self.requests.front().is_some_and(|at| now.duration_since(*at) >= WINDOW) {
+			self.requests.pop_front();
+		}
+		while self.tokens.front().is_some_and(|(at, _)| now.duration_since(*at) >= WINDOW) {
+			self.tokens.pop_front();
+		}
+	}
+
+	/// Returns the time to wait for a new request with those tokens (None if within the limits)
+	fn wait_for(&self, limit: &RateLimitConfig, tokens: u64, now: Instant) -> Option<Duration> {
+		let expires_in = |at: Instant| (at + WINDOW).saturating_duration_since(now);
+
+		if let Some(requests_per_min) = limit.requests_per_min()
+			&& self.requests.len() >= requests_per_min as usize
+		{
+			// wait for the oldest requests to leave the window
+			// NOTE: No request to wait for with `requests_per_min = 0`, so falls through to the tokens check
+			let index = self.requests.len() - requests_per_min as usize;
+			if let Some(at) = self.requests.get(index) {
+				return Some(expires_in(*at));
+			}
+		}
+
+		if let Some(tokens_per_min) = limit.tokens_per_min() {
+			let mut window_tokens: u64 = self.tokens.iter().map(|(_, t)| t).sum();
+			if window_tokens > 0 && window_tokens + tokens > tokens_per_min {
+				// wait for enough tokens to leave the window (or for the window to be empty)
+				for (at, t) in self.tokens.iter() {
+					window_tokens -= t;
+					if window_tokens == 0 || window_tokens + tokens <= tokens_per_min {
+						return Some(expires_in(*at));
+					}
+				}
+			}
+		}
+
+		None
+	}
+}
+
+// endregion: --- RateWindow
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_rate_limiter_requests_per_min() -> Result<()> {
+		// -- Setup & Fixtures
+		let config = RateLimitsConfig::new([("openai", RateLimitConfig::new(Some(2), None))]);
+		let limiter = RateLimiter::new(config);
+		let start = Instant::now();
+
+		// -- Exec & Check
+		assert!(limiter.try_acquire_at("openai", 100, start).is_ok());
+		assert!(limiter.try_acquire_at("openai", 100, start + Duration::from_secs(10)).is_ok());
+		let wait = limiter
+			.try_acquire_at("openai", 100, start + Duration::from_secs(20))
+			.err()
+			.ok_or("Should be rate limited")?;
+		assert_eq!(wait, Duration::from_secs(40));
+		assert!(limiter.try_acquire_at("openai", 100, start + Duration::from_secs(60)).is_ok());
+
+		// other adapter kinds are not limited
+		assert!(limiter.try_acquire_at("anthropic", 100, start).is_ok());
+		assert!(limiter.try_acquire_at("anthropic", 100, start).is_ok());
+		assert!(limiter.try_acquire_at("anthropic", 100, start).is_ok());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_rate_limiter_tokens_per_min() -> Result<()> {
+		// -- Setup & Fixtures
+		let config = RateLimitsConfig::new([("gemini", RateLimitConfig::new(None, Some(1000)))]);
+		let limiter = RateLimiter::new(config);
+		let start = Instant::now();
+
+		// -- Exec & Check
+		// a first request over the limit is still allowed (empty window)
+		assert!(limiter.try_acquire_at("gemini", 1500, start).is_ok());
+		let wait = limiter
+			.try_acquire_at("gemini", 100, start + Duration::from_secs(15))
+			.err()
+			.ok_or("Should be rate limited")?;
+		assert_eq!(wait, Duration::from_secs(45));
+
+		assert!(limiter.try_acquire_at("gemini", 600, start + Duration::from_secs(60)).is_ok());
+		assert!(limiter.try_acquire_at("gemini", 400, start + Duration::from_secs(61)).is_ok());
+		assert!(limiter.try_acquire_at("gemini", 1, start + Duration::from_secs(62)).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_rate_limiter_zero_requests_per_min_tokens_checked() -> Result<()> {
+		// -- Setup & Fixtures
+		let config = RateLimitsConfig::new([("openai", RateLimitConfig::new(Some(0), Some(1000)))]);
+		let limiter = RateLimiter::new(config);
+		let start = Instant::now();
+
+		// -- Exec & Check
+		assert!(limiter.try_acquire_at("openai", 800, start).is_ok());
+		let wait = limiter
+			.try_acquire_at("openai", 300, start + Duration::from_secs(30))
+			.err()
+			.ok_or("Should be rate limited by the tokens per min")?;
+		assert_eq!(wait, Duration::from_secs(30));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
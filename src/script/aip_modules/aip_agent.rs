@@ -71,6 +71,8 @@ pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 ///     By default, it is the directory of the caller agent. If provided, it overrides the default (e.g.,
 ///     using `CTX.WORKSPACE_DIR`). Note that pack references (e.g., `ns@pack/`) are still resolved to
 ///     their pack path regardless of this base directory.
+///   - `params?: table`: (since 0.8.34) The run params of the called agent, available as `CTX.PARAMS` (or `params`)
+///     in its stages and as `params` in its prompt templates. The params of the caller are not passed through.
 ///
 /// #### Input Examples:
 ///
@@ -230,8 +232,12 @@ fn get_agent_dir_from_lua(lua: &Lua) -> Option<SPath> {
 mod tests {
 	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
 
-	use crate::_test_support::{assert_contains, eval_lua, run_reflective_agent, run_test_agent, setup_lua};
+	use crate::_test_support::{
+		assert_contains, eval_lua, gen_test_dir_path, remove_test_dir, run_reflective_agent,
+		run_reflective_agent_with_options, run_test_agent, save_file_content, setup_lua,
+	};
 	use crate::agent::Agent;
+	use crate::run::{Cassette, CassetteMode, RunBaseOptions};
 	use crate::runtime::Runtime;
 	use value_ext::JsonValueExt;
 
@@ -299,6 +305,25 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_script_aip_agent_run_with_empty_params() -> Result<()> {
+		// -- Setup & Fixtures
+		// NOTE: An empty Lua table is converted as an empty array, but is a valid (empty) params
+		let script = r#"
+            local result = aip.agent.run("agent-script/agent-hello", { inputs = {"John"}, params = {} })
+            return result
+        "#;
+
+		// -- Exec
+		let mut res = run_reflective_agent(script, None).await?;
+
+		// -- Check
+		let output = res.x_remove::<String>("/outputs/0")?;
+		assert_contains(&output, "Hello 'John' from agent-hello.aip");
+
+		Ok(())
+	}
+
 	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 	async fn test_script_aip_agent_run_with_options() -> Result<()> {
 		// -- Setup & Fixtures
@@ -325,6 +350,48 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_script_aip_agent_run_replay_cassette() -> Result<()> {
+		// -- Setup & Fixtures
+		// The sub-agent `mock::default` response is recorded from the mock dir, then replayed without it
+		let dir = gen_test_dir_path();
+		let mock_dir = dir.join("mocks");
+		save_file_content(&mock_dir.join("default.md"), "Hello from the recorded sub-agent")?;
+		let sub_agent_file = dir.join("sub-agent.aip");
+		save_file_content(
+			&sub_agent_file,
+			"# Options\n\n```toml\nmodel = \"mock::default\"\n```\n\n# Instruction\n\nSay hello\n\n# Output\n\n```lua\nreturn ai_response.content\n```\n",
+		)?;
+		let sub_agent_path = std::fs::canonicalize(sub_agent_file.path())?;
+		let script = format!(
+			r#"return aip.agent.run("{}", {{ inputs = {{ "one" }} }})"#,
+			sub_agent_path.to_string_lossy()
+		);
+		let record_options = RunBaseOptions::default()
+			.with_mock_dir(mock_dir.clone())
+			.with_cassette(Cassette::new(dir.join("cassette"), CassetteMode::Record));
+		let replay_options =
+			RunBaseOptions::default().with_cassette(Cassette::new(dir.join("cassette"), CassetteMode::Replay));
+
+		// -- Exec
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let record_res = run_reflective_agent_with_options(&script, None, runtime, &record_options).await?;
+		remove_test_dir(&mock_dir)?;
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let replay_res = run_reflective_agent_with_options(&script, None, runtime, &replay_options).await?;
+
+		// -- Check
+		for mut res in [record_res, replay_res] {
+			let output = res.x_remove::<String>("/outputs/0")?;
+			assert_eq!(output, "Hello from the recorded sub-agent");
+		}
+
+		// -- Clean
+		remove_test_dir(&dir)?;
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_script_aip_agent_extract_options_simple() -> Result<()> {
 		// -- Setup & Fixtures
@@ -0,0 +1,374 @@
+//! Defines the `aip_ai` module, used in the lua engine.
+//!
+//! This module provides direct AI calls from a Lua script, without an agent file
+//! (e.g., a quick classification in the `# Data` stage).
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.ai` module exposes functions to call the AI models directly.
+//!
+//! ### Functions
+//!
+//! - `aip.ai.chat(params: {model?: string, prompt?: string, messages?: list, options?: table}): AiResponse`
+
+use crate::agent::{AgentOptions, load_and_merge_configs_agent_options};
+use crate::model::RuntimeCtx;
+use crate::run::{AiExecRes, AiResponse, RunBaseOptions, adapter_name, exec_ai_chat, price_it};
+use crate::runtime::Runtime;
+use crate::script::LuaValueExt;
+use crate::support::text::{format_duration, format_usage};
+use crate::{Error, Result};
+use genai::ModelName;
+use genai::chat::{ChatMessage, ChatRequest};
+use mlua::{FromLua, Lua, Table, Value};
+use simple_fs::SPath;
+use std::time::Instant;
+
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let rt = runtime.clone();
+	let chat = lua.create_async_function(move |lua, params: Table| {
+		let rt = rt.clone();
+		async move { aip_ai_chat(&lua, &rt, params).await }
+	})?;
+
+	table.set("chat", chat)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Sends a chat request to an AI model and returns its response (since 0.8.34).
+///
+/// ```lua
+/// -- API Signature
+/// aip.ai.chat(params: {
+///   model?: string,
+///   prompt?: string,
+///   messages?: {role: "system" | "user" | "assistant", content: string}[],
+///   options?: table
+/// }): AiResponse
+/// ```
+///
+/// No agent file is needed. The options are the workspace config `options`, overridden by the
+/// `options` argument (same keys as the agent `options`, e.g., `temperature`, `model_aliases`, `fallback_on`),
+/// and the model is the `model` argument (resolved with the model aliases), or the `options.model`.
+///
+/// The AI call is made like the AI stage of a task (retries, fallback models, rate limits, `mock::` models
+/// with the mock files of the agent, `--record`/`--replay` cassette, `cache_responses`).
+/// Its cost is added to the task cost when called from a task stage (`# Data`, `# Output`),
+/// otherwise to the run cost (`# Before All`, `# After All`), and both are counted in the run `max_cost_usd`.
+///
+/// ### Arguments
+///
+/// - `params: table`
+///   - `model?: string`: The model (or model alias), e.g., `"gpt-5-mini"`.
+///   - `prompt?: string`: The user message (added after the `messages`).
+///   - `messages?: list`: The chat messages, e.g., `{ {role = "system", content = "..."}, {role = "user", content = "..."} }`.
+///   - `options?: table`: The agent options (e.g., `{ temperature = 0 }`).
+///
+/// ### Example
+///
+/// ```lua
+/// local res = aip.ai.chat({
+///   model  = "gpt-5-mini",
+///   prompt = "Classify this file as 'code' or 'doc':\n\n" .. file.content,
+///   options = { temperature = 0 }
+/// })
+/// return { kind = res.content, cost = res.price_usd }
+/// ```
+///
+/// ### Returns
+///
+/// Returns an `AiResponse` table (same shape as the `ai_response` of the `# Output` stage).
+///
+/// ```ts
+/// {
+///   content: string | nil,
+///   reasoning_content: string | nil,
+///   model_name: string,
+///   adapter_kind: string,
+///   usage: { prompt_tokens: number, completion_tokens: number, ... },
+///   price_usd: number | nil,
+///   duration_sec: number,
+///   info: string
+/// }
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if:
+/// - Neither `prompt` nor `messages` is given, or a message is invalid.
+/// - No model is given (argument or options), or the workspace config `options` are invalid.
+/// - Not called from an agent run.
+/// - The AI call fails (after its retries and fallback models).
+pub async fn aip_ai_chat(lua: &Lua, runtime: &Runtime, params: Table) -> mlua::Result<Value> {
+	// -- Extract the params
+	let chat_messages = parse_chat_messages(&params)?;
+
+	// NOTE: The `model` argument overrides the `options.model`, and is resolved with the model aliases
+	let options_table = params.get::<Option<Table>>("options")?.unwrap_or(lua.create_table()?);
+	if let Some(model) = params.get::<Option<String>>("model")? {
+		options_table.set("model", model)?;
+	}
+	let options = AgentOptions::from_lua(Value::Table(options_table), lua)?;
+	let options = load_and_merge_configs_agent_options(runtime.dir_context())?.merge(options)?;
+
+	let model_resolved: ModelName = options
+		.resolve_model()
+		.map(|model| model.to_string().into())
+		.ok_or_else(|| Error::custom("aip.ai.chat - No model. Set the 'model' param or the 'options.model'"))?;
+	let chat_options = options.to_genai_options(None);
+	let chat_req = ChatRequest::from_messages(chat_messages);
+
+	// -- The run & task of the call (the task only when called from a task stage)
+	let rt_ctx = RuntimeCtx::extract_from_global(lua)?;
+	let run_id = rt_ctx
+		.get_run_id(runtime.mm())
+		.map_err(Error::from)?
+		.ok_or_else(|| Error::custom("aip.ai.chat - Must be called from an agent run"))?;
+	let task_id = rt_ctx.get_task_id(runtime.mm()).map_err(Error::from)?;
+	let agent_file = get_agent_file_from_lua(lua)
+		.ok_or_else(|| Error::custom("aip.ai.chat - Must be called from an agent run (no CTX.AGENT_FILE_PATH)"))?;
+
+	// -- Exec the chat (like the AI stage, with the mock models, cassette, responses cache, retries, fallbacks, and rate limits)
+	let run_base_options = RunBaseOptions::current().unwrap_or_default();
+	let start = Instant::now();
+	let AiExecRes {
+		chat_res, is_cached, ..
+	} = exec_ai_chat(
+		runtime,
+		runtime.genai_client(),
+		&options,
+		&run_base_options,
+		&agent_file,
+		run_id,
+		task_id,
+		&model_resolved,
+		None,
+		chat_req,
+		&chat_options,
+		None,
+	)
+	.await?;
+	let duration = start.elapsed();
+	let duration_sec = (duration.as_secs_f64() * 1000.0).round() / 1000.0;
+
+	// -- Price & add the cost to the task (or to the run when not in a task)
+	// NOTE: A cached response has no cost
+	let ai_price = if is_cached {
+		None
+	} else {
+		price_it(
+			chat_res.model_iden.adapter_kind.as_lower_str(),
+			&chat_res.model_iden.model_name,
+			&chat_res.usage,
+		)
+	};
+	if let Some(ai_price) = ai_price.as_ref() {
+		let rt_model = runtime.rt_model();
+		match task_id {
+			Some(task_id) => {
+				rt_model
+					.add_task_cost(
+						run_id,
+						task_id,
+						ai_price.cost,
+						ai_price.cost_cache_write,
+						ai_price.cost_cache_saving,
+					)
+					.await?
+			}
+			None => rt_model.add_run_cost(run_id, ai_price.cost).await?,
+		}
+	}
+
+	// -- Build the AiResponse
+	let mut info = format!("Duration: {}", format_duration(duration));
+	if is_cached {
+		info = format!("{info} | cached");
+	}
+	if let Some(ai_price) = ai_price.as_ref() {
+		info = format!("{info} | ~${}", ai_price.cost);
+	}
+	let adapter_kind = adapter_name(&chat_res.model_iden);
+	info = format!(
+		"{info} | {usage} | Model: {model_name} | Adapter: {adapter_kind}",
+		usage = format_usage(&chat_res.usage),
+		model_name = chat_res.model_iden.model_name,
+	);
+
+	let ai_response = AiResponse {
+		content: chat_res.content.into_joined_texts().filter(|s| !s.is_empty()),
+		json: None,
+		reasoning_content: chat_res.reasoning_content,
+		model_name: chat_res.model_iden.model_name,
+		adapter_kind,
+		usage: chat_res.usage,
+		price_usd: ai_price.map(|ap| ap.cost),
+		duration_sec,
+		info,
+	};
+
+	lua.pack(ai_response)
+}
+
+// region:    --- Support
+
+/// The calling agent file (for the mock models), from the Lua CTX global.
+fn get_agent_file_from_lua(lua: &Lua) -> Option<SPath> {
+	lua.globals()
+		.x_get_value("CTX")?
+		.x_get_string("AGENT_FILE_PATH")
+		.map(|s| s.into())
+}
+
+/// The chat messages of the `messages` and `prompt` params
+fn parse_chat_messages(params: &Table) -> Result<Vec<ChatMessage>> {
+	let mut chat_messages: Vec<ChatMessage> = Vec::new();
+
+	if let Some(messages) = params.get::<Option<Table>>("messages")? {
+		for (idx, msg) in messages.sequence_values::<Table>().enumerate() {
+			let msg =
+				msg.map_err(|err| Error::custom(format!("aip.ai.chat - Message #{} must be a table. {err}", idx + 1)))?;
+			let role = msg.get::<Option<String>>("role")?.unwrap_or_else(|| "user".to_string());
+			let content = msg
+				.get::<Option<String>>("content")?
+				.ok_or_else(|| Error::custom(format!("aip.ai.chat - Message #{} must have a 'content'", idx + 1)))?;
+			let chat_message = match role.as_str() {
+				"system" => ChatMessage::system(content),
+				"user" => ChatMessage::user(content),
+				"assistant" => ChatMessage::assistant(content),
+				other => {
+					return Err(Error::custom(format!(
+						"aip.ai.chat - Message #{} role '{other}' is not supported (must be 'system', 'user', or 'assistant')",
+						idx + 1
+					)));
+				}
+			};
+			chat_messages.push(chat_message);
+		}
+	}
+
+	if let Some(prompt) = params.get::<Option<String>>("prompt")? {
+		chat_messages.push(ChatMessage::user(prompt));
+	}
+
+	if chat_messages.is_empty() {
+		return Err(Error::custom("aip.ai.chat - Must have a 'prompt' or 'messages' param"));
+	}
+
+	Ok(chat_messages)
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{
+		gen_test_dir_path, remove_test_dir, run_reflective_agent_with_runtime, save_file_content,
+	};
+	use crate::agent::Agent;
+	use crate::model::{RunBmc, TaskBmc};
+	use crate::run::{RunBaseOptions, run_agent};
+	use crate::runtime::Runtime;
+	use value_ext::JsonValueExt;
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_ai_chat_mock_model_and_cost() -> Result<()> {
+		// -- Setup & Fixtures
+		// One call outside a task (Before All, cost on the run), and one in a task (Data, cost on the task)
+		let dir = gen_test_dir_path();
+		let mock_dir = dir.join("mocks");
+		save_file_content(&mock_dir.join("default.md"), "Hello from the mock")?;
+		let fx_content = r#"
+# Before All
+
+```lua
+return aip.ai.chat({ model = "mock::default", prompt = "Say hello" })
+```
+
+# Data
+
+```lua
+return aip.ai.chat({ model = "mock::default", prompt = "Say hello to " .. input })
+```
+
+# Output
+
+```lua
+return { before_all = before_all.content, data = data.content, price_usd = data.price_usd, adapter_kind = data.adapter_kind }
+```
+"#;
+		let agent = Agent::mock_from_content(fx_content)?;
+		let run_base_options = RunBaseOptions::default().with_mock_dir(mock_dir);
+
+		// -- Exec
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let res = run_agent(&runtime, None, agent, Some(vec!["one".into()]), &run_base_options, true).await?;
+
+		// -- Check
+		let mut outputs = res.outputs.ok_or("Should have outputs")?;
+		let mut output = outputs.pop().ok_or("Should have one output")?;
+		assert_eq!(output.x_remove::<String>("before_all")?, "Hello from the mock");
+		assert_eq!(output.x_remove::<String>("data")?, "Hello from the mock");
+		assert_eq!(output.x_remove::<f64>("price_usd")?, 0.0);
+		assert_eq!(output.x_remove::<String>("adapter_kind")?, "Mock");
+		let run = RunBmc::list(runtime.mm(), None)?.pop().ok_or("Should have one run")?;
+		assert_eq!(run.ai_cost, Some(0.0));
+		assert_eq!(run.total_cost, Some(0.0));
+		let task = TaskBmc::list_for_run(runtime.mm(), run.id)?
+			.pop()
+			.ok_or("Should have one task")?;
+		assert_eq!(task.cost, Some(0.0));
+
+		// -- Clean
+		remove_test_dir(&dir)?;
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_ai_chat_params_err() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_cases = [
+			(
+				"aip.ai.chat({ model = 'gpt-5-mini' })",
+				"Must have a 'prompt' or 'messages'",
+			),
+			(
+				"aip.ai.chat({ messages = { {role = 'tool', content = 'x'} } })",
+				"role 'tool' is not supported",
+			),
+			(
+				"aip.ai.chat({ messages = { {role = 'user'} } })",
+				"must have a 'content'",
+			),
+		];
+
+		for (fx_code, fx_err) in fx_cases {
+			// -- Exec
+			let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+			let err = run_reflective_agent_with_runtime(fx_code, None, runtime)
+				.await
+				.err()
+				.ok_or("Should fail")?;
+
+			// -- Check
+			let err = err.to_string();
+			assert!(err.contains(fx_err), "err: {err}");
+		}
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -3,6 +3,7 @@
 mod support;
 
 pub mod aip_agent;
+pub mod aip_ai;
 pub mod aip_cmd;
 pub mod aip_code;
 pub mod aip_csv;
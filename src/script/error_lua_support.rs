@@ -1,18 +1,19 @@
 //! Lua Management implementaitons for the crate::Error
 
 use crate::Error;
+use crate::script::ScriptLocation;
 use lazy_regex::regex;
 use std::borrow::Cow;
 use std::sync::Arc;
 
 impl Error {
-	pub fn from_error_with_script(lua_error: &mlua::Error, script: &str) -> Error {
+	pub fn from_error_with_script(lua_error: &mlua::Error, script: &str, location: Option<&ScriptLocation>) -> Error {
 		let mut buff: Vec<String> = Vec::new();
 		for item in lua_error.chain() {
 			if let Some(lua_item) = item.downcast_ref::<mlua::Error>() {
 				let msg = lua_item.to_string();
 				let msg = if msg.contains("traceback") | msg.contains("syntax") {
-					process_stack_with_script(&msg, script)
+					process_stack_with_script(&msg, script, location)
 				} else {
 					msg
 				};
@@ -25,7 +26,7 @@ impl Error {
 	}
 }
 
-fn process_stack_with_script(stack: &str, script: &str) -> String {
+fn process_stack_with_script(stack: &str, script: &str, location: Option<&ScriptLocation>) -> String {
 	let script_lines: Vec<&str> = script.lines().collect();
 	let mut buff: Vec<Cow<str>> = Vec::new();
 
@@ -36,9 +37,16 @@ fn process_stack_with_script(stack: &str, script: &str) -> String {
 			// Replace all occurrences of the pattern with the extracted number
 			let replaced_line = rx.replace_all(line, |caps: &regex::Captures| {
 				if let Some(num) = caps.get(1).and_then(|m| m.as_str().parse::<usize>().ok()) {
+					// The line in the agent file when the script location is known
+					let at = match location {
+						Some(location) => format!("{}:{}", location.file_path, location.file_line(num)),
+						None => format!("line {num}"),
+					};
 					if let Some(script_line) = script_lines.get(num - 1) {
 						let script_line = script_line.trim();
-						Cow::from(format!("At line {num} '{script_line}'"))
+						Cow::from(format!("At {at} '{script_line}'"))
+					} else if let Some(location) = location {
+						Cow::from(format!("At {}:{}", location.file_path, location.file_line(num)))
 					} else {
 						Cow::from(format!("Line({num})"))
 					}
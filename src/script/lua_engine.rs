@@ -1,12 +1,12 @@
 use crate::Result;
 use crate::hub::{HubEvent, get_hub};
 use crate::model::{LogKind, RuntimeCtx};
-use crate::run::Literals;
+use crate::run::{Literals, RunDeadline};
 use crate::runtime::Runtime;
 use crate::script::aip_modules::aip_lua;
-use crate::script::serde_value_to_lua_value;
 use crate::script::support::process_lua_eval_result;
-use mlua::{IntoLua, Lua, Table, Value};
+use crate::script::{ScriptLocation, serde_value_to_lua_value};
+use mlua::{HookTriggers, IntoLua, Lua, Table, Value, VmState};
 
 pub struct LuaEngine {
 	#[allow(unused)]
@@ -95,6 +95,10 @@ impl LuaEngine {
 		// -- Set CTX as global
 		// TODO: Might need to become USERMETA data to avoid mutability
 		let globals = lua.globals();
+		// `params` is a shortcut to `CTX.PARAMS`
+		if let Value::Table(ctx) = &ctx {
+			globals.set("params", ctx.get::<Value>("PARAMS")?)?;
+		}
 		globals.set("CTX", ctx)?;
 
 		Ok(engine)
@@ -104,10 +108,18 @@ impl LuaEngine {
 /// Public Function
 impl LuaEngine {
 	pub async fn eval(&self, script: &str, scope: Option<Table>) -> Result<Value> {
-		self.eval_with_paths(script, scope, std::iter::empty::<&str>()).await
+		self.eval_with_paths(script, scope, std::iter::empty::<&str>(), None).await
 	}
 
-	pub async fn eval_with_paths<I, S>(&self, script: &str, scope: Option<Table>, addl_lua_paths: I) -> Result<Value>
+	/// Eval the script with the additional lua paths (for `require`),
+	/// and the script location in the agent file (for the error lines)
+	pub async fn eval_with_paths<I, S>(
+		&self,
+		script: &str,
+		scope: Option<Table>,
+		addl_lua_paths: I,
+		location: Option<&ScriptLocation>,
+	) -> Result<Value>
 	where
 		I: IntoIterator<Item = S>,
 		S: AsRef<str>,
@@ -123,10 +135,37 @@ impl LuaEngine {
 			chunck
 		};
 
+		// -- Abort the script when the task (or run) deadline is passed (e.g., a busy loop)
+		let deadline = RunDeadline::current();
+		if let Some(deadline) = deadline {
+			lua.set_global_hook(
+				HookTriggers::new().every_nth_instruction(10_000),
+				move |_lua, _debug| {
+					if deadline.is_passed() {
+						Err(mlua::Error::runtime(deadline.to_error().to_string()))
+					} else {
+						Ok(VmState::Continue)
+					}
+				},
+			)?;
+		}
+
 		let res = chunck.eval_async::<Value>().await;
 		// let res = res?;
 
-		let res = process_lua_eval_result(&self.lua, res, script)?;
+		// NOTE: The hook is only for this eval (the engine can be reused later, e.g., for the tool handlers)
+		if deadline.is_some() {
+			lua.remove_global_hook();
+		}
+
+		// NOTE: The hook abort is returned as the timeout error (so that the task end error is at the timeout stage)
+		if res.is_err()
+			&& let Some(deadline) = deadline.filter(|deadline| deadline.is_passed())
+		{
+			return Err(deadline.to_error());
+		}
+
+		let res = process_lua_eval_result(&self.lua, res, script, location)?;
 
 		Ok(res)
 	}
@@ -364,7 +403,7 @@ fn init_aip(lua_vm: &Lua, runtime: &Runtime) -> Result<()> {
 		table, lua_vm, runtime, // -- The lua module names that refers to aip_...
 		flow, file, git, web, text, rust, path, md, tag, json, toml, csv, yaml, //
 		html, cmd, lua, code, hbs, semver, agent, uuid, hash, time, shape, pdf, editor, zip, //
-		udiffx
+		udiffx, ai
 	);
 
 	init_and_set!(table, lua_vm, runtime, run, task);
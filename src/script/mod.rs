@@ -14,10 +14,12 @@ mod support;
 mod aipack_custom;
 mod lua_engine;
 mod lua_uc;
+mod script_location;
 
 pub use aipack_custom::*;
 pub use lua_engine::*;
 pub use lua_helpers::*;
+pub use script_location::*;
 #[cfg(test)] // Needed for test only (beside this script module)
 pub use support::process_lua_eval_result;
 
@@ -0,0 +1,67 @@
+use lazy_regex::regex_captures;
+
+/// The location of a Lua code block in its agent file,
+/// so that the Lua error lines are reported as `agent.aip:LINE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptLocation {
+	pub file_path: String,
+	/// The 1-based line number, in the agent file, of the first line of the code block
+	pub line: usize,
+}
+
+/// Constructors
+impl ScriptLocation {
+	pub fn new(file_path: impl Into<String>, line: usize) -> Self {
+		Self {
+			file_path: file_path.into(),
+			line,
+		}
+	}
+
+	/// Find the first `At path/to/agent.aip:LINE` location in an error text (e.g., for the TUI error view)
+	pub fn find_in_text(text: &str) -> Option<ScriptLocation> {
+		let (_, file_path, line) = regex_captures!(r#"At ([^\s']+):(\d+)"#, text)?;
+		let line = line.parse::<usize>().ok()?;
+		Some(ScriptLocation::new(file_path, line))
+	}
+}
+
+impl ScriptLocation {
+	/// The agent file line of a 1-based line of the script
+	pub fn file_line(&self, script_line: usize) -> usize {
+		self.line + script_line.saturating_sub(1)
+	}
+}
+
+impl std::fmt::Display for ScriptLocation {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}:{}", self.file_path, self.line)
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_script_location_find_in_text_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let text = "Lua error:\nruntime error: At path/to/agent.aip:12 'return b.x' attempt to index a nil value";
+
+		// -- Exec
+		let location = ScriptLocation::find_in_text(text).ok_or("Should have location")?;
+
+		// -- Check
+		assert_eq!(location, ScriptLocation::new("path/to/agent.aip", 12));
+		assert_eq!(location.file_line(3), 14);
+		assert!(ScriptLocation::find_in_text("runtime error: At line 3 'return b.x'").is_none());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
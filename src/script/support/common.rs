@@ -1,21 +1,28 @@
 use crate::dir_context::PathResolver;
 use crate::runtime::Runtime;
+use crate::script::ScriptLocation;
 use crate::{Error, Result};
 use mlua::{Lua, Table, Value};
 use std::collections::{BTreeSet, HashSet};
 
 /// Process correctly the lua eval result
 /// (Used by the lua engine eval, and test)
-pub fn process_lua_eval_result(_lua: &Lua, res: mlua::Result<Value>, script: &str) -> Result<Value> {
+/// NOTE: When the script location is given, the error lines are the agent file lines
+pub fn process_lua_eval_result(
+	_lua: &Lua,
+	res: mlua::Result<Value>,
+	script: &str,
+	location: Option<&ScriptLocation>,
+) -> Result<Value> {
 	let res = match res {
 		Ok(res) => res,
-		Err(err) => return Err(Error::from_error_with_script(&err, script)),
+		Err(err) => return Err(Error::from_error_with_script(&err, script, location)),
 	};
 
 	let res = match res {
 		// This is when we d with pcall(...), see test_lua_json_parse_invalid
 		Value::Error(err) => {
-			return Err(Error::from_error_with_script(&err, script));
+			return Err(Error::from_error_with_script(&err, script, location));
 			// return Err(Error::from(&*err));
 		}
 		res => res,
@@ -1,5 +1,6 @@
 use crate::types::{CsvContent, CsvOptions};
 use crate::{Error, Result};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::path::Path;
 
@@ -189,6 +190,29 @@ pub fn load_csv_headers(path: impl AsRef<Path>, options: Option<CsvOptions>) ->
 	Ok(headers)
 }
 
+/// Convert the CSV content into one JSON value per row.
+///
+/// - With headers, each row is an object `{header: cell}` (missing cells are empty strings)
+/// - Without headers, each row is an array of strings
+pub fn csv_content_to_records(content: CsvContent) -> Vec<Value> {
+	let CsvContent { headers, rows } = content;
+
+	rows.into_iter()
+		.map(|row| {
+			if headers.is_empty() {
+				Value::Array(row.into_iter().map(Value::String).collect())
+			} else {
+				let mut cells = row.into_iter();
+				let record: Map<String, Value> = headers
+					.iter()
+					.map(|header| (header.clone(), Value::String(cells.next().unwrap_or_default())))
+					.collect();
+				Value::Object(record)
+			}
+		})
+		.collect()
+}
+
 /// Remap CSV headers (labels) to internal keys.
 ///
 /// Use `header_labels` map { key: label }.
@@ -137,3 +137,20 @@ fn test_support_csv_load_headers_remapped() -> Result<()> {
 
 	Ok(())
 }
+
+#[test]
+fn test_support_csv_content_to_records_simple() -> Result<()> {
+	// -- Setup & Fixtures
+	let content = load_csv("tests-data/sandbox-01/example.csv", None)?;
+
+	// -- Exec
+	let records = csv_content_to_records(content);
+
+	// -- Check
+	assert_eq!(records.len(), 2);
+	assert_eq!(records[0]["id"], "1");
+	assert_eq!(records[1]["name"], "Bob");
+	assert_eq!(records[1]["email"], "bob@example.com");
+
+	Ok(())
+}
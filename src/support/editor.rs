@@ -59,6 +59,12 @@ impl EditorProgram {
 /// Opens a file in the auto-detected editor.
 /// Returns the editor program if successful, or Error if no editor was found.
 pub fn open_file_auto(path: &SPath) -> Result<EditorProgram> {
+	open_file_at_line_auto(path, None)
+}
+
+/// Opens a file at a given line (1-based) in the auto-detected editor
+/// (for editors that do not support it, the file is opened without the line).
+pub fn open_file_at_line_auto(path: &SPath, line: Option<usize>) -> Result<EditorProgram> {
 	let Some(editor) = editor_program() else {
 		return Err(
 			format!("No editor found. Cannot open '{path}'.\nSet your VISUAL or EDITOR environment variable.").into(),
@@ -68,13 +74,27 @@ pub fn open_file_auto(path: &SPath) -> Result<EditorProgram> {
 	let program = editor.program();
 	let mut cmd = crate::support::os::new_run_command(program);
 
-	// VSCode (code) often needs '--' before the path to correctly handle paths on Windows
-	if editor == EditorProgram::Vscode {
-		cmd.arg("--");
+	match (&editor, line) {
+		// VSCode goes to the line with `-g path:line`
+		(EditorProgram::Vscode, Some(line)) => {
+			cmd.arg("-g").arg(format!("{path}:{line}"));
+		}
+		// VSCode (code) often needs '--' before the path to correctly handle paths on Windows
+		(EditorProgram::Vscode, None) => {
+			cmd.arg("--").arg(path.as_str());
+		}
+		(EditorProgram::Zed | EditorProgram::Sublime | EditorProgram::Atom, Some(line)) => {
+			cmd.arg(format!("{path}:{line}"));
+		}
+		(EditorProgram::Neovim | EditorProgram::Vim | EditorProgram::Emacs | EditorProgram::Nano, Some(line)) => {
+			cmd.arg(format!("+{line}")).arg(path.as_str());
+		}
+		_ => {
+			cmd.arg(path.as_str());
+		}
 	}
 
-	cmd.arg(path.as_str())
-		.spawn()
+	cmd.spawn()
 		.map_err(|err| format!("Failed to open editor '{program}' for file '{path}'.\nCause: {err}"))?;
 
 	Ok(editor)
@@ -0,0 +1,262 @@
+//! A light JSON Schema validation, for the common keywords of the structured outputs.
+//!
+//! Supported: `type` (string or list), `enum`, `const`, `properties`, `required`, `additionalProperties` (bool or schema),
+//! `items`, `minItems`, `maxItems`, `anyOf`, `oneOf`, `allOf`.
+//! Other keywords (e.g., `format`, `pattern`, `$ref`) are ignored.
+
+use crate::{Error, Result};
+use serde_json::Value;
+
+/// Validate the value against the JSON schema.
+/// Returns an error with the JSON pointer of the first invalid value.
+pub fn validate_json_schema(schema: &Value, value: &Value) -> Result<()> {
+	match check(schema, value, "") {
+		Some(err) => Err(Error::custom(err)),
+		None => Ok(()),
+	}
+}
+
+/// Returns the first validation error (None if valid)
+fn check(schema: &Value, value: &Value, path: &str) -> Option<String> {
+	// NOTE: `true` and `{}` schemas allow everything, `false` nothing
+	let schema = match schema {
+		Value::Object(schema) => schema,
+		Value::Bool(false) => return Some(format!("{}: no value allowed", display_path(path))),
+		_ => return None,
+	};
+
+	// -- type
+	if let Some(typ) = schema.get("type") {
+		let types: Vec<&str> = match typ {
+			Value::String(typ) => vec![typ.as_str()],
+			Value::Array(types) => types.iter().filter_map(|t| t.as_str()).collect(),
+			_ => Vec::new(),
+		};
+		if !types.is_empty() && !types.iter().any(|typ| matches_type(typ, value)) {
+			return Some(format!(
+				"{}: must be of type '{}' but was {}",
+				display_path(path),
+				types.join("' or '"),
+				type_name(value)
+			));
+		}
+	}
+
+	// -- enum & const
+	if let Some(Value::Array(values)) = schema.get("enum")
+		&& !values.contains(value)
+	{
+		return Some(format!(
+			"{}: must be one of {} but was {value}",
+			display_path(path),
+			Value::Array(values.clone())
+		));
+	}
+	if let Some(expected) = schema.get("const")
+		&& expected != value
+	{
+		return Some(format!("{}: must be {expected} but was {value}", display_path(path)));
+	}
+
+	// -- object
+	if let Value::Object(obj) = value {
+		if let Some(Value::Array(required)) = schema.get("required") {
+			for name in required.iter().filter_map(|n| n.as_str()) {
+				if !obj.contains_key(name) {
+					return Some(format!("{}: '{name}' is required", display_path(path)));
+				}
+			}
+		}
+		let properties = schema.get("properties").and_then(|p| p.as_object());
+		for (name, prop_value) in obj {
+			let prop_path = format!("{path}/{name}");
+			let err = match properties.and_then(|p| p.get(name)) {
+				Some(prop_schema) => check(prop_schema, prop_value, &prop_path),
+				None => match schema.get("additionalProperties") {
+					Some(Value::Bool(false)) => Some(format!("{}: property not allowed", display_path(&prop_path))),
+					Some(additional_schema) => check(additional_schema, prop_value, &prop_path),
+					None => None,
+				},
+			};
+			if err.is_some() {
+				return err;
+			}
+		}
+	}
+
+	// -- array
+	if let Value::Array(items) = value {
+		if let Some(min) = schema.get("minItems").and_then(|v| v.as_u64())
+			&& (items.len() as u64) < min
+		{
+			return Some(format!("{}: must have at least {min} items", display_path(path)));
+		}
+		if let Some(max) = schema.get("maxItems").and_then(|v| v.as_u64())
+			&& (items.len() as u64) > max
+		{
+			return Some(format!("{}: must have at most {max} items", display_path(path)));
+		}
+		if let Some(item_schema) = schema.get("items") {
+			for (idx, item) in items.iter().enumerate() {
+				if let Some(err) = check(item_schema, item, &format!("{path}/{idx}")) {
+					return Some(err);
+				}
+			}
+		}
+	}
+
+	// -- combinators
+	if let Some(Value::Array(schemas)) = schema.get("allOf") {
+		for sub_schema in schemas {
+			if let Some(err) = check(sub_schema, value, path) {
+				return Some(err);
+			}
+		}
+	}
+	if let Some(Value::Array(schemas)) = schema.get("anyOf")
+		&& !schemas.is_empty()
+		&& schemas.iter().all(|sub_schema| check(sub_schema, value, path).is_some())
+	{
+		return Some(format!(
+			"{}: does not match any of the 'anyOf' schemas",
+			display_path(path)
+		));
+	}
+	// NOTE: `oneOf` must match exactly one schema
+	if let Some(Value::Array(schemas)) = schema.get("oneOf")
+		&& !schemas.is_empty()
+	{
+		let match_count = schemas
+			.iter()
+			.filter(|sub_schema| check(sub_schema, value, path).is_none())
+			.count();
+		match match_count {
+			1 => (),
+			0 => {
+				return Some(format!(
+					"{}: does not match any of the 'oneOf' schemas",
+					display_path(path)
+				));
+			}
+			n => {
+				return Some(format!(
+					"{}: must match exactly one of the 'oneOf' schemas but matched {n}",
+					display_path(path)
+				));
+			}
+		}
+	}
+
+	None
+}
+
+fn matches_type(typ: &str, value: &Value) -> bool {
+	match typ {
+		"string" => value.is_string(),
+		"number" => value.is_number(),
+		"integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0),
+		"boolean" => value.is_boolean(),
+		"array" => value.is_array(),
+		"object" => value.is_object(),
+		"null" => value.is_null(),
+		_ => true,
+	}
+}
+
+fn type_name(value: &Value) -> &'static str {
+	match value {
+		Value::Null => "null",
+		Value::Bool(_) => "boolean",
+		Value::Number(_) => "number",
+		Value::String(_) => "string",
+		Value::Array(_) => "array",
+		Value::Object(_) => "object",
+	}
+}
+
+fn display_path(path: &str) -> &str {
+	if path.is_empty() { "(root)" } else { path }
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_json_schema_validate() -> Result<()> {
+		// -- Setup & Fixtures
+		let schema = json!({
+			"type": "object",
+			"properties": {
+				"label": { "type": "string", "enum": ["bug", "feature"] },
+				"score": { "type": "number" },
+				"tags": { "type": "array", "items": { "type": "string" } }
+			},
+			"required": ["label", "score"],
+			"additionalProperties": false
+		});
+
+		// -- Exec & Check
+		validate_json_schema(&schema, &json!({"label": "bug", "score": 0.8, "tags": ["a"]}))?;
+
+		let err = validate_json_schema(&schema, &json!({"label": "bug"}))
+			.err()
+			.ok_or("Should fail")?;
+		assert!(err.to_string().contains("'score' is required"), "err: {err}");
+
+		let err = validate_json_schema(&schema, &json!({"label": "bug", "score": 1, "tags": [1]}))
+			.err()
+			.ok_or("Should fail")?;
+		assert!(
+			err.to_string().contains("/tags/0: must be of type 'string'"),
+			"err: {err}"
+		);
+
+		let err = validate_json_schema(&schema, &json!({"label": "other", "score": 1}))
+			.err()
+			.ok_or("Should fail")?;
+		assert!(err.to_string().contains("/label: must be one of"), "err: {err}");
+
+		let err = validate_json_schema(&schema, &json!({"label": "bug", "score": 1, "x": 1}))
+			.err()
+			.ok_or("Should fail")?;
+		assert!(err.to_string().contains("/x: property not allowed"), "err: {err}");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_json_schema_validate_any_of_one_of() -> Result<()> {
+		// -- Setup & Fixtures
+		let any_of = json!({ "anyOf": [{ "type": "integer" }, { "type": "number" }] });
+		let one_of = json!({ "oneOf": [{ "type": "integer" }, { "type": "number" }] });
+
+		// -- Exec & Check
+		// an integer matches both schemas (ok for anyOf, not for oneOf)
+		validate_json_schema(&any_of, &json!(1))?;
+		let err = validate_json_schema(&one_of, &json!(1)).err().ok_or("Should fail")?;
+		assert!(err.to_string().contains("exactly one of the 'oneOf'"), "err: {err}");
+
+		validate_json_schema(&one_of, &json!(1.5))?;
+
+		let err = validate_json_schema(&one_of, &json!("a")).err().ok_or("Should fail")?;
+		assert!(
+			err.to_string().contains("does not match any of the 'oneOf'"),
+			"err: {err}"
+		);
+		let err = validate_json_schema(&any_of, &json!("a")).err().ok_or("Should fail")?;
+		assert!(
+			err.to_string().contains("does not match any of the 'anyOf'"),
+			"err: {err}"
+		);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -20,6 +20,7 @@ pub mod editor;
 pub mod files;
 pub mod hbs;
 pub mod html;
+pub mod json_schema;
 pub mod jsons;
 pub mod md;
 pub mod os;
@@ -0,0 +1,65 @@
+/// Returns a simple line diff of `expected` and `actual` (based on their longest common subsequence),
+/// with the `- ` lines only in expected, the `+ ` lines only in actual, and the `  ` common lines.
+pub fn line_diff(expected: &str, actual: &str) -> String {
+	let exp: Vec<&str> = expected.lines().collect();
+	let act: Vec<&str> = actual.lines().collect();
+
+	// -- The LCS lengths table (lcs[i][j] for exp[i..] and act[j..])
+	let mut lcs = vec![vec![0usize; act.len() + 1]; exp.len() + 1];
+	for i in (0..exp.len()).rev() {
+		for j in (0..act.len()).rev() {
+			lcs[i][j] = if exp[i] == act[j] {
+				lcs[i + 1][j + 1] + 1
+			} else {
+				lcs[i + 1][j].max(lcs[i][j + 1])
+			};
+		}
+	}
+
+	// -- Walk the table
+	let mut lines: Vec<String> = Vec::new();
+	let (mut i, mut j) = (0, 0);
+	while i < exp.len() && j < act.len() {
+		if exp[i] == act[j] {
+			lines.push(format!("  {}", exp[i]));
+			i += 1;
+			j += 1;
+		} else if lcs[i + 1][j] >= lcs[i][j + 1] {
+			lines.push(format!("- {}", exp[i]));
+			i += 1;
+		} else {
+			lines.push(format!("+ {}", act[j]));
+			j += 1;
+		}
+	}
+	lines.extend(exp[i..].iter().map(|line| format!("- {line}")));
+	lines.extend(act[j..].iter().map(|line| format!("+ {line}")));
+
+	lines.join("\n")
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_text_line_diff_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let expected = "one\ntwo\nthree";
+		let actual = "one\n2\nthree\nfour";
+
+		// -- Exec
+		let diff = line_diff(expected, actual);
+
+		// -- Check
+		assert_eq!(diff, "  one\n- two\n+ 2\n  three\n+ four");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
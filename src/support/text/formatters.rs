@@ -4,6 +4,7 @@ use num_format::ToFormattedString;
 use simple_fs::PrettySizeOptions;
 use std::time::Duration;
 use time::{OffsetDateTime, format_description};
+use time_tz::OffsetDateTimeExt as _;
 
 // region:    --- Spaces
 
@@ -114,6 +115,27 @@ pub fn format_time_local(epoch_us: i64) -> Result<String> {
 	Ok(res)
 }
 
+/// Format the epoch_us as local `YYYY-MM-DD hh:mm:ss`
+/// (fallback to `YYYY-MM-DD hh:mm:ss UTC` if the local time zone cannot be determined)
+///
+/// NOTE: Uses the system time zone, as `UtcOffset::current_local_offset()` fails in a multi-threaded process.
+pub fn format_datetime_local(epoch_us: i64) -> Result<String> {
+	fn inner(epoch_us: i64) -> std::result::Result<String, Box<dyn std::error::Error>> {
+		let secs = epoch_us / 1_000_000;
+		let utc_dt = OffsetDateTime::from_unix_timestamp(secs)?;
+		let format = format_description::parse_borrowed::<3>("[year]-[month]-[day] [hour]:[minute]:[second]")?;
+
+		match time_tz::system::get_timezone() {
+			Ok(local_tz) => Ok(utc_dt.to_timezone(local_tz).format(&format)?),
+			Err(_) => Ok(format!("{} UTC", utc_dt.format(&format)?)),
+		}
+	}
+
+	let res = inner(epoch_us).map_err(|err| format!("Cannot format epoch_us '{epoch_us}'.\nCause: {err}"))?;
+
+	Ok(res)
+}
+
 // endregion: --- Duration
 
 /// Formats 9 fix chars
@@ -1,12 +1,14 @@
 // region:    --- Modules
 
 mod change;
+mod diff;
 mod formatters;
 mod hash;
 mod line_block_iter;
 mod text_common;
 
 pub use change::*;
+pub use diff::*;
 pub use formatters::*;
 pub use hash::*;
 pub use line_block_iter::*;
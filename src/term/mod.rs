@@ -2,16 +2,10 @@
 
 mod common;
 mod control;
-mod init_term;
-mod prompt_input;
-mod prompt_select;
 mod safer_print;
 
 pub use common::*;
 pub use control::*;
-pub use init_term::*;
-pub use prompt_input::*;
-pub use prompt_select::*;
 pub use safer_print::*;
 
 // endregion: --- Modules
@@ -1,10 +1,12 @@
 use super::{AppStateCore, SysState};
 use crate::Result;
+use crate::hub::AiStreamEvent;
 use crate::model::{Id, ModelEvent, ModelManager, Task};
 use crate::support::time::now_micro;
 use crate::tui::core::event::{AppActionEvent, LastAppEvent};
 use crate::tui::core::{
-	AppStage, ConfigTab, GroupDashTab, MouseEvt, OverviewTasksMode, RunItemStore, RunTab, RunTasksInfo, ScrollZones,
+	AiStreamBuf, AiStreams, AppStage, ConfigTab, GroupDashTab, MouseEvt, OverviewTasksMode, RunItemStore, RunTab,
+	RunTasksInfo, ScrollZones,
 };
 use crate::tui::view::PopupView;
 use crossterm::event::MouseEvent;
@@ -42,6 +44,7 @@ impl AppState {
 			selected_loop_id: None,
 
 			running_tick_start: None,
+			ai_streams: AiStreams::default(),
 
 			// -- RunMainView
 			run_tab: RunTab::Tasks, // Tasks tab by default
@@ -203,6 +206,17 @@ impl AppState {
 			None
 		}
 	}
+
+	/// The streamed AI response of the current task (None if not in its AI call)
+	pub fn current_task_ai_stream(&self) -> Option<&AiStreamBuf> {
+		let task = self.current_task()?;
+		self.core.ai_streams.get(task.id)
+	}
+
+	/// Called from the app state processor on the AI stream hub events
+	pub(in crate::tui::core) fn apply_ai_stream_event(&mut self, event: &AiStreamEvent) {
+		self.core.ai_streams.apply(event);
+	}
 }
 
 /// System & Event
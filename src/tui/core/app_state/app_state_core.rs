@@ -2,8 +2,8 @@ use super::SysState;
 use crate::model::{ErrRec, Id, ModelManager, Task};
 use crate::tui::core::event::{AppActionEvent, LastAppEvent};
 use crate::tui::core::{
-	AppStage, ConfigTab, GroupDashData, GroupDashTab, MouseEvt, OverviewTasksMode, RunItemStore, RunTab, RunTasksInfo,
-	ScrollIden, ScrollZone, ScrollZones, UiAction,
+	AiStreams, AppStage, ConfigTab, GroupDashData, GroupDashTab, MouseEvt, OverviewTasksMode, RunItemStore, RunTab,
+	RunTasksInfo, ScrollIden, ScrollZone, ScrollZones, UiAction,
 };
 use crate::tui::view::PopupView;
 use arboard::Clipboard;
@@ -66,6 +66,9 @@ pub(in crate::tui::core) struct AppStateCore {
 	/// Time of when the current run started
 	pub running_tick_start: Option<i64>,
 
+	/// The streamed AI responses of the tasks in their AI stage
+	pub ai_streams: AiStreams,
+
 	// -- System & Event
 	pub mm: ModelManager,
 	pub last_app_event: LastAppEvent,
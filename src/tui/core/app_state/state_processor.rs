@@ -11,6 +11,8 @@ use std::time::Duration;
 
 const SCROLL_KEY_MAIN_VIEW: bool = true;
 const AUTO_SWITCH_OVERVIEW_US: i64 = 500_000;
+/// Max number of runs loaded for the runs nav (most recent first), so a long persisted history stays cheap to refresh.
+const MAX_DISPLAY_RUNS: i64 = 200;
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct ProcessAppStateOpts {
@@ -44,6 +46,12 @@ pub fn process_app_state(state: &mut AppState, opts: ProcessAppStateOpts) {
 		state.clear_popup();
 	}
 
+	// -- Apply the streamed AI response chunks (for the task view)
+	if let Some(ai_stream_event) = state.last_app_event().as_ai_stream_event().cloned() {
+		state.apply_ai_stream_event(&ai_stream_event);
+		state.trigger_redraw();
+	}
+
 	// -- Toggle show sys state
 	if let Some(key_event) = state.last_app_event().as_key_event()
 		&& key_event.code == KeyCode::Char('M')
@@ -407,7 +415,7 @@ fn refresh_runs(state: &mut AppState) {
 	let was_on_top_loop = state.is_selected_on_top_loop();
 	let was_on_top_run = state.is_selected_on_top_run();
 
-	let new_runs = RunBmc::list_for_display(state.mm(), None).unwrap_or_default();
+	let new_runs = RunBmc::list_for_display(state.mm(), Some(MAX_DISPLAY_RUNS)).unwrap_or_default();
 	let loop_groups = LoopBmc::list(state.mm(), None)
 		.unwrap_or_default()
 		.into_iter()
@@ -685,6 +693,26 @@ fn process_actions(state: &mut AppState) {
 				}
 				state.clear_action();
 			}
+			UiAction::OpenFileAtLine { path, line } => {
+				let spath = SPath::from(&path);
+				match crate::support::editor::open_file_at_line_auto(&spath, Some(line)) {
+					Ok(editor) => {
+						state.set_popup(PopupView {
+							content: format!("Opening file\n{path}:{line}\n(with {})", editor.program()),
+							mode: PopupMode::Timed(Duration::from_millis(2000)),
+							is_err: false,
+						});
+					}
+					Err(err) => {
+						state.set_popup(PopupView {
+							content: format!("Failed to open file\n{path}\n(Cause: {err})"),
+							mode: PopupMode::Timed(Duration::from_millis(3000)),
+							is_err: true,
+						});
+					}
+				}
+				state.clear_action();
+			}
 			UiAction::WorkConfirm(id) => {
 				state.core_mut().to_send_action = Some(AppActionEvent::WorkConfirm(id));
 				state.trigger_redraw();
@@ -47,6 +47,7 @@ pub enum ScrollDir {
 ///
 /// See `dev/spec-code/spec-code-tui.md` for the architectural rationale and flow.
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum AppActionEvent {
 	Quit,
 	Redo,
@@ -22,7 +22,10 @@ pub async fn start_tui(mm: ModelManager, executor_tx: ExecutorTx, args: CliArgs)
 	// Enable mouse capture
 	execute!(stdout(), EnableMouseCapture)?;
 
+	// The task view shows the AI responses live
+	get_hub().set_ai_stream_consumer(true);
 	let _ = exec_app(terminal, mm, executor_tx, args).await;
+	get_hub().set_ai_stream_consumer(false);
 
 	// -- Restoring Terminal
 	let _ = title_guard.restore();
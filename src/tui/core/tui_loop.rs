@@ -2,7 +2,7 @@ use super::app_event_handlers::handle_app_event;
 use super::event::{AppActionEvent, AppEvent, LastAppEvent};
 use crate::Result;
 use crate::exec::ExecutorTx;
-use crate::hub::HubEvent;
+use crate::hub::{AiStreamEvent, HubEvent};
 use crate::model::{EntityType, Id, ModelManager};
 use crate::support::time::now_micro;
 use crate::tui::core::app_state::{ProcessAppStateOpts, process_app_state};
@@ -107,6 +107,7 @@ struct Debouncer {
 	last_redraw_event: Option<AppEvent>,
 	ui_events: Vec<AppEvent>,
 	event_by_run_id: HashMap<Id, AppEvent>,
+	ai_stream_events: Vec<AiStreamEvent>,
 	tick_event: Option<AppEvent>,
 }
 
@@ -116,6 +117,7 @@ impl Debouncer {
 			last_redraw_event: None,
 			ui_events: Vec::new(),
 			event_by_run_id: HashMap::new(),
+			ai_stream_events: Vec::new(),
 			tick_event: None,
 		};
 		debouncer.process(first_event);
@@ -153,6 +155,16 @@ impl Debouncer {
 					self.last_redraw_event = Some(app_event);
 				}
 			}
+			// NOTE: All the AI stream chunks are kept (merged when consecutive for the same task)
+			AppEvent::Hub(HubEvent::AiStream(ai_stream_event)) => {
+				if !self
+					.ai_stream_events
+					.last_mut()
+					.is_some_and(|last| last.merge(&ai_stream_event))
+				{
+					self.ai_stream_events.push(ai_stream_event);
+				}
+			}
 			AppEvent::Hub(hub_event) => self.last_redraw_event = Some(AppEvent::Hub(hub_event)),
 			AppEvent::Tick(tick) => self.tick_event = Some(AppEvent::Tick(tick)),
 		}
@@ -161,6 +173,11 @@ impl Debouncer {
 	fn into_events(self) -> Vec<AppEvent> {
 		let mut events = self.ui_events;
 		events.extend(self.event_by_run_id.into_values());
+		events.extend(
+			self.ai_stream_events
+				.into_iter()
+				.map(|ai_stream_event| AppEvent::Hub(HubEvent::AiStream(ai_stream_event))),
+		);
 		// for now, append the last redraw (might not be needed)
 		if let Some(last_redraw_event) = self.last_redraw_event {
 			events.push(last_redraw_event);
@@ -179,6 +196,7 @@ impl Debouncer {
 /// - DoRedraw, will get ignored if other event in the list
 /// - HubEvent(Model(entity = Task)) for the same run get collapse two the latest one
 ///     - Will be added at the event of the list
+/// - HubEvent(AiStream) are all kept, with the consecutive chunks of the same task merged
 /// - HubEvent(other) will be ingored (or latest)
 /// -
 fn debounce_events(app_rx: AppRx, first_event: AppEvent) -> (AppRx, Vec<AppEvent>) {
@@ -0,0 +1,79 @@
+use crate::hub::{AiStreamEvent, AiStreamKind};
+use crate::model::Id;
+use std::collections::HashMap;
+
+/// The streamed AI responses of the tasks in their AI stage (by task id)
+#[derive(Debug, Default)]
+pub struct AiStreams {
+	by_task_id: HashMap<Id, AiStreamBuf>,
+}
+
+/// The content received so far of a streamed AI response
+#[derive(Debug, Default)]
+pub struct AiStreamBuf {
+	pub content: String,
+	pub reasoning: String,
+}
+
+impl AiStreams {
+	pub fn apply(&mut self, event: &AiStreamEvent) {
+		match &event.kind {
+			AiStreamKind::Start => {
+				self.by_task_id.insert(event.task_id, AiStreamBuf::default());
+			}
+			AiStreamKind::Content(chunk) => {
+				self.by_task_id.entry(event.task_id).or_default().content.push_str(chunk);
+			}
+			AiStreamKind::Reasoning(chunk) => {
+				self.by_task_id.entry(event.task_id).or_default().reasoning.push_str(chunk);
+			}
+			// NOTE: The task view then shows the recorded AI response state
+			AiStreamKind::End => {
+				self.by_task_id.remove(&event.task_id);
+			}
+		}
+	}
+
+	pub fn get(&self, task_id: Id) -> Option<&AiStreamBuf> {
+		self.by_task_id.get(&task_id)
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	#[test]
+	fn test_tui_core_types_ai_streams_apply() -> Result<()> {
+		// -- Setup & Fixtures
+		let event = |task_id: i64, kind: AiStreamKind| AiStreamEvent {
+			task_id: task_id.into(),
+			kind,
+		};
+		let mut ai_streams = AiStreams::default();
+
+		// -- Exec
+		ai_streams.apply(&event(1, AiStreamKind::Start));
+		let mut chunk = event(1, AiStreamKind::Content("Hello".into()));
+		assert!(chunk.merge(&event(1, AiStreamKind::Content(" world".into()))));
+		assert!(!chunk.merge(&event(1, AiStreamKind::Reasoning("thinking".into()))));
+		ai_streams.apply(&chunk);
+		ai_streams.apply(&event(1, AiStreamKind::Reasoning("thinking".into())));
+		ai_streams.apply(&event(2, AiStreamKind::Content("other".into())));
+		ai_streams.apply(&event(2, AiStreamKind::End));
+
+		// -- Check
+		let buf = ai_streams.get(1.into()).ok_or("Should have task 1 stream")?;
+		assert_eq!(buf.content, "Hello world");
+		assert_eq!(buf.reasoning, "thinking");
+		assert!(ai_streams.get(2.into()).is_none());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
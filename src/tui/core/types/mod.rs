@@ -1,5 +1,6 @@
 // region:    --- Modules
 
+mod ai_streams;
 mod group_dash;
 mod link_zone;
 mod mouse_evt;
@@ -13,6 +14,7 @@ mod run_tasks_info;
 mod scroll_zone;
 mod ui_action;
 
+pub use ai_streams::*;
 #[allow(unused_imports)]
 pub use group_dash::*;
 pub use link_zone::*;
@@ -43,4 +43,10 @@ pub enum UiAction {
 
 	// Open the file at the given path
 	OpenFile(String),
+
+	// Open the file at the given path and line (e.g., the agent file line of a Lua error)
+	OpenFileAtLine {
+		path: String,
+		line: usize,
+	},
 }
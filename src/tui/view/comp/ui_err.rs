@@ -1,8 +1,9 @@
 use crate::model::{ErrBmc, Id, ModelManager};
+use crate::script::ScriptLocation;
 use crate::tui::core::{LinkZones, UiAction};
 use crate::tui::style;
 use crate::tui::view::comp;
-use ratatui::style::Color;
+use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 
 #[allow(unused)]
@@ -70,6 +71,25 @@ pub fn ui_for_err_with_hover(
 				path_color,
 			);
 
+			// -- Open the agent file at the Lua error line
+			if let Some(location) = ScriptLocation::find_in_text(&content) {
+				let mut spans = vec![comp::new_marker("", Style::default()), Span::raw(" ")];
+				spans.extend(spans_prefix);
+				let span_idx = spans.len();
+				spans.push(Span::styled(format!("Open {location}"), style::STL_TXT_ACTION));
+				link_zones.push_link_zone(
+					0,
+					span_idx,
+					1,
+					UiAction::OpenFileAtLine {
+						path: location.file_path,
+						line: location.line,
+					},
+				);
+				out.push(Line::from(spans));
+				link_zones.inc_current_line_by(1);
+			}
+
 			// Separator line (no zones)
 			out.push(Line::default());
 			link_zones.inc_current_line_by(1);
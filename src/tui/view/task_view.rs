@@ -1,6 +1,6 @@
 use crate::model::{EndState, Log, LogBmc, ModelManager, PinBmc, Run, RunningState, Task, TaskBmc};
 use crate::support::text::truncate_with_ellipsis;
-use crate::tui::core::{LinkZones, ScrollIden, UiAction};
+use crate::tui::core::{AiStreamBuf, LinkZones, ScrollIden, UiAction};
 use crate::tui::view::support::RectExt as _;
 use crate::tui::view::{comp, support};
 use crate::tui::{AppState, style};
@@ -277,7 +277,14 @@ fn render_body(area: Rect, buf: &mut Buffer, state: &mut AppState, show_steps: b
 		link_zones.set_current_line(all_lines.len());
 		support::extend_lines(
 			&mut all_lines,
-			ui_for_ai(run, task, max_width, &mut link_zones, path_color),
+			ui_for_ai(
+				run,
+				task,
+				state.current_task_ai_stream(),
+				max_width,
+				&mut link_zones,
+				path_color,
+			),
 			true,
 		);
 	}
@@ -439,6 +446,7 @@ fn ui_for_input(
 fn ui_for_ai(
 	run: &Run,
 	task: &Task,
+	ai_stream: Option<&AiStreamBuf>,
 	max_width: u16,
 	link_zones: &mut LinkZones,
 	path_color: Option<Color>,
@@ -467,6 +475,21 @@ fn ui_for_ai(
 			marker_style_active,
 		),
 
+		// The response streamed so far (reasoning, then content)
+		RunningState::Running
+			if let Some(ai_stream) = ai_stream
+				&& !(ai_stream.content.is_empty() && ai_stream.reasoning.is_empty()) =>
+		{
+			let mut content = format!("➜ Receiving response from AI model {model_names}.\n");
+			if !ai_stream.reasoning.is_empty() {
+				content = format!("{content}\nReasoning:\n{}\n", ai_stream.reasoning.trim_end());
+			}
+			if !ai_stream.content.is_empty() {
+				content = format!("{content}\n{}", ai_stream.content.trim_end());
+			}
+			(Some(content), marker_style_active)
+		}
+
 		RunningState::Running => (
 			Some(format!("➜ Sending prompt to AI model {model_names}.")),
 			marker_style_active,
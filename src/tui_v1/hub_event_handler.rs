@@ -50,6 +50,7 @@ pub async fn handle_hub_event(
 			exit_tx.send(()).await?;
 		}
 		HubEvent::Model(_model_event) => (), // TODO: needs to decide what to do here
+		HubEvent::AiStream(_ai_stream_event) => (), // the final response is printed when received
 	}
 
 	Ok(())
@@ -2,6 +2,7 @@
 
 mod hub_event_handler;
 mod in_reader;
+mod ndjson_event_handler;
 mod printer;
 mod prompter;
 mod tui_elem;
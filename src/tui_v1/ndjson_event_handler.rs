@@ -0,0 +1,348 @@
+//! The NDJSON output of `aip run --output ndjson`
+//!
+//! Each hub event of interest is written as one JSON object per line on stdout,
+//! with a `type` property (`run_step`, `print`, `message`, `error`, `prompt`, `exec_end`).
+//!
+//! A `prompt` line (e.g., from `aip xelf setup`) expects its answer as one line on stdin.
+//!
+//! The run/task lifecycle is derived from the `RunStep` logs (see `RtStep`), which are
+//! looked up in the runtime db when their `ModelEvent` is received.
+//! The run `End` step has the After All value (`after_all`), and the `TaskEnd` steps have the task `output`.
+
+use crate::Result;
+use crate::exec::ExecStatusEvent;
+use crate::hub::HubEvent;
+use crate::model::{
+	EntityAction, EntityType, ErrBmc, Id, Log, LogBmc, LogKind, ModelEvent, ModelManager, RelIds, RunBmc, RunStep,
+	TaskBmc,
+};
+use crate::tui_v1::prompter::PromptParams;
+use crate::tui_v1::{ExitTx, PrintEvent};
+use serde_json::{Map, Value, json};
+use std::io::Write as _;
+use tokio::io::{AsyncBufReadExt as _, BufReader};
+
+pub async fn handle_hub_event_ndjson(event: HubEvent, mm: &ModelManager, exit_tx: &ExitTx) -> Result<()> {
+	if let Some(line) = hub_event_to_json(mm, &event)? {
+		write_line(line);
+	}
+
+	match event {
+		HubEvent::Prompt(params) => prompt_answer(params).await?,
+		HubEvent::Executor(ExecStatusEvent::EndExec) | HubEvent::Quit => exit_tx.send(()).await?,
+		_ => (),
+	}
+
+	Ok(())
+}
+
+/// The NDJSON line of a hub event (None if the event is not part of the output)
+fn hub_event_to_json(mm: &ModelManager, event: &HubEvent) -> Result<Option<Value>> {
+	let value = match event {
+		HubEvent::Message(msg) | HubEvent::InfoShort(msg) => Some(json!({"type": "message", "text": msg.as_ref()})),
+
+		HubEvent::Error { error } => Some(json!({"type": "error", "message": error.to_string()})),
+
+		HubEvent::Print(print_event) => match &**print_event {
+			PrintEvent::InfoShort(msg) => Some(json!({"type": "message", "text": msg})),
+			PrintEvent::GenericErrorMsg(msg) => Some(json!({"type": "error", "message": msg})),
+			PrintEvent::ApiKeyEnvMissing { model_iden, env_name } => Some(json!({
+				"type": "error",
+				"message": format!("Missing API key env '{env_name}' for model '{}'", model_iden.model_name),
+			})),
+			// The other print events are not part of a run
+			_ => None,
+		},
+
+		// NOTE: The Lua prints are written from their `AgentPrint` log (which has the run/task ids)
+		HubEvent::LuaPrint(_) => None,
+
+		HubEvent::Model(model_event) => model_event_to_json(mm, model_event)?,
+
+		// NOTE: The answer is read from stdin (see `prompt_answer`)
+		HubEvent::Prompt(params) => Some(json!({"type": "prompt", "message": params.message})),
+
+		HubEvent::Executor(ExecStatusEvent::EndExec) => Some(json!({"type": "exec_end"})),
+
+		HubEvent::Quit
+		| HubEvent::Executor(_)
+		| HubEvent::RtModelChange
+		| HubEvent::DoExecRedo
+		| HubEvent::AiStream(_) => None,
+	};
+
+	Ok(value)
+}
+
+// region:    --- Support
+
+/// Read the answer of a `prompt` line, as one line on stdin
+///
+/// NOTE: Unlike the interactive prompt, nothing is written to stdout (only the NDJSON lines are).
+async fn prompt_answer(params: PromptParams) -> Result<()> {
+	let mut input = String::new();
+	BufReader::new(tokio::io::stdin()).read_line(&mut input).await?;
+	params.one_shot_res.send(input).await?;
+	Ok(())
+}
+
+fn write_line(value: Value) {
+	let mut stdout = std::io::stdout().lock();
+	// NOTE: Nothing much to do if stdout is closed
+	let _ = writeln!(stdout, "{value}");
+	let _ = stdout.flush();
+}
+
+/// Only the created logs are of interest (run steps and agent prints)
+///
+/// NOTE: A log creation is published twice (by `LogBmc::create` and `base::create`),
+///       only the one with the relation ids is used.
+fn model_event_to_json(mm: &ModelManager, model_event: &ModelEvent) -> Result<Option<Value>> {
+	let ModelEvent {
+		entity: EntityType::Log,
+		action: EntityAction::Created,
+		id: Some(log_id),
+		rel_ids: RelIds { run_id: Some(_), .. },
+	} = *model_event
+	else {
+		return Ok(None);
+	};
+
+	let log = LogBmc::get(mm, log_id)?;
+
+	let value = match log.kind {
+		Some(LogKind::RunStep) => Some(run_step_to_json(mm, &log)?),
+		Some(LogKind::AgentPrint) => Some(json!({
+			"type": "print",
+			"run_id": log.run_id.as_i64(),
+			"task_id": log.task_id.map(|id| id.as_i64()),
+			"stage": log.stage.as_ref().map(|_| log.stage_as_str()),
+			"text": log.message,
+		})),
+		// NOTE: The other logs are also published as hub messages
+		_ => None,
+	};
+
+	Ok(value)
+}
+
+fn run_step_to_json(mm: &ModelManager, log: &Log) -> Result<Value> {
+	let mut obj = Map::new();
+	obj.insert("type".into(), "run_step".into());
+	obj.insert("step".into(), log.step_as_str().into());
+	obj.insert("ts".into(), log.ctime.as_i64().into());
+	obj.insert("run_id".into(), log.run_id.as_i64().into());
+
+	match (&log.step, log.task_id) {
+		(Some(RunStep::Start), _) => {
+			let run = RunBmc::get(mm, log.run_id)?;
+			obj.insert("agent_name".into(), run.agent_name.into());
+			obj.insert("agent_path".into(), run.agent_path.into());
+			obj.insert("model".into(), run.model.into());
+		}
+
+		(Some(RunStep::End), _) => {
+			let run = RunBmc::get(mm, log.run_id)?;
+			obj.insert(
+				"end_state".into(),
+				run.end_state.as_ref().map(|s| -> &'static str { s.into() }).into(),
+			);
+			obj.insert("total_cost".into(), run.total_cost.into());
+			obj.insert("total_task_ms".into(), run.total_task_ms.into());
+			obj.insert("after_all".into(), RunBmc::get_after_all(mm, &run)?);
+			obj.insert("error".into(), get_err_content(mm, run.end_err_id)?.into());
+		}
+
+		(Some(step), Some(task_id)) => {
+			let task = TaskBmc::get(mm, task_id)?;
+			obj.insert("task_id".into(), task_id.as_i64().into());
+			obj.insert("task_idx".into(), task.idx.into());
+
+			if matches!(step, RunStep::TaskEnd) {
+				obj.insert(
+					"end_state".into(),
+					task.end_state.as_ref().map(|s| -> &'static str { s.into() }).into(),
+				);
+				obj.insert("skip_reason".into(), task.end_skip_reason.clone().into());
+				obj.insert(
+					"model".into(),
+					task.model_ov.clone().or(task.model_upstream.clone()).into(),
+				);
+				obj.insert("cost".into(), task.cost.into());
+				obj.insert("tk_prompt_total".into(), task.tk_prompt_total.into());
+				obj.insert("tk_completion_total".into(), task.tk_completion_total.into());
+				obj.insert("output".into(), TaskBmc::get_output_value(mm, &task)?);
+				obj.insert("error".into(), get_err_content(mm, task.end_err_id)?.into());
+			}
+		}
+
+		_ => (),
+	}
+
+	Ok(Value::Object(obj))
+}
+
+fn get_err_content(mm: &ModelManager, err_id: Option<Id>) -> Result<Option<String>> {
+	let Some(err_id) = err_id else {
+		return Ok(None);
+	};
+	Ok(ErrBmc::get(mm, err_id)?.content)
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::agent::Agent;
+	use crate::model::{LogForCreate, RunForCreate};
+	use crate::run::{RunBaseOptions, run_agent};
+	use crate::runtime::Runtime;
+
+	#[tokio::test]
+	async fn test_ndjson_hub_event_to_json_types() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = ModelManager::new().await?;
+		let run_id = RunBmc::create(
+			&mm,
+			RunForCreate {
+				parent_id: None,
+				agent_name: Some("agent-01".to_string()),
+				agent_path: Some("path/agent-01.aip".to_string()),
+				has_task_stages: None,
+				has_prompt_parts: None,
+			},
+		)?;
+		let log_event = |log_id: Id| {
+			HubEvent::Model(ModelEvent {
+				entity: EntityType::Log,
+				action: EntityAction::Created,
+				id: Some(log_id),
+				rel_ids: RelIds {
+					run_id: Some(run_id),
+					..Default::default()
+				},
+			})
+		};
+		let step_log_id = LogBmc::create(
+			&mm,
+			LogForCreate {
+				run_id,
+				task_id: None,
+				kind: Some(LogKind::RunStep),
+				step: Some(RunStep::Start),
+				stage: None,
+				message: None,
+			},
+		)?;
+		let print_log_id = LogBmc::create(
+			&mm,
+			LogForCreate {
+				run_id,
+				task_id: None,
+				kind: Some(LogKind::AgentPrint),
+				step: None,
+				stage: None,
+				message: Some("Hello print".to_string()),
+			},
+		)?;
+		let (prompt_params, _prompt_rx) = PromptParams::new("Continue? ");
+		let events = [
+			(HubEvent::Message("Hello message".into()), "message"),
+			(HubEvent::InfoShort("Hello info".into()), "message"),
+			(
+				HubEvent::Error {
+					error: crate::Error::custom("Some error").into(),
+				},
+				"error",
+			),
+			(
+				HubEvent::Print(PrintEvent::GenericErrorMsg("Some print error".into()).into()),
+				"error",
+			),
+			(log_event(step_log_id), "run_step"),
+			(log_event(print_log_id), "print"),
+			(HubEvent::Prompt(prompt_params), "prompt"),
+			(HubEvent::Executor(ExecStatusEvent::EndExec), "exec_end"),
+		];
+
+		// -- Exec & Check
+		for (event, typ) in events {
+			let value = hub_event_to_json(&mm, &event)?.ok_or("Should have a json line")?;
+			assert_eq!(
+				value.get("type").and_then(|v| v.as_str()),
+				Some(typ),
+				"event: {event:?}"
+			);
+			match typ {
+				"run_step" => {
+					assert_eq!(value.get("run_id").and_then(|v| v.as_i64()), Some(run_id.as_i64()));
+					assert_eq!(value.get("agent_name").and_then(|v| v.as_str()), Some("agent-01"));
+				}
+				"print" => assert_eq!(value.get("text").and_then(|v| v.as_str()), Some("Hello print")),
+				"prompt" => assert_eq!(value.get("message").and_then(|v| v.as_str()), Some("Continue? ")),
+				_ => (),
+			}
+		}
+
+		// the events which are not part of the output
+		for event in [HubEvent::LuaPrint("lua print".into()), HubEvent::Quit, HubEvent::RtModelChange] {
+			assert!(hub_event_to_json(&mm, &event)?.is_none(), "event: {event:?}");
+		}
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_ndjson_run_end_after_all() -> Result<()> {
+		// -- Setup & Fixtures
+		let content = r#"
+# Output
+
+```lua
+return input .. "!"
+```
+
+# After All
+
+```lua
+return { count = #outputs, last = outputs[#outputs] }
+```
+"#;
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let agent = Agent::mock_from_content(content)?;
+		let inputs = Some(vec!["one".into(), "two".into()]);
+		run_agent(&runtime, None, agent, inputs, &RunBaseOptions::default(), true).await?;
+		let mm = runtime.mm();
+		let run = RunBmc::list(mm, None)?.pop().ok_or("Should have one run")?;
+		let end_log = LogBmc::list_for_run_only(mm, run.id)?
+			.into_iter()
+			.find(|log| matches!(log.step, Some(RunStep::End)))
+			.ok_or("Should have the run end step log")?;
+		let event = HubEvent::Model(ModelEvent {
+			entity: EntityType::Log,
+			action: EntityAction::Created,
+			id: Some(end_log.id),
+			rel_ids: RelIds {
+				run_id: Some(run.id),
+				..Default::default()
+			},
+		});
+
+		// -- Exec
+		let value = hub_event_to_json(mm, &event)?.ok_or("Should have a json line")?;
+
+		// -- Check
+		assert_eq!(value.get("step").and_then(|v| v.as_str()), Some("End"));
+		assert_eq!(value.get("end_state").and_then(|v| v.as_str()), Some("Ok"));
+		assert_eq!(value.get("after_all"), Some(&json!({ "count": 2, "last": "two!" })));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,4 +1,6 @@
 use crate::dir_context::PackDir;
+use crate::exec::RunReport;
+use crate::model::Run;
 use derive_more::From;
 use genai::ModelIden;
 use std::collections::HashSet;
@@ -22,4 +24,10 @@ pub enum PrintEvent {
 	},
 
 	GenericErrorMsg(String),
+
+	/// The past runs (aip history)
+	RunHistory(Vec<Run>),
+
+	/// One past run (aip show)
+	RunReport(Box<RunReport>),
 }
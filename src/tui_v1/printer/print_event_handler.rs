@@ -29,5 +29,15 @@ pub fn handle_print(print_event: Arc<PrintEvent>, interactive: bool) {
 
 		// Print Generic error
 		PrintEvent::GenericErrorMsg(msg) => print_error_generic(msg, interactive),
+
+		// -- Print run history (aip history)
+		PrintEvent::RunHistory(runs) => {
+			let _ = printers::print_run_history(runs);
+		}
+
+		// -- Print one run (aip show)
+		PrintEvent::RunReport(report) => {
+			let _ = printers::print_run_report(report);
+		}
 	}
 }
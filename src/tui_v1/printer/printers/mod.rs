@@ -6,6 +6,7 @@ mod print_error_generic;
 mod print_error_key_env_missing;
 mod print_info;
 mod print_pack_list;
+mod print_run_history;
 
 #[allow(unused)]
 pub use common::*;
@@ -14,5 +15,6 @@ pub use print_error_generic::*;
 pub use print_error_key_env_missing::*;
 pub use print_info::*;
 pub use print_pack_list::*;
+pub use print_run_history::*;
 
 // endregion: --- Modules
@@ -0,0 +1,278 @@
+use crate::Result;
+use crate::exec::RunReport;
+use crate::model::{EndState, ErrRec, Pin, Run, Task};
+use crate::support::text::{format_datetime_local, format_duration_us, format_f64, truncate_with_ellipsis};
+use crossterm::execute;
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use std::io::{Write as _, stdout};
+
+/// Max chars of the input/output content when not `--full`
+const CONTENT_MAX_CHARS: usize = 300;
+
+/// Prints the run history table (aip history).
+pub fn print_run_history(runs: &[Run]) -> Result<()> {
+	let mut stdout = stdout();
+
+	if runs.is_empty() {
+		writeln!(stdout, "\nNo runs in the run history.\n")?;
+		return Ok(());
+	}
+
+	let id_width = runs.iter().map(|r| r.id.to_string().len()).max().unwrap_or(2).max(2);
+
+	writeln!(stdout, "\nRun history (most recent first):\n")?;
+	execute!(
+		stdout,
+		SetAttribute(Attribute::Bold),
+		Print(format!(
+			"{:>id_width$}  {:<19}  {:<7}  {:>9}  {:>10}  {:>4}  {}",
+			"ID", "Start", "State", "Cost", "Duration", "Redo", "Agent (model)"
+		)),
+		SetAttribute(Attribute::Reset),
+		Print("\n")
+	)?;
+
+	for run in runs {
+		let start = fmt_time(run.start.map(|t| t.as_i64()));
+		let state = fmt_end_state(run.end_state, run.end.is_some());
+		let cost = fmt_cost(run.total_cost);
+		let duration = run.duration_us().map(format_duration_us).unwrap_or_else(|| "-".to_string());
+		let redo = run.flow_redo_count.map(|c| c.to_string()).unwrap_or_else(|| "-".to_string());
+		let agent = run.agent_name.as_deref().unwrap_or("-");
+		let model = run.model.as_deref().map(|m| format!(" ({m})")).unwrap_or_default();
+
+		execute!(
+			stdout,
+			Print(format!("{:>id_width$}  {start:<19}  ", run.id)),
+			SetForegroundColor(end_state_color(run.end_state)),
+			Print(format!("{state:<7}")),
+			ResetColor,
+			Print(format!("  {cost:>9}  {duration:>10}  {redo:>4}  {agent}{model}\n")),
+		)?;
+	}
+
+	writeln!(stdout)?;
+	stdout.flush()?;
+
+	Ok(())
+}
+
+/// Prints one run with its tasks, inputs/outputs, errors, and pins (aip show).
+pub fn print_run_report(report: &RunReport) -> Result<()> {
+	let mut stdout = stdout();
+	let run = &report.run;
+
+	// -- Run
+	execute!(
+		stdout,
+		Print("\n"),
+		SetAttribute(Attribute::Bold),
+		Print(format!("Run {}", run.id)),
+		SetAttribute(Attribute::Reset),
+		Print(format!("  (uid: {})\n\n", run.uid)),
+	)?;
+	print_field("Agent", run.agent_name.as_deref().unwrap_or("-"))?;
+	print_field("Path", run.agent_path.as_deref().unwrap_or("-"))?;
+	if let Some(label) = run.label.as_deref() {
+		print_field("Label", label)?;
+	}
+	if let Some(replay_of_id) = run.replay_of_id {
+		print_field("Replay of", &format!("run {replay_of_id}"))?;
+	}
+	if let Some(resume_of_id) = run.resume_of_id {
+		print_field("Resume of", &format!("run {resume_of_id}"))?;
+	}
+	print_field("Model", run.model.as_deref().unwrap_or("-"))?;
+	print_field("State", &fmt_end_state(run.end_state, run.end.is_some()))?;
+	if let Some(skip_reason) = run.end_skip_reason.as_deref() {
+		print_field("Skip", skip_reason)?;
+	}
+	print_field("Start", &fmt_time(run.start.map(|t| t.as_i64())))?;
+	print_field(
+		"Duration",
+		&run.duration_us().map(format_duration_us).unwrap_or_else(|| "-".to_string()),
+	)?;
+	print_field("Cost", &fmt_cost(run.total_cost))?;
+	print_field(
+		"Redo",
+		&run.flow_redo_count.map(|c| c.to_string()).unwrap_or_else(|| "-".to_string()),
+	)?;
+
+	// -- Run pins
+	if !report.pins.is_empty() {
+		print_section(&format!("Pins ({})", report.pins.len()))?;
+		for pin in report.pins.iter() {
+			print_pin(pin, "  ")?;
+		}
+	}
+
+	// -- Tasks
+	print_section(&format!("Tasks ({})", report.tasks.len()))?;
+	for task_report in report.tasks.iter() {
+		let task = &task_report.task;
+		print_task_heading(task)?;
+		print_inout("Input", task_report.input.as_deref(), report.full)?;
+		print_inout("Output", task_report.output.as_deref(), report.full)?;
+		for pin in task_report.pins.iter() {
+			print_pin(pin, "    ")?;
+		}
+	}
+
+	// -- Errors
+	if !report.errs.is_empty() {
+		print_section(&format!("Errors ({})", report.errs.len()))?;
+		for err in report.errs.iter() {
+			print_err(err, report)?;
+		}
+	}
+
+	writeln!(stdout)?;
+	stdout.flush()?;
+
+	Ok(())
+}
+
+// region:    --- Support
+
+fn print_section(title: &str) -> Result<()> {
+	let mut stdout = stdout();
+	execute!(
+		stdout,
+		Print("\n"),
+		SetAttribute(Attribute::Bold),
+		Print(title),
+		SetAttribute(Attribute::Reset),
+		Print("\n")
+	)?;
+	Ok(())
+}
+
+fn print_field(label: &str, value: &str) -> Result<()> {
+	let mut stdout = stdout();
+	execute!(
+		stdout,
+		SetAttribute(Attribute::Dim),
+		Print(format!("  {label:<9} ")),
+		SetAttribute(Attribute::Reset),
+		Print(format!("{value}\n"))
+	)?;
+	Ok(())
+}
+
+fn print_task_heading(task: &Task) -> Result<()> {
+	let mut stdout = stdout();
+	let idx = task.idx.map(|idx| idx.to_string()).unwrap_or_else(|| "-".to_string());
+	let state = fmt_end_state(task.end_state, task.end.is_some());
+	let cost = fmt_cost(task.cost);
+	let duration = match (task.start, task.end) {
+		(Some(start), Some(end)) => format_duration_us(end.as_i64() - start.as_i64()),
+		_ => "-".to_string(),
+	};
+	let label = task.label.as_deref().map(|l| format!("  {l}")).unwrap_or_default();
+
+	execute!(
+		stdout,
+		Print(format!("  #{idx:<4} ")),
+		SetForegroundColor(end_state_color(task.end_state)),
+		Print(format!("{state:<7}")),
+		ResetColor,
+		Print(format!("  {cost:>9}  {duration:>10}{label}\n")),
+	)?;
+	if let Some(skip_reason) = task.end_skip_reason.as_deref() {
+		execute!(stdout, Print(format!("        Skip: {skip_reason}\n")))?;
+	}
+
+	Ok(())
+}
+
+fn print_inout(label: &str, content: Option<&str>, full: bool) -> Result<()> {
+	let Some(content) = content else {
+		return Ok(());
+	};
+
+	let content = if full {
+		content.into()
+	} else {
+		truncate_with_ellipsis(content, CONTENT_MAX_CHARS, "...")
+	};
+
+	let mut stdout = stdout();
+	execute!(
+		stdout,
+		SetAttribute(Attribute::Dim),
+		Print(format!("        {label}: ")),
+		SetAttribute(Attribute::Reset),
+		Print(format!("{}\n", indent_continuation_lines(&content, "          "))),
+	)?;
+	Ok(())
+}
+
+fn print_pin(pin: &Pin, indent: &str) -> Result<()> {
+	let mut stdout = stdout();
+	let iden = pin.iden.as_deref().unwrap_or("-");
+	let content = pin.content.as_deref().unwrap_or_default();
+	execute!(
+		stdout,
+		SetForegroundColor(Color::Blue),
+		Print(format!("{indent}Pin {iden}: ")),
+		ResetColor,
+		Print(format!("{content}\n")),
+	)?;
+	Ok(())
+}
+
+fn print_err(err: &ErrRec, report: &RunReport) -> Result<()> {
+	let mut stdout = stdout();
+	let task_idx = err
+		.task_id
+		.and_then(|task_id| report.tasks.iter().find(|t| t.task.id == task_id).and_then(|t| t.task.idx));
+	let location = match (err.stage, task_idx) {
+		(Some(stage), Some(idx)) => format!("[{stage} - task #{idx}] "),
+		(Some(stage), None) => format!("[{stage}] "),
+		(None, Some(idx)) => format!("[task #{idx}] "),
+		(None, None) => String::new(),
+	};
+	let content = err.content.as_deref().unwrap_or_default();
+	execute!(
+		stdout,
+		SetForegroundColor(Color::Red),
+		Print(format!("  {location}")),
+		ResetColor,
+		Print(format!("{}\n", indent_continuation_lines(content, "    "))),
+	)?;
+	Ok(())
+}
+
+fn fmt_time(epoch_us: Option<i64>) -> String {
+	epoch_us
+		.and_then(|us| format_datetime_local(us).ok())
+		.unwrap_or_else(|| "-".to_string())
+}
+
+fn fmt_cost(cost: Option<f64>) -> String {
+	cost.map(|c| format!("${}", format_f64(c))).unwrap_or_else(|| "-".to_string())
+}
+
+/// NOTE: A run/task without end (e.g., process killed) is shown as "Unended"
+fn fmt_end_state(end_state: Option<EndState>, has_end: bool) -> String {
+	match (end_state, has_end) {
+		(Some(end_state), _) => end_state.to_string(),
+		(None, true) => "Ended".to_string(),
+		(None, false) => "Unended".to_string(),
+	}
+}
+
+fn end_state_color(end_state: Option<EndState>) -> Color {
+	match end_state {
+		Some(EndState::Ok) => Color::Green,
+		Some(EndState::Err) => Color::Red,
+		Some(EndState::Cancel) | Some(EndState::Skip) => Color::Yellow,
+		None => Color::Reset,
+	}
+}
+
+fn indent_continuation_lines(content: &str, indent: &str) -> String {
+	content.trim_end().replace('\n', &format!("\n{indent}"))
+}
+
+// endregion: --- Support
@@ -3,9 +3,11 @@ use crate::event::{Tx, new_channel};
 use crate::exec::cli::CliArgs;
 use crate::exec::{ExecActionEvent, ExecutorTx};
 use crate::hub::{HubEvent, get_hub};
+use crate::model::ModelManager;
 use crate::term::{TermTitleGuard, safer_println};
 use crate::tui_v1::hub_event_handler::handle_hub_event;
 use crate::tui_v1::in_reader::InReader;
+use crate::tui_v1::ndjson_event_handler::handle_hub_event_ndjson;
 use crossterm::cursor::MoveUp;
 use crossterm::event::{KeyCode, KeyEventKind, KeyModifiers};
 use crossterm::execute;
@@ -16,12 +18,24 @@ use derive_more::{Deref, From};
 #[derive(Debug)]
 pub struct TuiAppV1 {
 	executor_tx: ExecutorTx,
+	/// When set, the hub events are written as NDJSON (see `--output ndjson`)
+	ndjson_mm: Option<ModelManager>,
 }
 
 /// Constructor
 impl TuiAppV1 {
 	pub fn new(executor_tx: ExecutorTx) -> Self {
-		Self { executor_tx }
+		Self {
+			executor_tx,
+			ndjson_mm: None,
+		}
+	}
+
+	/// Write the hub events as NDJSON on stdout.
+	/// The ModelManager is needed to look up the run and task states.
+	pub fn with_ndjson(mut self, mm: ModelManager) -> Self {
+		self.ndjson_mm = Some(mm);
+		self
 	}
 }
 
@@ -139,6 +153,7 @@ impl TuiAppV1 {
 	/// For now, we just print most of tose event content.
 	fn run_handle_hub_event(&self, exit_tx: ExitTx, interactive: bool) -> Result<()> {
 		let exec_tx = self.executor_tx();
+		let ndjson_mm = self.ndjson_mm.clone();
 		let hub_rx = get_hub().take_rx()?;
 
 		tokio::spawn(async move {
@@ -146,7 +161,11 @@ impl TuiAppV1 {
 				let evt_res = hub_rx.recv().await;
 				match evt_res {
 					Ok(event) => {
-						if let Err(err) = handle_hub_event(event, &exec_tx, &exit_tx, interactive).await {
+						let res = match &ndjson_mm {
+							Some(mm) => handle_hub_event_ndjson(event, mm, &exit_tx).await,
+							None => handle_hub_event(event, &exec_tx, &exit_tx, interactive).await,
+						};
+						if let Err(err) = res {
 							println!("Tui ERROR while handling handle_hub_event. Cause {err}")
 						}
 					}
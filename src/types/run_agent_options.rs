@@ -12,6 +12,8 @@ pub struct RunAgentOptions {
 	pub inputs: Option<Vec<serde_json::Value>>,
 	pub options: Option<AgentOptions>,
 	pub agent_base_dir: Option<SPath>,
+	/// The run params of the called agent (`CTX.PARAMS`)
+	pub params: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
 impl FromLua for RunAgentOptions {
@@ -58,10 +60,26 @@ impl FromLua for RunAgentOptions {
 				// -- agent_base_dir
 				let agent_base_dir = table.x_get_string("agent_base_dir").map(SPath::new);
 
+				// -- params
+				// NOTE: An empty Lua table (e.g., `params = {}`) is converted as an empty array
+				let params = match table.x_get_value("params").map(lua_value_to_serde_value).transpose()? {
+					Some(serde_json::Value::Object(params)) => Some(params),
+					Some(serde_json::Value::Array(arr)) if arr.is_empty() => Some(serde_json::Map::new()),
+					Some(_) => {
+						return Err(mlua::Error::FromLuaConversionError {
+							from: "Table",
+							to: "RunAgentOptions".to_string(),
+							message: Some("The 'params' field must be a Lua table with key/values".into()),
+						});
+					}
+					None => None,
+				};
+
 				Ok(Self {
 					inputs,
 					options,
 					agent_base_dir,
+					params,
 				})
 			}
 			other => Err(mlua::Error::FromLuaConversionError {